@@ -0,0 +1,848 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Interactive command line game of SpotIt! in `cardgame` crate.
+//!
+//! The `play` command of the binary asks for a game mode and a deck order, draws the face up cards in the terminal and reads the symbols typed by the player.
+//! Symbol names are matched loosely: case, spaces and dashes do not matter, and a three letter abbreviation, a unique prefix or a unique fuzzy match of a symbol on the cards is enough.
+//!
+//! A player stuck on two cards can type "hint" for hints which narrow the search step by step, each costing a point. See `game::hint`.
+//! Given a profile file, solo and practice record how long every symbol took to spot and the misses, and practice goes over the symbols the player finds hardest. See `profile`.
+//!
+//! Lines are read through a `LineSource`, so a line editor with tab completion can be plugged in by the binary, and tests can drive a whole game from a script.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt! Rules`]: https://www.ultraboardgames.com/spot-it/game-rules.php
+
+use crate::difficulty::Difficulty;
+use crate::game::clock::SystemClock;
+use crate::game::hint::{Hints, HINT_COST};
+use crate::game::practice::Practice;
+use crate::game::solo::Solo;
+use crate::game::tower::Tower;
+use crate::game::triplet::Triplet;
+use crate::game::well::Well;
+use crate::game::{common_symbol, Game, GameMode, Phase, Table, Verdict};
+use crate::profile::{Profile, Profiles};
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use crate::terminal::{abbreviation, TermCard, TermStyle};
+use crate::{Deck, SpotItDeck};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+
+/// This enum defines the game modes which can be played alone at the terminal.
+#[derive(Debug, PartialEq, Clone, Copy, Eq, strum_macros::EnumIter)]
+pub enum Mode {
+    /// This is the classic game: name the symbol shared by the two face up cards.
+    Classic,
+    /// This is the classic game against the clock.
+    Solo,
+    /// This is "The Tower": name the symbol shared by your card and the draw pile.
+    Tower,
+    /// This is "The Well": empty your pile onto the well.
+    Well,
+    /// This is "Triplet": find three cards sharing a symbol in a layout of nine.
+    Triplet,
+    /// This is practice of the symbols the player finds hardest.
+    Practice,
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for Mode {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        choose(s, &Mode::iter().collect::<Vec<_>>())
+            .ok_or_else(|| format!("Unknown game mode: {}.", s))
+    }
+}
+
+/// This enum defines the order of the deck.
+#[derive(Debug, PartialEq, Clone, Copy, Eq, strum_macros::EnumIter)]
+pub enum DeckOrder {
+    /// This is the order the deck is generated in, the same in every game.
+    Ordered,
+    /// This is a random order.
+    Shuffled,
+}
+
+impl fmt::Display for DeckOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for DeckOrder {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        choose(s, &DeckOrder::iter().collect::<Vec<_>>())
+            .ok_or_else(|| format!("Unknown deck order: {}.", s))
+    }
+}
+
+/// This function picks an option by its number in a menu, starting from 1, or by its name.
+fn choose<T: Copy + fmt::Display>(input: &str, options: &[T]) -> Option<T> {
+    let input = input.trim();
+    if let Ok(number) = input.parse::<usize>() {
+        return number.checked_sub(1).and_then(|i| options.get(i)).copied();
+    }
+    options
+        .iter()
+        .find(|o| o.to_string().eq_ignore_ascii_case(input))
+        .copied()
+}
+
+/// This struct defines the options of a game at the terminal. Options left out are asked for.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Options {
+    /// This is the game mode.
+    pub mode: Option<Mode>,
+    /// This is the order of the deck.
+    pub order: Option<DeckOrder>,
+    /// This is the seed of the shuffle. A random one is used if left out.
+    pub seed: Option<u64>,
+    /// This is the name of the player.
+    pub name: String,
+    /// This is true if answers come from a script rather than a person.
+    pub script: bool,
+    /// This is how cards are drawn.
+    pub style: TermStyle,
+    /// This is the difficulty, which sets the deck and the hints.
+    pub difficulty: Difficulty,
+    /// This is the file of learning profiles, which solo and practice record into. See `profile::Profiles`.
+    pub profile: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            mode: None,
+            order: None,
+            seed: None,
+            name: "Player".to_string(),
+            script: false,
+            style: TermStyle::detect(),
+            difficulty: Difficulty::default(),
+            profile: None,
+        }
+    }
+}
+
+/// This function creates the deck of the difficulty in the order asked for. A shuffle with the same seed always gives the same order.
+pub fn deck(difficulty: Difficulty, order: DeckOrder, seed: Option<u64>) -> SpotItDeck {
+    let mut deck = difficulty.deck();
+    match (order, seed) {
+        (DeckOrder::Ordered, _) => {}
+        (DeckOrder::Shuffled, Some(seed)) => deck.cards.shuffle(&mut StdRng::seed_from_u64(seed)),
+        (DeckOrder::Shuffled, None) => deck.shuffle(),
+    }
+    deck
+}
+
+/// This function normalizes a name for loose matching, i.e. lower case without spaces, dashes or underscores.
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// This function returns true if all the characters of the needle appear in the haystack in order.
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+/// This function finds the symbol the player means among the candidates, e.g. the symbols on the face up cards.
+/// The input may be the name, the three letter abbreviation, a unique prefix of the name, or a unique fuzzy match, i.e. letters of the name in order.
+/// It returns an error listing the candidates if the input matches none or several of them.
+pub fn match_symbol(input: &str, candidates: &[SpotItSymbol]) -> Result<SpotItSymbol, String> {
+    let input = normalize(input);
+    if input.is_empty() {
+        return Err("Type the name of a symbol.".to_string());
+    }
+    let names: Vec<(SpotItSymbol, String)> = candidates
+        .iter()
+        .map(|s| (*s, normalize(&s.to_string())))
+        .collect();
+    let tests: [&dyn Fn(&str) -> bool; 3] = [
+        &|name: &str| name == input,
+        &|name: &str| name.starts_with(&input),
+        &|name: &str| is_subsequence(&input, name),
+    ];
+    for (i, test) in tests.iter().enumerate() {
+        let mut found: Vec<SpotItSymbol> = names
+            .iter()
+            .filter(|(_, name)| test(name))
+            .map(|(s, _)| *s)
+            .collect();
+        if i == 0 {
+            found.extend(
+                candidates
+                    .iter()
+                    .filter(|s| normalize(&abbreviation(**s)) == input),
+            );
+        }
+        found.sort();
+        found.dedup();
+        match found.len() {
+            0 => continue,
+            1 => return Ok(found[0]),
+            _ => {
+                let names: Vec<String> = found.iter().map(|s| s.to_string()).collect();
+                return Err(format!("Did you mean {}?", names.join(", ")));
+            }
+        }
+    }
+    Err(format!("Unknown symbol: {}.", input))
+}
+
+/// This function returns the names of the candidates which start with what has been typed so far, for tab completion.
+pub fn complete(prefix: &str, candidates: &[SpotItSymbol]) -> Vec<String> {
+    let prefix = normalize(prefix);
+    let mut names: Vec<String> = candidates
+        .iter()
+        .map(|s| s.to_string())
+        .filter(|name| normalize(name).starts_with(&prefix))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// This trait defines where the lines typed by the player come from.
+pub trait LineSource {
+    /// This function shows the prompt and reads a line, or returns None at the end of the input.
+    /// The candidates are the symbols which make sense at this point, for sources which complete names.
+    fn read_line(&mut self, prompt: &str, candidates: &[SpotItSymbol]) -> Option<String>;
+}
+
+/// This struct defines a script of lines read from any reader, e.g. the standard input. Prompts are not shown.
+#[derive(Debug)]
+pub struct Script<R: BufRead> {
+    reader: R,
+}
+
+impl<R: BufRead> Script<R> {
+    /// This function creates a script reading from the reader.
+    pub fn new(reader: R) -> Self {
+        Script { reader }
+    }
+}
+
+impl<R: BufRead> LineSource for Script<R> {
+    fn read_line(&mut self, _prompt: &str, _candidates: &[SpotItSymbol]) -> Option<String> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line.trim_end_matches(['\r', '\n']).to_string()),
+        }
+    }
+}
+
+/// This enum defines what the player typed during a game.
+enum Command {
+    Answer(String),
+    Hint,
+    Quit,
+}
+
+/// This struct defines a game at the terminal, reading from a line source and writing to an output.
+pub struct Session<'a, S: LineSource, W: Write> {
+    options: &'a Options,
+    input: &'a mut S,
+    out: &'a mut W,
+    rng: StdRng,
+}
+
+impl<'a, S: LineSource, W: Write> Session<'a, S, W> {
+    /// This function creates a session with the options.
+    pub fn new(options: &'a Options, input: &'a mut S, out: &'a mut W) -> Self {
+        Session {
+            options,
+            input,
+            out,
+            rng: match options.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            },
+        }
+    }
+
+    /// This function asks for what is missing from the options, then plays a game until it finishes, the player quits or the input ends.
+    pub fn run(&mut self) -> io::Result<()> {
+        let mode = match self.options.mode {
+            Some(mode) => mode,
+            None => match self.menu("game mode")? {
+                Some(mode) => mode,
+                None => return Ok(()),
+            },
+        };
+        let order = match self.options.order {
+            Some(order) => order,
+            None => match self.menu("deck order")? {
+                Some(order) => order,
+                None => return Ok(()),
+            },
+        };
+        let difficulty = self.options.difficulty;
+        let deck = deck(difficulty, order, self.options.seed);
+        let names = [self.options.name.as_str()];
+        // The usual deck is not named, as before there were levels
+        let level = match difficulty {
+            Difficulty::Standard => String::new(),
+            level => format!("{} ", level),
+        };
+        writeln!(self.out, "Playing {} ({} {}deck).", mode, order, level)?;
+        writeln!(
+            self.out,
+            "Type the matching symbol, \"hint\" for a hint ({} point), \"scores\" to see the scores or \"quit\" to stop.",
+            HINT_COST
+        )?;
+        match mode {
+            Mode::Classic => self.classic(Game::new(deck, &names).map_err(invalid)?),
+            Mode::Solo => {
+                self.solo(Solo::new(deck, names[0], SystemClock::new()).map_err(invalid)?)
+            }
+            Mode::Tower => self.tower(Tower::new(deck, &names).map_err(invalid)?),
+            Mode::Well => self.well(Well::new(deck, &names).map_err(invalid)?),
+            Mode::Triplet => self.triplet(Triplet::new(deck, &names).map_err(invalid)?),
+            Mode::Practice => {
+                let profile = self.load_profiles()?.player(names[0]);
+                let seed = self.rng.gen();
+                let practice = Practice::new(deck, names[0], profile, SystemClock::new(), seed);
+                self.practice(practice.map_err(invalid)?)
+            }
+        }
+    }
+
+    /// This function reads the profiles from the profile file, or returns none if there is no file.
+    fn load_profiles(&self) -> io::Result<Profiles> {
+        match &self.options.profile {
+            Some(path) => Profiles::load(path).map_err(invalid),
+            None => Ok(Profiles::default()),
+        }
+    }
+
+    /// This function keeps the profile of the player in the profile file, if there is one.
+    fn save_profile(&mut self, profile: Profile) -> io::Result<()> {
+        let Some(path) = &self.options.profile else {
+            return Ok(());
+        };
+        let mut profiles = self.load_profiles()?;
+        profiles.set_player(&self.options.name, profile);
+        profiles.save(path).map_err(invalid)?;
+        writeln!(self.out, "Profile saved to {}.", path.display())
+    }
+
+    /// This function prints the symbols the player is slowest at, with their mean time to spot and misses.
+    fn weakest(&mut self, profile: &Profile) -> io::Result<()> {
+        let weakest: Vec<String> = profile
+            .weakest()
+            .into_iter()
+            .take(3)
+            .map(|symbol| {
+                let stats = profile.stats(symbol);
+                let time = match stats.mean_time() {
+                    Some(time) => format!("{:.1}s", time.as_secs_f64()),
+                    None => "not spotted".to_string(),
+                };
+                match stats.misses {
+                    0 => format!("{} ({})", symbol, time),
+                    1 => format!("{} ({}, 1 miss)", symbol, time),
+                    misses => format!("{} ({}, {} misses)", symbol, time, misses),
+                }
+            })
+            .collect();
+        match weakest.is_empty() {
+            true => Ok(()),
+            false => writeln!(self.out, "Weakest symbols: {}", weakest.join(", ")),
+        }
+    }
+
+    /// This function asks the player to pick an option from a menu until they pick a valid one.
+    /// It returns None if the input ends first.
+    fn menu<T>(&mut self, what: &str) -> io::Result<Option<T>>
+    where
+        T: Copy + fmt::Display + IntoEnumIterator,
+    {
+        let options: Vec<T> = T::iter().collect();
+        loop {
+            writeln!(self.out, "Choose a {}:", what)?;
+            for (i, option) in options.iter().enumerate() {
+                writeln!(self.out, "  {}) {}", i + 1, option)?;
+            }
+            let Some(line) = self.input.read_line("> ", &[]) else {
+                return Ok(None);
+            };
+            match choose(&line, &options) {
+                Some(option) => return Ok(Some(option)),
+                None => writeln!(self.out, "There is no {} called {}.", what, line.trim())?,
+            }
+        }
+    }
+
+    /// This function draws cards with a caption above each of them.
+    fn show(&mut self, cards: &[(String, &SpotItCard)]) -> io::Result<()> {
+        for (caption, card) in cards {
+            writeln!(self.out, "{}", caption)?;
+            writeln!(self.out, "{}", card.render(&self.options.style))?;
+        }
+        Ok(())
+    }
+
+    /// This function reads a command, answering "scores" itself.
+    fn command(&mut self, table: &Table, candidates: &[SpotItSymbol]) -> io::Result<Command> {
+        loop {
+            let Some(line) = self.input.read_line("> ", candidates) else {
+                return Ok(Command::Quit);
+            };
+            match line.trim().to_lowercase().as_str() {
+                "" => continue,
+                "quit" | "exit" | "q" => return Ok(Command::Quit),
+                "hint" | "h" | "?" => return Ok(Command::Hint),
+                "scores" => {
+                    self.scores(table)?;
+                    continue;
+                }
+                _ => return Ok(Command::Answer(line)),
+            }
+        }
+    }
+
+    /// This function reads the symbol the player names, and returns None if they quit.
+    /// Hints asked for are given from the hints for the two cards, and taken by the first player at the table.
+    fn answer(
+        &mut self,
+        table: &mut Table,
+        candidates: &[SpotItSymbol],
+        mut hints: Option<&mut Hints>,
+    ) -> io::Result<Option<SpotItSymbol>> {
+        loop {
+            let line = match self.command(table, candidates)? {
+                Command::Answer(line) => line,
+                Command::Hint => {
+                    match hints.as_mut() {
+                        Some(hints) => {
+                            writeln!(self.out, "Hint: {}", hints.next_hint())?;
+                            table.take_hint(0).map_err(invalid)?;
+                        }
+                        None => writeln!(self.out, "There is no hint for these cards.")?,
+                    }
+                    continue;
+                }
+                Command::Quit => return Ok(None),
+            };
+            match match_symbol(&line, candidates) {
+                Ok(symbol) => return Ok(Some(symbol)),
+                Err(message) => writeln!(self.out, "{}", message)?,
+            }
+        }
+    }
+
+    /// This function returns the hints for the two cards, i.e. those given so far if they are for the same cards, or new ones.
+    fn hints_for(
+        &mut self,
+        hints: Option<Hints>,
+        card: &SpotItCard,
+        other: &SpotItCard,
+    ) -> Option<Hints> {
+        let fraction = self.options.difficulty.hint_fraction();
+        hints.filter(|h| h.is_for(card, other)).or_else(|| {
+            Hints::new(card, other, &mut self.rng)
+                .ok()
+                .map(|h| h.with_fraction(fraction))
+        })
+    }
+
+    /// This function tells the player the verdict on their claim.
+    fn verdict(&mut self, verdict: Verdict) -> io::Result<()> {
+        match verdict {
+            Verdict::Correct(claim) => writeln!(self.out, "Correct: {}!", claim.symbol),
+            Verdict::Wrong(claim) => {
+                writeln!(self.out, "Wrong: {} is not on both cards.", claim.symbol)
+            }
+            Verdict::Late(claim) => writeln!(self.out, "Too late for {}.", claim.symbol),
+        }
+    }
+
+    /// This function prints the score of every player.
+    fn scores(&mut self, table: &Table) -> io::Result<()> {
+        for player in table.players.iter() {
+            write!(
+                self.out,
+                "{}: {} points, {} cards",
+                player.name,
+                player.score,
+                player.pile.len()
+            )?;
+            match player.hints {
+                0 => writeln!(self.out)?,
+                hints => writeln!(self.out, ", {} hints", hints)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// This function prints the scores at the end of a game.
+    fn game_over(&mut self, table: &Table, phase: Phase) -> io::Result<()> {
+        match phase {
+            Phase::Finished => writeln!(self.out, "Game over.")?,
+            _ => writeln!(self.out, "Game stopped.")?,
+        }
+        self.scores(table)
+    }
+
+    fn classic(&mut self, mut game: Game) -> io::Result<()> {
+        game.deal().map_err(invalid)?;
+        let mut hints = None;
+        while let Some((centre, draw)) = game.face_up_cards() {
+            let (centre, draw) = (centre.clone(), draw.clone());
+            writeln!(self.out, "Round {}", game.round())?;
+            self.show(&[("Centre".to_string(), &centre), ("Draw".to_string(), &draw)])?;
+            let candidates = symbols(&[&centre, &draw]);
+            hints = self.hints_for(hints, &centre, &draw);
+            let Some(symbol) = self.answer(game.table_mut(), &candidates, hints.as_mut())? else {
+                break;
+            };
+            let verdict = game.claim(0, symbol).map_err(invalid)?;
+            self.verdict(verdict)?;
+        }
+        self.game_over(game.table(), game.phase())
+    }
+
+    fn solo(&mut self, mut solo: Solo) -> io::Result<()> {
+        solo.deal().map_err(invalid)?;
+        let mut profile = self.load_profiles()?.player(&self.options.name);
+        let mut hints = None;
+        while let Some((centre, draw)) = solo.face_up_cards() {
+            let (centre, draw) = (centre.clone(), draw.clone());
+            self.show(&[("Centre".to_string(), &centre), ("Draw".to_string(), &draw)])?;
+            let candidates = symbols(&[&centre, &draw]);
+            hints = self.hints_for(hints, &centre, &draw);
+            let Some(symbol) = self.answer(solo.table_mut(), &candidates, hints.as_mut())? else {
+                break;
+            };
+            let verdict = solo.claim(symbol).map_err(invalid)?;
+            self.verdict(verdict)?;
+            let matching = common_symbol(&centre, &draw);
+            match (verdict, matching) {
+                (Verdict::Correct(claim), _) => {
+                    let time = solo.reaction_times().last().copied().unwrap_or_default();
+                    writeln!(self.out, "Time: {:.2}s", time.as_secs_f64())?;
+                    profile.record_spot(claim.symbol, time);
+                }
+                (Verdict::Wrong(_), Some(matching)) => profile.record_miss(matching),
+                _ => (),
+            }
+        }
+        self.game_over(solo.table(), solo.phase())?;
+        let report = solo.report();
+        writeln!(self.out, "Matches: {}", report.matches)?;
+        writeln!(self.out, "Wrong answers: {}", report.wrong)?;
+        writeln!(self.out, "Median time: {:.2}s", report.median.as_secs_f64())?;
+        writeln!(self.out, "Best time: {:.2}s", report.best.as_secs_f64())?;
+        writeln!(self.out, "Accuracy: {:.0}%", report.accuracy * 100.0)?;
+        self.save_profile(profile)
+    }
+
+    fn tower(&mut self, mut tower: Tower) -> io::Result<()> {
+        tower.deal().map_err(invalid)?;
+        let mut hints = None;
+        while let Some(face_up) = tower.face_up_card() {
+            let face_up = face_up.clone();
+            let Some(own) = tower.table().players[0].top_card().cloned() else {
+                break;
+            };
+            self.show(&[
+                (
+                    format!("Your tower ({} cards)", tower.tower_heights()[0]),
+                    &own,
+                ),
+                ("Draw".to_string(), &face_up),
+            ])?;
+            let candidates = symbols(&[&own, &face_up]);
+            hints = self.hints_for(hints, &own, &face_up);
+            let Some(symbol) = self.answer(tower.table_mut(), &candidates, hints.as_mut())? else {
+                break;
+            };
+            let verdict = tower.claim(0, symbol).map_err(invalid)?;
+            self.verdict(verdict)?;
+        }
+        self.game_over(tower.table(), tower.phase())
+    }
+
+    fn well(&mut self, mut well: Well) -> io::Result<()> {
+        well.deal().map_err(invalid)?;
+        let mut hints = None;
+        while well.phase() == Phase::Playing {
+            let (Some(own), Some(well_card)) = (
+                well.table().players[0].top_card().cloned(),
+                well.well_card().cloned(),
+            ) else {
+                break;
+            };
+            self.show(&[
+                (format!("Your pile ({} cards)", well.pile_sizes()[0]), &own),
+                ("Well".to_string(), &well_card),
+            ])?;
+            let candidates = symbols(&[&own, &well_card]);
+            hints = self.hints_for(hints, &own, &well_card);
+            let Some(symbol) = self.answer(well.table_mut(), &candidates, hints.as_mut())? else {
+                break;
+            };
+            let verdict = well.claim(0, symbol).map_err(invalid)?;
+            self.verdict(verdict)?;
+        }
+        self.game_over(well.table(), well.phase())
+    }
+
+    fn triplet(&mut self, mut game: Triplet) -> io::Result<()> {
+        game.deal().map_err(invalid)?;
+        writeln!(
+            self.out,
+            "Type the numbers of three cards and their symbol, e.g. \"1 4 7 apple\"."
+        )?;
+        while game.phase() == Phase::Playing {
+            let layout = game.layout().to_vec();
+            let captioned: Vec<(String, &SpotItCard)> = layout
+                .iter()
+                .enumerate()
+                .map(|(i, card)| (format!("Card {}", i + 1), card))
+                .collect();
+            self.show(&captioned)?;
+            let candidates = symbols(&layout.iter().collect::<Vec<_>>());
+            let line = match self.command(game.table(), &candidates)? {
+                Command::Answer(line) => line,
+                Command::Hint => {
+                    writeln!(self.out, "Hints are only given for two cards.")?;
+                    continue;
+                }
+                Command::Quit => break,
+            };
+            let (cards, symbol) = match parse_triplet(&line, &candidates) {
+                Ok(parsed) => parsed,
+                Err(message) => {
+                    writeln!(self.out, "{}", message)?;
+                    continue;
+                }
+            };
+            match game.claim(0, cards, symbol) {
+                Ok(Verdict::Wrong(claim)) => writeln!(
+                    self.out,
+                    "Wrong: {} is not on all three cards.",
+                    claim.symbol
+                )?,
+                Ok(verdict) => self.verdict(verdict)?,
+                Err(message) => writeln!(self.out, "{}", message)?,
+            }
+        }
+        self.game_over(game.table(), game.phase())
+    }
+
+    fn practice(&mut self, mut practice: Practice) -> io::Result<()> {
+        practice.deal().map_err(invalid)?;
+        let mut hints = None;
+        while let Some((card, other)) = practice.pair() {
+            let (card, other) = (card.clone(), other.clone());
+            let (played, rounds) = practice.progress();
+            writeln!(self.out, "Pair {} of {}", played + 1, rounds)?;
+            self.show(&[
+                ("Card 1".to_string(), &card),
+                ("Card 2".to_string(), &other),
+            ])?;
+            let candidates = symbols(&[&card, &other]);
+            hints = self.hints_for(hints, &card, &other);
+            let Some(symbol) = self.answer(practice.table_mut(), &candidates, hints.as_mut())?
+            else {
+                break;
+            };
+            let time = practice.elapsed();
+            let verdict = practice.claim(symbol).map_err(invalid)?;
+            self.verdict(verdict)?;
+            if let Verdict::Correct(_) = verdict {
+                writeln!(self.out, "Time: {:.2}s", time.as_secs_f64())?;
+            }
+        }
+        self.game_over(practice.table(), practice.phase())?;
+        self.weakest(practice.profile())?;
+        self.save_profile(practice.into_profile())
+    }
+}
+
+/// This function reads "1 4 7 apple" as the positions of three cards, starting from 1, and a symbol.
+fn parse_triplet(
+    line: &str,
+    candidates: &[SpotItSymbol],
+) -> Result<([usize; 3], SpotItSymbol), String> {
+    let mut words = line.split_whitespace();
+    let mut cards = [0; 3];
+    for card in cards.iter_mut() {
+        *card = words
+            .next()
+            .and_then(|w| w.parse::<usize>().ok())
+            .and_then(|n| n.checked_sub(1))
+            .ok_or_else(|| {
+                "Start with the numbers of three cards, e.g. \"1 4 7 apple\".".to_string()
+            })?;
+    }
+    let symbol = match_symbol(&words.collect::<Vec<_>>().join(" "), candidates)?;
+    Ok((cards, symbol))
+}
+
+/// This function returns the symbols on the cards, without repeats.
+fn symbols(cards: &[&SpotItCard]) -> Vec<SpotItSymbol> {
+    let mut symbols: Vec<SpotItSymbol> = cards.iter().flat_map(|c| c.symbols()).collect();
+    symbols.sort();
+    symbols.dedup();
+    symbols
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SpotItSymbol::*;
+
+    #[test]
+    fn symbols_are_matched_loosely() {
+        let candidates = [Apple, Apricot, ChicoFruit, Blackberry, Blackcurrant];
+        assert_eq!(match_symbol("apple", &candidates), Ok(Apple));
+        assert_eq!(match_symbol("  CHICO fruit ", &candidates), Ok(ChicoFruit));
+        assert_eq!(match_symbol("chico-fruit", &candidates), Ok(ChicoFruit));
+        assert_eq!(match_symbol("Blc", &candidates), Ok(Blackcurrant));
+        assert_eq!(match_symbol("apr", &candidates), Ok(Apricot));
+        assert_eq!(match_symbol("bkbry", &candidates), Ok(Blackberry));
+        assert_eq!(
+            match_symbol("ap", &candidates),
+            Err("Did you mean Apple, Apricot?".to_string())
+        );
+        assert!(match_symbol("banana", &candidates).is_err());
+        assert!(match_symbol("", &candidates).is_err());
+    }
+    #[test]
+    fn completes_names_of_candidates() {
+        let candidates = [Apple, Apricot, ChicoFruit];
+        assert_eq!(complete("ap", &candidates), vec!["Apple", "Apricot"]);
+        assert_eq!(complete("chicof", &candidates), vec!["Chico Fruit"]);
+        assert!(complete("x", &candidates).is_empty());
+    }
+    #[test]
+    fn modes_and_orders_are_chosen_by_number_or_name() {
+        assert_eq!("tower".parse(), Ok(Mode::Tower));
+        assert_eq!("2".parse(), Ok(DeckOrder::Shuffled));
+        assert_eq!("Ordered".parse(), Ok(DeckOrder::Ordered));
+        assert!("chess".parse::<Mode>().is_err());
+        assert!("0".parse::<Mode>().is_err());
+    }
+    #[test]
+    fn menus_ask_until_answered() {
+        let options = Options {
+            script: true,
+            style: TermStyle::plain(),
+            ..Options::default()
+        };
+        let mut input = Script::new("9\ntriplet\nordered\nquit\n".as_bytes());
+        let mut out = Vec::new();
+        Session::new(&options, &mut input, &mut out).run().unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("There is no game mode called 9."));
+        assert!(out.contains("Playing triplet (ordered deck)."));
+        assert!(out.contains("Card 9"));
+        assert!(out.ends_with("Game stopped.\nPlayer: 0 points, 0 cards\n"));
+    }
+    #[test]
+    fn triplets_are_parsed() {
+        assert_eq!(
+            parse_triplet("1 4 7 apple", &[Apple]),
+            Ok(([0, 3, 6], Apple))
+        );
+        assert!(parse_triplet("1 4 apple", &[Apple]).is_err());
+        assert!(parse_triplet("0 4 7 apple", &[Apple]).is_err());
+    }
+    #[test]
+    fn hints_narrow_the_search_and_cost_points() {
+        let options = Options {
+            mode: Some(Mode::Classic),
+            order: Some(DeckOrder::Ordered),
+            seed: Some(3),
+            script: true,
+            style: TermStyle::plain(),
+            ..Options::default()
+        };
+        let mut input = Script::new("hint\n?\nh\nquit\n".as_bytes());
+        let mut out = Vec::new();
+        Session::new(&options, &mut input, &mut out).run().unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let hints: Vec<&str> = out.lines().filter(|l| l.starts_with("Hint: ")).collect();
+        assert_eq!(hints.len(), 3);
+        assert!(hints[0].starts_with("Hint: It is not "));
+        assert!(hints[2].starts_with("Hint: It is "));
+        assert!(out.ends_with("Game stopped.\nPlayer: -3 points, 0 cards, 3 hints\n"));
+
+        let options = Options {
+            mode: Some(Mode::Triplet),
+            ..options
+        };
+        let mut input = Script::new("hint\nquit\n".as_bytes());
+        let mut out = Vec::new();
+        Session::new(&options, &mut input, &mut out).run().unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Hints are only given for two cards."));
+    }
+    #[test]
+    fn practice_is_recorded_in_the_profile() {
+        let dir = std::env::temp_dir().join(format!("cardgame-practice-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("profiles.json");
+        let options = Options {
+            mode: Some(Mode::Practice),
+            order: Some(DeckOrder::Ordered),
+            seed: Some(4),
+            name: "Ann".to_string(),
+            script: true,
+            style: TermStyle::plain(),
+            profile: Some(path.clone()),
+            ..Options::default()
+        };
+        // The answer is revealed by the third hint, and the same seed deals the same pair again
+        let mut input = Script::new("zzz\nhint\nhint\nhint\nquit\n".as_bytes());
+        let mut out = Vec::new();
+        Session::new(&options, &mut input, &mut out).run().unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Pair 1 of 20"));
+        let hint = out
+            .lines()
+            .rfind(|l| l.starts_with("Hint: It is "))
+            .unwrap();
+        let answer = hint
+            .trim_start_matches("Hint: It is ")
+            .trim_end_matches('.');
+        assert!(out.contains("Profile saved to "));
+
+        let script = format!("{}\nquit\n", answer);
+        let mut input = Script::new(script.as_bytes());
+        let mut out = Vec::new();
+        Session::new(&options, &mut input, &mut out).run().unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Pair 2 of 20"));
+        assert!(out.contains("Weakest symbols: "));
+        let profile = Profiles::load(&path).unwrap().player("Ann");
+        assert_eq!(profile.step, 1);
+        assert_eq!(profile.symbols.values().map(|s| s.spotted).sum::<u32>(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,359 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Support library to import, export, validate and describe SpotIt! decks of `cardgame` crate.
+//!
+//! Decks are stored in one of three formats, so that designers can make and check decks without writing Rust:
+//! - text: one card per line, e.g. "[Apple, Chico Fruit]", as cards are displayed. Blank lines and lines starting with `#` are skipped.
+//! - JSON: `{"cards": [["Apple", "ChicoFruit"], ...]}`, or just the list of cards.
+//! - CSV: one card per row, one symbol per column.
+//!
+//! Symbol names may be written in words or not, in any case. See `SpotItSymbol::from_str`.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`Maths behind SpotIt! card game]: https://www.smithsonianmag.com/science-nature/math-card-game-spot-it-180970873/
+
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use crate::{Deck, SpotItDeck};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// This enum defines the formats a deck can be written in.
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub enum DeckFormat {
+    /// This is one card per line, as cards are displayed.
+    Text,
+    /// This is a JSON object with the list of cards.
+    Json,
+    /// This is one card per row of comma separated symbols.
+    Csv,
+}
+
+impl DeckFormat {
+    /// This function guesses the format from the extension of a file, i.e. `.json`, `.csv`, or text for anything else.
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .as_deref()
+        {
+            Some("json") => DeckFormat::Json,
+            Some("csv") => DeckFormat::Csv,
+            _ => DeckFormat::Text,
+        }
+    }
+}
+
+impl fmt::Display for DeckFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for DeckFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "text" | "txt" => Ok(DeckFormat::Text),
+            "json" => Ok(DeckFormat::Json),
+            "csv" => Ok(DeckFormat::Csv),
+            _ => Err(format!("Unknown deck format: {}.", s)),
+        }
+    }
+}
+
+/// This struct defines the statistics of a deck.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DeckStats {
+    /// This is the number of cards.
+    pub cards: usize,
+    /// This is the number of different symbols on the cards.
+    pub symbols: usize,
+    /// This is the fewest and the most symbols on a card.
+    pub symbols_per_card: (usize, usize),
+    /// This is the fewest and the most cards a symbol is on.
+    pub cards_per_symbol: (usize, usize),
+    /// This is the number of pairs of cards which do not share exactly one symbol.
+    pub bad_pairs: usize,
+    /// This is the order n if the deck is a whole projective plane, i.e. n^2 + n + 1 cards and symbols, and n + 1 symbols on every card, for n of 1 or more.
+    pub order: Option<usize>,
+}
+
+impl fmt::Display for DeckStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Cards: {}", self.cards)?;
+        writeln!(f, "Symbols: {}", self.symbols)?;
+        writeln!(
+            f,
+            "Symbols per card: {}",
+            range(self.symbols_per_card.0, self.symbols_per_card.1)
+        )?;
+        writeln!(
+            f,
+            "Cards per symbol: {}",
+            range(self.cards_per_symbol.0, self.cards_per_symbol.1)
+        )?;
+        writeln!(
+            f,
+            "Pairs not sharing exactly one symbol: {}",
+            self.bad_pairs
+        )?;
+        match self.order {
+            Some(order) => write!(f, "Projective plane of order {}", order),
+            None => write!(f, "Not a whole projective plane"),
+        }
+    }
+}
+
+fn range(min: usize, max: usize) -> String {
+    match min == max {
+        true => min.to_string(),
+        false => format!("{} to {}", min, max),
+    }
+}
+
+impl SpotItDeck {
+    /// This function reads a deck written in the format.
+    /// It returns an error naming the line, row or card which cannot be read.
+    pub fn import(text: &str, format: DeckFormat) -> Result<SpotItDeck, String> {
+        let rows: Vec<Vec<String>> = match format {
+            DeckFormat::Text => read_lines(text, ',', true),
+            DeckFormat::Csv => read_lines(text, ',', false),
+            DeckFormat::Json => read_json(text)?,
+        };
+        let mut deck = SpotItDeck::new();
+        for (i, row) in rows.iter().enumerate() {
+            let mut card = SpotItCard(HashSet::new());
+            for name in row {
+                let symbol: SpotItSymbol =
+                    name.parse().map_err(|e| format!("Card {}: {}", i + 1, e))?;
+                if !card.0.insert(symbol) {
+                    return Err(format!("Card {}: {} is there twice.", i + 1, symbol));
+                }
+            }
+            deck.push_card(card);
+        }
+        Ok(deck)
+    }
+
+    /// This function reads a deck from a file, in the format given by its extension. See `DeckFormat::from_path`.
+    pub fn from_file(path: &Path) -> Result<SpotItDeck, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::import(&text, DeckFormat::from_path(path))
+    }
+
+    /// This function writes the deck in the format, with the symbols of every card in a stable order.
+    pub fn export(&self, format: DeckFormat) -> String {
+        let rows = self.cards.iter().map(|c| c.sorted_symbols());
+        match format {
+            DeckFormat::Text => self
+                .cards
+                .iter()
+                .map(|card| format!("{}\n", card))
+                .collect(),
+            DeckFormat::Csv => rows
+                .map(|symbols| {
+                    let names: Vec<String> = symbols.iter().map(|s| format!("{:?}", s)).collect();
+                    format!("{}\n", names.join(","))
+                })
+                .collect(),
+            DeckFormat::Json => {
+                let cards: Vec<Vec<String>> = rows
+                    .map(|symbols| symbols.iter().map(|s| format!("{:?}", s)).collect())
+                    .collect();
+                let json = serde_json::json!({ "cards": cards });
+                // A list of strings always turns into JSON
+                serde_json::to_string_pretty(&json).unwrap_or_default() + "\n"
+            }
+        }
+    }
+
+    /// This function returns what is wrong with the deck for playing SpotIt!, or nothing if it is fine.
+    /// Every card must have a symbol, no two cards may be the same, and every two cards must share exactly one symbol.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.cards.len() < 2 {
+            problems.push("A deck needs at least two cards.".to_string());
+        }
+        for (i, card) in self.cards.iter().enumerate() {
+            if card.0.is_empty() {
+                problems.push(format!("Card {} has no symbol.", i + 1));
+            }
+        }
+        for (i, card) in self.cards.iter().enumerate() {
+            for (j, other) in self.cards.iter().enumerate().skip(i + 1) {
+                let shared = card.0.intersection(&other.0).count();
+                if card == other && !card.0.is_empty() {
+                    problems.push(format!("Cards {} and {} are the same.", i + 1, j + 1));
+                } else if shared != 1 && !card.0.is_empty() && !other.0.is_empty() {
+                    problems.push(format!(
+                        "Cards {} and {} share {} symbols.",
+                        i + 1,
+                        j + 1,
+                        shared
+                    ));
+                }
+            }
+        }
+        problems
+    }
+
+    /// This function returns the statistics of the deck.
+    pub fn stats(&self) -> DeckStats {
+        let mut cards_per_symbol: BTreeMap<SpotItSymbol, usize> = BTreeMap::new();
+        for symbol in self.cards.iter().flat_map(|c| c.0.iter()) {
+            *cards_per_symbol.entry(*symbol).or_default() += 1;
+        }
+        let per_card: Vec<usize> = self.cards.iter().map(|c| c.0.len()).collect();
+        let bad_pairs = (0..self.cards.len())
+            .flat_map(|i| (i + 1..self.cards.len()).map(move |j| (i, j)))
+            .filter(|(i, j)| !self.cards[*i].match_exactly_one_symbol(&self.cards[*j]))
+            .count();
+        let min_max = |values: Vec<usize>| {
+            (
+                values.iter().min().copied().unwrap_or(0),
+                values.iter().max().copied().unwrap_or(0),
+            )
+        };
+        let symbols_per_card = min_max(per_card);
+        let order = symbols_per_card.0.checked_sub(1).filter(|n| {
+            let size = n * n + n + 1;
+            *n >= 1
+                && bad_pairs == 0
+                && symbols_per_card.0 == symbols_per_card.1
+                && self.cards.len() == size
+                && cards_per_symbol.len() == size
+        });
+        DeckStats {
+            cards: self.cards.len(),
+            symbols: cards_per_symbol.len(),
+            symbols_per_card,
+            cards_per_symbol: min_max(cards_per_symbol.into_values().collect()),
+            bad_pairs,
+            order,
+        }
+    }
+}
+
+/// This function splits lines into trimmed names, skipping blank lines and comments, and brackets around a line if asked.
+fn read_lines(text: &str, separator: char, brackets: bool) -> Vec<Vec<String>> {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match brackets {
+            true => line.trim_start_matches('[').trim_end_matches(']'),
+            false => line,
+        })
+        .map(|line| {
+            line.split(separator)
+                .map(|name| name.trim().trim_matches('"').to_string())
+                .filter(|name| !name.is_empty())
+                .collect()
+        })
+        .collect()
+}
+
+/// This function reads the names of the symbols on every card from JSON.
+fn read_json(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(text).map_err(|e| format!("Not valid JSON: {}", e))?;
+    let cards = value.get("cards").unwrap_or(&value);
+    serde_json::from_value(cards.clone())
+        .map_err(|_| "Expected a list of cards, each a list of symbol names.".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decks_round_trip_in_every_format() {
+        let deck = SpotItDeck::generate_by_prime(3).unwrap();
+        for format in [DeckFormat::Text, DeckFormat::Json, DeckFormat::Csv] {
+            let text = deck.export(format);
+            assert_eq!(SpotItDeck::import(&text, format).unwrap().cards, deck.cards);
+        }
+        assert!(deck.export(DeckFormat::Text).starts_with("[Apple, Apricot"));
+        assert!(deck.export(DeckFormat::Csv).starts_with("Apple,Apricot"));
+    }
+    #[test]
+    fn imports_loose_names_and_reports_bad_cards() {
+        let text = "# a tiny deck\n\n[apple, Chico Fruit]\nAPPLE, banana\n";
+        let deck = SpotItDeck::import(text, DeckFormat::Text).unwrap();
+        assert_eq!(deck.cards.len(), 2);
+        assert!(deck.cards[0].0.contains(&SpotItSymbol::ChicoFruit));
+        assert_eq!(
+            SpotItDeck::import("Apple\nApple, Potato\n", DeckFormat::Csv).err(),
+            Some("Card 2: Unknown symbol: Potato.".to_string())
+        );
+        assert!(SpotItDeck::import("Apple, apple", DeckFormat::Csv).is_err());
+        let deck = SpotItDeck::import("[[\"Apple\"], [\"Apple\"]]", DeckFormat::Json).unwrap();
+        assert_eq!(deck.cards.len(), 2);
+        assert!(SpotItDeck::import("{\"cards\": 1}", DeckFormat::Json).is_err());
+        assert!(SpotItDeck::import("[", DeckFormat::Json).is_err());
+    }
+    #[test]
+    fn finds_problems_with_decks() {
+        assert!(SpotItDeck::default().problems().is_empty());
+        let deck =
+            SpotItDeck::import("Apple,Banana\nApple,Banana\nFig\n\n", DeckFormat::Csv).unwrap();
+        assert_eq!(
+            deck.problems(),
+            vec![
+                "Cards 1 and 2 are the same.",
+                "Cards 1 and 3 share 0 symbols.",
+                "Cards 2 and 3 share 0 symbols.",
+            ]
+        );
+        let deck = SpotItDeck::import("[]\n[Apple]", DeckFormat::Text).unwrap();
+        assert_eq!(deck.problems(), vec!["Card 1 has no symbol."]);
+        assert_eq!(SpotItDeck::new().problems().len(), 1);
+    }
+    #[test]
+    fn describes_decks() {
+        let stats = SpotItDeck::default().stats();
+        assert_eq!(stats.cards, 57);
+        assert_eq!(stats.symbols, 57);
+        assert_eq!(stats.symbols_per_card, (8, 8));
+        assert_eq!(stats.cards_per_symbol, (8, 8));
+        assert_eq!(stats.bad_pairs, 0);
+        assert_eq!(stats.order, Some(7));
+        assert!(stats.to_string().ends_with("Projective plane of order 7"));
+
+        let mut deck = SpotItDeck::default();
+        deck.cards.pop();
+        let stats = deck.stats();
+        assert_eq!(stats.cards_per_symbol, (7, 8));
+        assert_eq!(stats.order, None);
+        assert!(stats.to_string().contains("Cards per symbol: 7 to 8\n"));
+
+        let stats = SpotItDeck::import("[Apple]", DeckFormat::Text)
+            .unwrap()
+            .stats();
+        assert_eq!(stats.order, None);
+        assert!(!stats.to_string().contains("Projective plane"));
+        let deck = SpotItDeck::import(
+            "[Apple, Banana]\n[Apple, Cherry]\n[Banana, Cherry]",
+            DeckFormat::Text,
+        );
+        assert_eq!(deck.unwrap().stats().order, Some(1));
+    }
+    #[test]
+    fn format_follows_extension() {
+        assert_eq!(
+            DeckFormat::from_path(Path::new("deck.JSON")),
+            DeckFormat::Json
+        );
+        assert_eq!(
+            DeckFormat::from_path(Path::new("deck.csv")),
+            DeckFormat::Csv
+        );
+        assert_eq!(DeckFormat::from_path(Path::new("deck")), DeckFormat::Text);
+        assert_eq!("txt".parse(), Ok(DeckFormat::Text));
+        assert!("svg".parse::<DeckFormat>().is_err());
+    }
+}
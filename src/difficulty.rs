@@ -0,0 +1,339 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Difficulty levels of SpotIt! in `cardgame` crate.
+//!
+//! One `Difficulty` sets up the whole game: the order of the deck, i.e. how many symbols are on a card, and the `Layout` the cards are drawn with.
+//! A `Layout` varies the size of the symbols, turns them, and chooses whether symbols which look alike, e.g. Lemon and Lime, are kept apart or put on the same cards.
+//!
+//! The expert deck of the original game is of order 7, and a deck of order 11 would need 133 symbols, more than the fruits of `SpotItSymbol`.
+//! So the expert level plays the largest deck there is, and is harder than the standard level by its layout.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt!`]: https://www.amazon.com/Asmodee-SP411-Spot-It/dp/B0039S7NO6
+
+use crate::spotitcard::{SpotItCard, SpotItSymbol, SymbolCategory};
+use crate::svg::{spotit_face, SvgCard};
+use crate::theme::Theme;
+use crate::SpotItDeck;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+
+/// This is the pairs of symbols which look alike, e.g. two small dark berries.
+pub const LOOK_ALIKES: [(SpotItSymbol, SpotItSymbol); 15] = [
+    (SpotItSymbol::Lemon, SpotItSymbol::Lime),
+    (SpotItSymbol::Orange, SpotItSymbol::Tangerine),
+    (SpotItSymbol::Clementine, SpotItSymbol::Mandarine),
+    (SpotItSymbol::Blackberry, SpotItSymbol::Mulberry),
+    (SpotItSymbol::Raspberry, SpotItSymbol::Salmonberry),
+    (SpotItSymbol::Blueberry, SpotItSymbol::Bilberry),
+    (SpotItSymbol::Plum, SpotItSymbol::Damson),
+    (SpotItSymbol::Redcurrant, SpotItSymbol::Cranberry),
+    (SpotItSymbol::Peach, SpotItSymbol::Nectarine),
+    (SpotItSymbol::Melon, SpotItSymbol::Honeydew),
+    (SpotItSymbol::Lychee, SpotItSymbol::Longan),
+    (SpotItSymbol::Mangosteen, SpotItSymbol::PurpleMangosteen),
+    (SpotItSymbol::Grapefruit, SpotItSymbol::Pomelo),
+    (SpotItSymbol::Blackcurrant, SpotItSymbol::Elderberry),
+    (SpotItSymbol::Apricot, SpotItSymbol::Plumcot),
+];
+
+/// This enum defines how symbols which look alike are dealt with when a deck is made.
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub enum LookAlikes {
+    /// This is a deck of symbols which look different, from every kind of fruit in turn. Look-alikes are only used if the deck needs them.
+    Avoided,
+    /// This is the deck as generated, whatever the symbols look like.
+    Allowed,
+    /// This is a deck with every pair of look-alikes, put on the same cards as far as the deck allows.
+    Paired,
+}
+
+/// This struct defines how a symbol is drawn on a card.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Placement {
+    /// This is the size of the symbol, as a share of the usual size.
+    pub scale: f64,
+    /// This is the angle the symbol is turned by, clockwise in degrees.
+    pub angle: f64,
+}
+
+/// This struct defines how the symbols of a deck are chosen and drawn.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Layout {
+    /// This is how much the size of a symbol can differ from the usual size, from 0 for none to 1, e.g. 0.5 draws symbols from half to one and a half times the size.
+    pub size_variance: f64,
+    /// This is true if symbols are turned at random angles.
+    pub rotation: bool,
+    /// This is how symbols which look alike are dealt with.
+    pub look_alikes: LookAlikes,
+}
+
+impl Default for Layout {
+    /// This function creates the layout cards were always drawn with: every symbol of the same size and upright.
+    fn default() -> Self {
+        Layout {
+            size_variance: 0.0,
+            rotation: false,
+            look_alikes: LookAlikes::Allowed,
+        }
+    }
+}
+
+impl Layout {
+    /// This function returns how every symbol of the card is drawn, in the order of `SpotItCard::sorted_symbols`.
+    /// The same card is always drawn the same way, so printed decks and snapshots do not change from one run to the next.
+    pub fn placements(&self, card: &SpotItCard) -> Vec<Placement> {
+        let symbols = card.sorted_symbols();
+        let seed = symbols.iter().fold(0u64, |seed, s| {
+            seed.wrapping_mul(131).wrapping_add(*s as u64 + 1)
+        });
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        let variance = self.size_variance.clamp(0.0, 1.0);
+        symbols
+            .iter()
+            .map(|_| {
+                let (size, turn): (f64, f64) = (rng.gen(), rng.gen());
+                Placement {
+                    scale: 1.0 + variance * (size * 2.0 - 1.0),
+                    angle: match self.rotation {
+                        true => turn * 360.0,
+                        false => 0.0,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// This function returns the symbols of a deck of `count` symbols, in the order they are placed on the projective plane.
+    pub fn symbols(&self, count: usize) -> Vec<SpotItSymbol> {
+        let partners: Vec<SpotItSymbol> = LOOK_ALIKES.iter().map(|(_, b)| *b).collect();
+        let symbols: Vec<SpotItSymbol> = match self.look_alikes {
+            LookAlikes::Allowed => SpotItSymbol::iter().collect(),
+            LookAlikes::Paired => {
+                let paired: Vec<SpotItSymbol> =
+                    LOOK_ALIKES.iter().flat_map(|(a, b)| [*a, *b]).collect();
+                let rest = SpotItSymbol::iter().filter(|s| !paired.contains(s));
+                paired.iter().copied().chain(rest).collect()
+            }
+            LookAlikes::Avoided => {
+                // One fruit of every kind in turn, leaving the second of every look-alike pair to the end
+                let kinds: Vec<Vec<SpotItSymbol>> = SymbolCategory::iter()
+                    .map(|kind| {
+                        SpotItSymbol::iter()
+                            .filter(|s| s.category() == kind && !partners.contains(s))
+                            .collect()
+                    })
+                    .collect();
+                let longest = kinds.iter().map(|k| k.len()).max().unwrap_or(0);
+                let turns = (0..longest).flat_map(|i| kinds.iter().filter_map(move |k| k.get(i)));
+                turns.copied().chain(partners.iter().copied()).collect()
+            }
+        };
+        symbols.into_iter().take(count).collect()
+    }
+
+    /// This function generates a deck of order n, with the symbols of this layout.
+    pub fn deck(&self, n: u8) -> Result<SpotItDeck, String> {
+        let mut deck = SpotItDeck::generate_by_prime(n)?;
+        let count = n as usize * n as usize + n as usize + 1;
+        let relabel: HashMap<SpotItSymbol, SpotItSymbol> =
+            SpotItSymbol::iter().zip(self.symbols(count)).collect();
+        for card in deck.cards.iter_mut() {
+            card.0 = card.0.iter().map(|s| relabel[s]).collect();
+        }
+        Ok(deck)
+    }
+}
+
+/// This struct defines a SpotIt! card drawn with a layout.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LaidOutCard<'a> {
+    /// This is the card, whose symbols decide which cards match.
+    pub card: &'a SpotItCard,
+    /// This is the layout the symbols are drawn with.
+    pub layout: Layout,
+}
+
+impl SvgCard for LaidOutCard<'_> {
+    fn svg_face(&self, width: f64, height: f64, bleed: f64) -> String {
+        // The fruits theme has a label for every symbol
+        let labels = Theme::fruits().card_labels(self.card).unwrap_or_default();
+        let placements = self.layout.placements(self.card);
+        spotit_face(&labels, &placements, width, height, bleed)
+    }
+}
+
+impl SpotItDeck {
+    /// This function lays out every card of the deck, to be drawn with the layout.
+    pub fn lay_out(&self, layout: Layout) -> Vec<LaidOutCard<'_>> {
+        self.cards
+            .iter()
+            .map(|card| LaidOutCard { card, layout })
+            .collect()
+    }
+}
+
+/// This enum defines the difficulty levels, from the easiest.
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Default, strum_macros::EnumIter)]
+pub enum Difficulty {
+    /// This is 7 cards of 3 symbols, which look nothing alike.
+    Toddler,
+    /// This is 13 cards of 4 symbols, a little bigger or smaller.
+    Easy,
+    /// This is 31 cards of 6 symbols, of different sizes.
+    Junior,
+    /// This is the usual 57 cards of 8 symbols, of different sizes and turned.
+    #[default]
+    Standard,
+    /// This is the usual 57 cards, with more different sizes and look-alikes side by side.
+    Expert,
+}
+
+impl Difficulty {
+    /// This function returns the order of the deck, a prime: the deck has n^2 + n + 1 cards of n + 1 symbols.
+    pub fn order(&self) -> u8 {
+        match self {
+            Difficulty::Toddler => 2,
+            Difficulty::Easy => 3,
+            Difficulty::Junior => 5,
+            Difficulty::Standard | Difficulty::Expert => 7,
+        }
+    }
+
+    /// This function returns the layout the cards are drawn with.
+    pub fn layout(&self) -> Layout {
+        let (size_variance, rotation, look_alikes) = match self {
+            Difficulty::Toddler => (0.0, false, LookAlikes::Avoided),
+            Difficulty::Easy => (0.15, false, LookAlikes::Avoided),
+            Difficulty::Junior => (0.25, false, LookAlikes::Allowed),
+            Difficulty::Standard => (0.3, true, LookAlikes::Allowed),
+            Difficulty::Expert => (0.5, true, LookAlikes::Paired),
+        };
+        Layout {
+            size_variance,
+            rotation,
+            look_alikes,
+        }
+    }
+
+    /// This function returns the share of the symbols which do not match ruled out by the first hint. See `game::hint::Hints::with_fraction`.
+    pub fn hint_fraction(&self) -> f64 {
+        match self {
+            Difficulty::Toddler => 0.75,
+            Difficulty::Easy | Difficulty::Junior => 0.6,
+            Difficulty::Standard => crate::game::hint::DEFAULT_FRACTION,
+            Difficulty::Expert => 0.4,
+        }
+    }
+
+    /// This function generates the deck of the level, in generated order.
+    pub fn deck(&self) -> SpotItDeck {
+        // Every level is of a prime order small enough for the symbols there are
+        self.layout().deck(self.order()).unwrap()
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::iter()
+            .find(|d| d.to_string() == s.trim().to_lowercase())
+            .ok_or_else(|| format!("Unknown difficulty: {}.", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Deck;
+    use std::collections::HashSet;
+
+    #[test]
+    fn levels_set_the_deck() {
+        let sizes: Vec<(usize, usize)> = Difficulty::iter()
+            .map(|d| {
+                let deck = d.deck();
+                assert!(deck.problems().is_empty(), "{}", d);
+                (deck.cards.len(), deck.cards[0].0.len())
+            })
+            .collect();
+        assert_eq!(sizes, [(7, 3), (13, 4), (31, 6), (57, 8), (57, 8)]);
+        // The standard deck is the usual one
+        assert_eq!(
+            Difficulty::Standard.deck().cards,
+            SpotItDeck::default().cards
+        );
+        assert_eq!("Expert".parse(), Ok(Difficulty::Expert));
+        assert!("impossible".parse::<Difficulty>().is_err());
+    }
+    #[test]
+    fn look_alikes_are_avoided_or_paired() {
+        let symbols: HashSet<SpotItSymbol> = Difficulty::Toddler
+            .deck()
+            .cards
+            .iter()
+            .flat_map(|c| c.0.iter().copied())
+            .collect();
+        let kinds: HashSet<SymbolCategory> = symbols.iter().map(|s| s.category()).collect();
+        assert_eq!(kinds.len(), 7);
+        assert!(LOOK_ALIKES
+            .iter()
+            .all(|(a, b)| !(symbols.contains(a) && symbols.contains(b))));
+
+        let deck = Difficulty::Expert.deck();
+        let together = LOOK_ALIKES
+            .iter()
+            .filter(|(a, b)| {
+                deck.cards
+                    .iter()
+                    .any(|c| c.0.contains(a) && c.0.contains(b))
+            })
+            .count();
+        assert!(together >= 10, "{} pairs share a card", together);
+    }
+    #[test]
+    fn placements_vary_by_layout() {
+        let card = &SpotItDeck::default().cards[0];
+        let plain = Layout::default().placements(card);
+        assert_eq!(plain.len(), 8);
+        assert!(plain.iter().all(|p| p.scale == 1.0 && p.angle == 0.0));
+
+        let expert = Difficulty::Expert.layout().placements(card);
+        assert_eq!(expert, Difficulty::Expert.layout().placements(card));
+        assert!(expert.iter().all(|p| (0.5..=1.5).contains(&p.scale)));
+        assert!(expert.iter().any(|p| p.angle != 0.0));
+        assert!(expert.iter().any(|p| p.scale != expert[0].scale));
+    }
+    #[test]
+    fn laid_out_cards_are_drawn_sized_and_turned() {
+        use crate::raster::RasterCard;
+        let deck = SpotItDeck::default();
+        let plain = deck.lay_out(Layout::default());
+        let expert = deck.lay_out(Difficulty::Expert.layout());
+        assert_eq!(
+            plain[0].to_svg(80.0, 80.0),
+            deck.cards[0].to_svg(80.0, 80.0)
+        );
+        assert!(!plain[0].to_svg(80.0, 80.0).contains("rotate("));
+        assert!(expert[0].to_svg(80.0, 80.0).contains("rotate("));
+        assert_eq!(
+            plain[0].to_png(64, 64).unwrap(),
+            deck.cards[0].to_png(64, 64).unwrap()
+        );
+        assert_ne!(
+            expert[0].to_png(64, 64).unwrap(),
+            deck.cards[0].to_png(64, 64).unwrap()
+        );
+    }
+}
@@ -10,6 +10,7 @@
 //! [`French Card Game`]: https://en.wikipedia.org/wiki/French_playing_cards
 //! [`SpotIt! Rules`]: https://www.ultraboardgames.com/spot-it/game-rules.php
 
+use std::fmt;
 use strum_macros::EnumIter;
 
 /// This defines the suits of the French Card Game.
@@ -42,6 +43,43 @@ pub enum FrenchRank {
     Ace, // will be 14
 }
 
+impl FrenchSuit {
+    /// This function returns the Unicode glyph of the suit, e.g. ♠ for a spade.
+    pub fn glyph(&self) -> char {
+        match self {
+            FrenchSuit::Club => '♣',
+            FrenchSuit::Diamond => '♦',
+            FrenchSuit::Heart => '♥',
+            FrenchSuit::Spade => '♠',
+        }
+    }
+    /// This function returns true if the suit is printed in red, i.e. diamonds and hearts.
+    pub fn is_red(&self) -> bool {
+        matches!(self, FrenchSuit::Diamond | FrenchSuit::Heart)
+    }
+}
+
+impl FrenchRank {
+    /// This function returns the short label printed on the corner of a card, e.g. "10" or "A".
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            FrenchRank::Two => "2",
+            FrenchRank::Three => "3",
+            FrenchRank::Four => "4",
+            FrenchRank::Five => "5",
+            FrenchRank::Six => "6",
+            FrenchRank::Seven => "7",
+            FrenchRank::Eight => "8",
+            FrenchRank::Nine => "9",
+            FrenchRank::Ten => "10",
+            FrenchRank::Jack => "J",
+            FrenchRank::Queen => "Q",
+            FrenchRank::King => "K",
+            FrenchRank::Ace => "A",
+        }
+    }
+}
+
 /// This tuple struct defines a French Card.
 #[derive(Debug, PartialEq, Clone, Eq, Hash)]
 pub struct FrenchCard(pub FrenchRank, pub FrenchSuit);
@@ -65,6 +103,47 @@ impl FrenchCard {
     }
 }
 
+impl FrenchCard {
+    /// This function returns the Unicode playing card glyph of the card, e.g. 🂡 for the ace of spades.
+    pub fn glyph(&self) -> char {
+        let suit_base: u32 = match self.suit() {
+            FrenchSuit::Spade => 0x1F0A0,
+            FrenchSuit::Heart => 0x1F0B0,
+            FrenchSuit::Diamond => 0x1F0C0,
+            FrenchSuit::Club => 0x1F0D0,
+        };
+        // Unicode has a knight between the jack and the queen, which is not used in French cards
+        let rank_offset = match self.rank() {
+            FrenchRank::Ace => 1,
+            FrenchRank::Queen => 0xD,
+            FrenchRank::King => 0xE,
+            rank => rank as u32,
+        };
+        char::from_u32(suit_base + rank_offset).unwrap_or('?')
+    }
+}
+
+impl fmt::Display for FrenchSuit {
+    /// This function shows the glyph of the suit, e.g. "♠".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.glyph())
+    }
+}
+
+impl fmt::Display for FrenchRank {
+    /// This function shows the short name of the rank, e.g. "10" or "A".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.short_name())
+    }
+}
+
+impl fmt::Display for FrenchCard {
+    /// This function shows the rank and the suit of the card, e.g. "A♠".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&format!("{}{}", self.rank(), self.suit()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -101,4 +180,38 @@ mod tests {
         assert!(!card1.match_suit(&card3)); // two cards with same rank but different suit
         assert!(!card1.match_suit(&card4)); // two cards with different suit and rank
     }
+    #[test]
+    fn can_show_short_name_and_glyph_of_frenchcard() {
+        assert_eq!(FrenchRank::Ten.short_name(), "10");
+        assert_eq!(FrenchRank::Queen.short_name(), "Q");
+        assert_eq!(FrenchSuit::Spade.glyph(), '♠');
+        assert!(FrenchSuit::Heart.is_red());
+        assert!(!FrenchSuit::Club.is_red());
+    }
+    #[test]
+    fn can_display_frenchcard() {
+        assert_eq!(
+            FrenchCard(FrenchRank::Ace, FrenchSuit::Spade).to_string(),
+            "A♠"
+        );
+        assert_eq!(
+            FrenchCard(FrenchRank::Ten, FrenchSuit::Heart).to_string(),
+            "10♥"
+        );
+        assert_eq!(
+            format!("{:>3}", FrenchCard(FrenchRank::Two, FrenchSuit::Club)),
+            " 2♣"
+        );
+    }
+    #[test]
+    fn can_show_unicode_glyph_of_frenchcard() {
+        assert_eq!(FrenchCard(FrenchRank::Ace, FrenchSuit::Spade).glyph(), '🂡');
+        assert_eq!(FrenchCard(FrenchRank::Ten, FrenchSuit::Heart).glyph(), '🂺');
+        assert_eq!(
+            FrenchCard(FrenchRank::Jack, FrenchSuit::Diamond).glyph(),
+            '🃋'
+        );
+        assert_eq!(FrenchCard(FrenchRank::Queen, FrenchSuit::Club).glyph(), '🃝');
+        assert_eq!(FrenchCard(FrenchRank::King, FrenchSuit::Club).glyph(), '🃞');
+    }
 }
@@ -0,0 +1,478 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Arbitration of claims shouted at the same time in SpotIt! in `cardgame` crate.
+//!
+//! At a real table two players often shout at once, and some shout the wrong symbol.
+//! An `Arbiter` referees a game of any mode: it takes claims with the time they were shouted, and judges every one of them against the cards as they were, using the shared symbol of the cards rather than trusting the claim.
+//! Right claims within the tie window of the first right claim are a tie, which the `TieBreak` settles. Right claims after the window are late.
+//! Wrong claims get the `Penalty` of the rules: losing a card, sitting out turns, or losing points.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt! Rules`]: https://www.ultraboardgames.com/spot-it/game-rules.php
+
+use super::{shared_symbol, Claim, GameMode, PlayerId, Verdict};
+use crate::spotitcard::SpotItSymbol;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// This is the tie window, unless set otherwise: claims closer than this are too close to tell apart.
+pub const DEFAULT_WINDOW: Duration = Duration::from_millis(150);
+
+/// This enum defines what happens to a player who claims a wrong symbol.
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Serialize, Deserialize)]
+pub enum Penalty {
+    /// This is no penalty.
+    Nothing,
+    /// This is losing a card, in whatever way counts against the player in the mode. See `GameMode::lose_card`.
+    LoseCard,
+    /// This is having claims ignored for the number of turns, i.e. until that many matches have been taken by others.
+    SitOut(usize),
+    /// This is losing the number of points.
+    Deduct(i32),
+}
+
+/// This enum defines how a tie between right claims is settled.
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub enum TieBreak {
+    /// This is the earliest claim taking the match, and the first in seating order if they were shouted at the very same time.
+    Earliest,
+    /// This is a draw between the tied players.
+    Random,
+    /// This is no one taking the match, so the same cards are played again.
+    Void,
+}
+
+/// This struct defines the rules of arbitration.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Rules {
+    /// This is how close right claims must be to be a tie.
+    pub window: Duration,
+    /// This is how a tie is settled.
+    pub tie_break: TieBreak,
+    /// This is the penalty for a wrong claim.
+    pub penalty: Penalty,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            window: DEFAULT_WINDOW,
+            tie_break: TieBreak::Earliest,
+            penalty: Penalty::Nothing,
+        }
+    }
+}
+
+/// This struct defines a claim shouted at a time, on one of the player's chances. See `GameMode::chances`.
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub struct TimedClaim {
+    /// This is the player who claims.
+    pub player: PlayerId,
+    /// This is the position of the chance claimed on.
+    pub chance: usize,
+    /// This is the symbol claimed.
+    pub symbol: SpotItSymbol,
+    /// This is when the claim was shouted, on any clock shared by the players.
+    pub at: Duration,
+}
+
+/// This struct defines the ruling on a timed claim.
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub struct Ruling {
+    /// This is the claim.
+    pub claim: TimedClaim,
+    /// This is the verdict, or None if the claim was ignored because the player sits out.
+    pub verdict: Option<Verdict>,
+    /// This is the penalty given for the claim, if any.
+    pub penalty: Option<Penalty>,
+}
+
+/// This struct defines a referee over a game of any mode.
+pub struct Arbiter<M: GameMode> {
+    game: M,
+    rules: Rules,
+    sitting_out: Vec<usize>,
+    rng: ChaCha12Rng,
+}
+
+impl<M: GameMode> Arbiter<M> {
+    /// This function referees the game with the rules. Ties settled at random are drawn from the seed.
+    pub fn new(game: M, rules: Rules, seed: u64) -> Self {
+        let players = game.table().players.len();
+        Arbiter {
+            game,
+            rules,
+            sitting_out: vec![0; players],
+            rng: ChaCha12Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// This function returns the game.
+    pub fn game(&self) -> &M {
+        &self.game
+    }
+
+    /// This function returns the game, e.g. to deal it.
+    pub fn game_mut(&mut self) -> &mut M {
+        &mut self.game
+    }
+
+    /// This function returns the rules.
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// This function returns the number of turns the player still sits out.
+    pub fn sitting_out(&self, player: PlayerId) -> usize {
+        self.sitting_out.get(player).copied().unwrap_or(0)
+    }
+
+    /// This function judges claims shouted about the same cards, and returns a ruling for each of them, in the order they were shouted.
+    /// It returns an error if a claim is from no player or on no chance, and nothing is judged then.
+    /// It also returns an error if the game judges a claim otherwise than its cards say, e.g. a mode whose `GameMode::claim_chance` does not match its chances. The claims passed to the game before that one are not undone then.
+    pub fn judge(&mut self, claims: &[TimedClaim]) -> Result<Vec<Ruling>, String> {
+        let mut claims = claims.to_vec();
+        // Claims shouted at the same time are taken in seating order
+        claims.sort_by_key(|c| (c.at, c.player));
+        let mut rights = Vec::new();
+        for claim in claims.iter() {
+            self.game.table().player(claim.player)?;
+            let chances = self.game.chances(claim.player);
+            let cards = chances.get(claim.chance).ok_or_else(|| {
+                format!("Player {} has no chance {}.", claim.player, claim.chance)
+            })?;
+            rights.push(shared_symbol(cards) == Some(claim.symbol));
+        }
+
+        let heard: Vec<bool> = claims
+            .iter()
+            .map(|c| self.sitting_out(c.player) == 0)
+            .collect();
+        let right_at: Vec<usize> = (0..claims.len())
+            .filter(|i| heard[*i] && rights[*i])
+            .collect();
+        let tied: Vec<usize> = match right_at.first() {
+            Some(first) => right_at
+                .iter()
+                .copied()
+                .filter(|i| claims[*i].at <= claims[*first].at + self.rules.window)
+                .collect(),
+            None => Vec::new(),
+        };
+        let winner = match self.rules.tie_break {
+            TieBreak::Earliest => tied.first().copied(),
+            TieBreak::Random => tied.choose(&mut self.rng).copied(),
+            TieBreak::Void if tied.len() > 1 => None,
+            TieBreak::Void => tied.first().copied(),
+        };
+
+        // Wrong claims go to the game before the winner's, so that they are judged on the same cards
+        let mut rulings = Vec::new();
+        for (i, claim) in claims.iter().enumerate() {
+            let as_claim = Claim {
+                player: claim.player,
+                symbol: claim.symbol,
+            };
+            let verdict = match (heard[i], rights[i]) {
+                (false, _) => None,
+                (true, false) => {
+                    self.expect(claim, Verdict::Wrong(as_claim))?;
+                    Some(Verdict::Wrong(as_claim))
+                }
+                (true, true) => Some(Verdict::Late(as_claim)),
+            };
+            rulings.push(Ruling {
+                claim: *claim,
+                verdict,
+                penalty: None,
+            });
+        }
+        if let Some(winner) = winner {
+            let claim = claims[winner];
+            let verdict = Verdict::Correct(Claim {
+                player: claim.player,
+                symbol: claim.symbol,
+            });
+            self.expect(&claim, verdict)?;
+            rulings[winner].verdict = Some(verdict);
+            for turns in self.sitting_out.iter_mut() {
+                *turns = turns.saturating_sub(1);
+            }
+        }
+
+        let penalty = self.rules.penalty;
+        if penalty != Penalty::Nothing {
+            for ruling in rulings
+                .iter_mut()
+                .filter(|r| matches!(r.verdict, Some(Verdict::Wrong(_))))
+            {
+                let player = ruling.claim.player;
+                self.game.penalise(player, penalty)?;
+                if let Penalty::SitOut(turns) = penalty {
+                    self.sitting_out[player] = self.sitting_out[player].max(turns);
+                }
+                ruling.penalty = Some(penalty);
+            }
+        }
+        Ok(rulings)
+    }
+
+    /// This function passes a claim on to the game, and checks that the game judges it as the arbiter did.
+    fn expect(&mut self, claim: &TimedClaim, verdict: Verdict) -> Result<(), String> {
+        let judged = self
+            .game
+            .claim_chance(claim.player, claim.chance, claim.symbol)?;
+        match judged == verdict {
+            true => Ok(()),
+            false => Err(format!(
+                "The game judged {:?} where the cards say {:?}.",
+                judged, verdict
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::registry::{ModeOptions, Registry};
+    use crate::game::tower::Tower;
+    use crate::game::{Game, Phase};
+    use crate::SpotItDeck;
+
+    fn game() -> Game {
+        let mut game = Game::new(
+            SpotItDeck::generate_by_prime(3).unwrap(),
+            &["Ann", "Bob", "Cat"],
+        )
+        .unwrap();
+        game.deal().unwrap();
+        game
+    }
+
+    fn right<M: GameMode>(arbiter: &Arbiter<M>, player: PlayerId, at: u64) -> TimedClaim {
+        // The first chance with a match, e.g. the first three cards of a triplet
+        let (chance, symbol) = arbiter
+            .game()
+            .chances(player)
+            .iter()
+            .enumerate()
+            .find_map(|(i, cards)| Some((i, shared_symbol(cards)?)))
+            .unwrap();
+        TimedClaim {
+            player,
+            chance,
+            symbol,
+            at: Duration::from_millis(at),
+        }
+    }
+
+    fn wrong<M: GameMode>(arbiter: &Arbiter<M>, player: PlayerId, at: u64) -> TimedClaim {
+        let chances = arbiter.game().chances(player);
+        let symbol = *chances[0][0]
+            .0
+            .iter()
+            .find(|s| !chances[0][1].0.contains(s))
+            .unwrap();
+        TimedClaim {
+            player,
+            chance: 0,
+            symbol,
+            at: Duration::from_millis(at),
+        }
+    }
+
+    #[test]
+    fn earliest_right_claim_takes_the_match() {
+        let mut arbiter = Arbiter::new(game(), Rules::default(), 1);
+        // Bob shouts last but is given first
+        let claims = [
+            right(&arbiter, 1, 900),
+            wrong(&arbiter, 2, 100),
+            right(&arbiter, 0, 500),
+        ];
+        let rulings = arbiter.judge(&claims).unwrap();
+        let order: Vec<PlayerId> = rulings.iter().map(|r| r.claim.player).collect();
+        assert_eq!(order, vec![2, 0, 1]);
+        assert!(matches!(rulings[0].verdict, Some(Verdict::Wrong(_))));
+        assert!(matches!(rulings[1].verdict, Some(Verdict::Correct(_))));
+        assert!(matches!(rulings[2].verdict, Some(Verdict::Late(_))));
+        assert_eq!(rulings[0].penalty, None);
+        assert_eq!(arbiter.game().table().players[0].score, 1);
+        assert_eq!(arbiter.game().table().draw_pile.len(), 11);
+    }
+    #[test]
+    fn claims_at_the_same_time_go_to_the_first_in_seating_order() {
+        let mut arbiter = Arbiter::new(game(), Rules::default(), 1);
+        let claims = [right(&arbiter, 2, 100), right(&arbiter, 0, 100)];
+        let rulings = arbiter.judge(&claims).unwrap();
+        assert_eq!(rulings[0].claim.player, 0);
+        assert!(matches!(rulings[0].verdict, Some(Verdict::Correct(_))));
+        assert!(matches!(rulings[1].verdict, Some(Verdict::Late(_))));
+        assert_eq!(arbiter.game().table().players[0].score, 1);
+        assert_eq!(arbiter.game().table().players[2].score, 0);
+    }
+    #[test]
+    fn ties_are_settled_by_the_rules() {
+        let rules = Rules {
+            tie_break: TieBreak::Void,
+            ..Rules::default()
+        };
+        let mut arbiter = Arbiter::new(game(), rules, 1);
+        let claims = [right(&arbiter, 0, 100), right(&arbiter, 1, 200)];
+        let rulings = arbiter.judge(&claims).unwrap();
+        assert!(rulings
+            .iter()
+            .all(|r| matches!(r.verdict, Some(Verdict::Late(_)))));
+        assert_eq!(arbiter.game().table().draw_pile.len(), 12);
+        // Outside the window, there is no tie
+        let claims = [right(&arbiter, 0, 100), right(&arbiter, 1, 300)];
+        assert!(matches!(
+            arbiter.judge(&claims).unwrap()[0].verdict,
+            Some(Verdict::Correct(_))
+        ));
+
+        let rules = Rules {
+            tie_break: TieBreak::Random,
+            window: Duration::from_secs(1),
+            ..Rules::default()
+        };
+        let winners = |seed| {
+            let mut arbiter = Arbiter::new(game(), rules, seed);
+            let mut winners = Vec::new();
+            while arbiter.game().phase() == Phase::Playing {
+                let claims: Vec<TimedClaim> = (0..3).map(|p| right(&arbiter, p, 0)).collect();
+                let rulings = arbiter.judge(&claims).unwrap();
+                winners.extend(
+                    rulings
+                        .iter()
+                        .filter(|r| matches!(r.verdict, Some(Verdict::Correct(_))))
+                        .map(|r| r.claim.player),
+                );
+            }
+            winners
+        };
+        assert_eq!(winners(7), winners(7));
+        assert_eq!(winners(7).len(), 12);
+        assert!((0..3).all(|p| winners(7).contains(&p)));
+    }
+    #[test]
+    fn wrong_claims_are_penalised() {
+        let rules = |penalty| Rules {
+            penalty,
+            ..Rules::default()
+        };
+        let mut arbiter = Arbiter::new(game(), rules(Penalty::Deduct(2)), 1);
+        let rulings = arbiter.judge(&[wrong(&arbiter, 1, 0)]).unwrap();
+        assert_eq!(rulings[0].penalty, Some(Penalty::Deduct(2)));
+        assert_eq!(arbiter.game().table().players[1].score, -2);
+
+        let mut tower =
+            Tower::new(SpotItDeck::generate_by_prime(3).unwrap(), &["Ann", "Bob"]).unwrap();
+        tower.deal().unwrap();
+        let mut arbiter = Arbiter::new(tower, rules(Penalty::LoseCard), 1);
+        let bottom = arbiter.game().table().players[0].top_card().cloned();
+        arbiter.judge(&[wrong(&arbiter, 0, 0)]).unwrap();
+        assert!(arbiter.game().table().players[0].pile.is_empty());
+        assert_eq!(arbiter.game().table().draw_pile.first().cloned(), bottom);
+        // Without a card, there is nothing to claim on
+        assert!(arbiter.judge(&[right(&arbiter, 1, 0)]).is_ok());
+        assert!(arbiter.game().chances(0).is_empty());
+
+        // A card lost in the classic game goes with its point, and does not come back into play once the game is over
+        let mut game =
+            Game::new(SpotItDeck::generate_by_prime(1).unwrap(), &["Ann", "Bob"]).unwrap();
+        game.deal().unwrap();
+        let mut arbiter = Arbiter::new(game, rules(Penalty::LoseCard), 1);
+        arbiter.judge(&[right(&arbiter, 1, 0)]).unwrap();
+        arbiter
+            .judge(&[wrong(&arbiter, 1, 0), right(&arbiter, 0, 500)])
+            .unwrap();
+        let table = arbiter.game().table();
+        assert_eq!(arbiter.game().phase(), Phase::Finished);
+        assert!(table.draw_pile.is_empty());
+        assert!(table.players[1].pile.is_empty());
+        assert_eq!(table.players[1].score, 0);
+        assert_eq!(arbiter.game().winners(), vec![0]);
+    }
+    #[test]
+    fn lost_cards_count_against_the_player_in_every_mode() {
+        let registry = Registry::builtin();
+        for name in registry.names() {
+            let names: &[&str] = match name {
+                "solo" => &["Ann"],
+                _ => &["Ann", "Bob"],
+            };
+            let options = ModeOptions { rounds: 2 };
+            // The smallest deck each mode can be played with
+            let order = match name {
+                "triplet" => 3,
+                _ => 2,
+            };
+            let deck = SpotItDeck::generate_by_prime(order).unwrap();
+            let mut game = registry.start(name, deck, names, &options).unwrap();
+            game.deal().unwrap();
+            let rules = Rules {
+                penalty: Penalty::LoseCard,
+                ..Rules::default()
+            };
+            let mut arbiter = Arbiter::new(game, rules, 1);
+            // The last player claims wrong three times, e.g. as many as their cards in "The Well"
+            let last = names.len() - 1;
+            for _ in 0..3 {
+                if arbiter.game().chances(last).is_empty() {
+                    break;
+                }
+                arbiter.judge(&[wrong(&arbiter, last, 0)]).unwrap();
+            }
+            // Then Ann takes every match to the end
+            let mut turns = 0;
+            while arbiter.game().phase() != Phase::Finished {
+                turns += 1;
+                assert!(turns < 100, "{} does not finish", name);
+                if arbiter.game().phase() == Phase::Setup {
+                    arbiter.game_mut().deal().unwrap();
+                }
+                assert!(!arbiter.game().chances(0).is_empty(), "{} hangs", name);
+                arbiter.judge(&[right(&arbiter, 0, 0)]).unwrap();
+            }
+            if names.len() > 1 {
+                assert_eq!(arbiter.game().winners(), vec![0], "{}", name);
+            }
+        }
+    }
+    #[test]
+    fn players_sitting_out_are_ignored() {
+        let rules = Rules {
+            penalty: Penalty::SitOut(2),
+            ..Rules::default()
+        };
+        let mut arbiter = Arbiter::new(game(), rules, 1);
+        arbiter.judge(&[wrong(&arbiter, 0, 0)]).unwrap();
+        assert_eq!(arbiter.sitting_out(0), 2);
+        // A wrong claim changes no cards, so it is no turn
+        arbiter.judge(&[wrong(&arbiter, 1, 0)]).unwrap();
+        assert_eq!(arbiter.sitting_out(0), 2);
+        for turn in 0..2 {
+            let claims = [right(&arbiter, 0, 0), right(&arbiter, 2, 500)];
+            let rulings = arbiter.judge(&claims).unwrap();
+            assert_eq!(rulings[0].verdict, None);
+            assert!(matches!(rulings[1].verdict, Some(Verdict::Correct(_))));
+            assert_eq!(arbiter.sitting_out(0), 1 - turn);
+        }
+        let rulings = arbiter.judge(&[right(&arbiter, 0, 0)]).unwrap();
+        assert!(matches!(rulings[0].verdict, Some(Verdict::Correct(_))));
+    }
+    #[test]
+    fn bad_claims_are_refused() {
+        let mut arbiter = Arbiter::new(game(), Rules::default(), 1);
+        let claim = right(&arbiter, 0, 0);
+        assert!(arbiter.judge(&[TimedClaim { player: 5, ..claim }]).is_err());
+        assert!(arbiter.judge(&[TimedClaim { chance: 1, ..claim }]).is_err());
+        assert_eq!(arbiter.game().table().draw_pile.len(), 12);
+    }
+}
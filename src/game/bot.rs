@@ -0,0 +1,341 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Computer opponents for SpotIt! in `cardgame` crate.
+//!
+//! A `Bot` is a `Spotter`, so it plays any mode which implements `GameMode`, next to other bots or people.
+//! It always finds a match, but only after a reaction delay drawn from a `Reaction` distribution, and it names a wrong symbol now and then, as set by its error rate.
+//! A `Skill` level picks both for players who do not want to tune them.
+//!
+//! Bots draw their delays and mistakes from their own random number generator, seeded when they are created, so a game between bots plays out the same every time.
+//! The generator is saved with the bot, so a saved game goes on the same way once resumed.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+
+use super::{shared_symbol, Sighting, Spotter};
+use crate::spotitcard::SpotItCard;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use strum::IntoEnumIterator;
+
+/// This enum defines how long a bot takes to spot a match.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum Reaction {
+    /// This is always the same delay.
+    Fixed(Duration),
+    /// This is any delay between the two, all as likely.
+    Uniform { min: Duration, max: Duration },
+    /// This is a delay around the mean, more or less by the deviation, and never below zero.
+    Normal { mean: Duration, deviation: Duration },
+}
+
+impl Reaction {
+    /// This function draws a delay.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Duration {
+        match *self {
+            Reaction::Fixed(delay) => delay,
+            Reaction::Uniform { min, max } if min < max => rng.gen_range(min..=max),
+            Reaction::Uniform { min, .. } => min,
+            Reaction::Normal { mean, deviation } => {
+                // Box-Muller transform of two uniform numbers into a standard normal one
+                let (u, v): (f64, f64) = (1.0 - rng.gen::<f64>(), rng.gen());
+                let z = (-2.0 * u.ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos();
+                Duration::from_secs_f64((mean.as_secs_f64() + z * deviation.as_secs_f64()).max(0.0))
+            }
+        }
+    }
+}
+
+/// This enum defines the skill levels of bots.
+#[derive(Debug, PartialEq, Clone, Copy, Eq, strum_macros::EnumIter)]
+pub enum Skill {
+    /// This is a slow bot which often gets it wrong.
+    Beginner,
+    /// This is a bot about as quick as a casual player.
+    Intermediate,
+    /// This is a quick bot which rarely gets it wrong.
+    Expert,
+}
+
+impl Skill {
+    /// This function returns how long a bot of this skill takes to spot a match.
+    pub fn reaction(&self) -> Reaction {
+        let (mean, deviation) = match self {
+            Skill::Beginner => (3000, 1000),
+            Skill::Intermediate => (1800, 600),
+            Skill::Expert => (900, 250),
+        };
+        Reaction::Normal {
+            mean: Duration::from_millis(mean),
+            deviation: Duration::from_millis(deviation),
+        }
+    }
+
+    /// This function returns the share of claims a bot of this skill gets wrong, from 0 to 1.
+    pub fn error_rate(&self) -> f64 {
+        match self {
+            Skill::Beginner => 0.2,
+            Skill::Intermediate => 0.08,
+            Skill::Expert => 0.02,
+        }
+    }
+}
+
+impl fmt::Display for Skill {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for Skill {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Skill::iter()
+            .find(|skill| skill.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("Unknown skill level: {}.", s))
+    }
+}
+
+/// This struct defines a computer opponent.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Bot {
+    name: String,
+    reaction: Reaction,
+    error_rate: f64,
+    // This is the generator behind `StdRng`, which can be saved
+    rng: ChaCha12Rng,
+}
+
+impl Bot {
+    /// This function creates a bot of the skill level, whose delays and mistakes are drawn from the seed.
+    pub fn new(name: &str, skill: Skill, seed: u64) -> Self {
+        Bot {
+            name: name.to_string(),
+            reaction: skill.reaction(),
+            error_rate: skill.error_rate(),
+            rng: ChaCha12Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// This function sets how long the bot takes to spot a match.
+    pub fn with_reaction(mut self, reaction: Reaction) -> Self {
+        self.reaction = reaction;
+        self
+    }
+
+    /// This function sets the share of claims the bot gets wrong, kept between 0 and 1.
+    pub fn with_error_rate(mut self, error_rate: f64) -> Self {
+        self.error_rate = error_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// This function returns how long the bot takes to spot a match.
+    pub fn reaction(&self) -> Reaction {
+        self.reaction
+    }
+
+    /// This function returns the share of claims the bot gets wrong.
+    pub fn error_rate(&self) -> f64 {
+        self.error_rate
+    }
+}
+
+impl Spotter for Bot {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// A bot picks one of the chances with a match at random. A mistake names another symbol of the first card of that chance.
+    fn spot(&mut self, chances: &[Vec<&SpotItCard>]) -> Option<Sighting> {
+        let matches: Vec<_> = chances
+            .iter()
+            .enumerate()
+            .filter_map(|(i, cards)| Some((i, shared_symbol(cards)?)))
+            .collect();
+        let (chance, symbol) = *matches.choose(&mut self.rng)?;
+        let after = self.reaction.sample(&mut self.rng);
+        let mut symbol = symbol;
+        if self.rng.gen_bool(self.error_rate) {
+            let mut wrong: Vec<_> = chances[chance][0]
+                .0
+                .iter()
+                .filter(|s| **s != symbol)
+                .copied()
+                .collect();
+            // Sets have no order, so sort to keep the choice reproducible
+            wrong.sort();
+            symbol = wrong.choose(&mut self.rng).copied().unwrap_or(symbol);
+        }
+        Some(Sighting {
+            chance,
+            symbol,
+            after,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::ManualClock;
+    use crate::game::hot_potato::HotPotato;
+    use crate::game::poisoned_gift::PoisonedGift;
+    use crate::game::solo::Solo;
+    use crate::game::tower::Tower;
+    use crate::game::triplet::Triplet;
+    use crate::game::well::Well;
+    use crate::game::{play_game, play_turn, Game, GameMode, Phase, Turn, Verdict, MAX_TURNS};
+    use crate::{Deck, SpotItDeck};
+    use rand::rngs::StdRng;
+
+    fn bots(skills: &[Skill], seed: u64) -> Vec<Box<dyn Spotter>> {
+        skills
+            .iter()
+            .enumerate()
+            .map(|(i, skill)| {
+                Box::new(Bot::new(&format!("Bot {}", i), *skill, seed + i as u64))
+                    as Box<dyn Spotter>
+            })
+            .collect()
+    }
+
+    fn play_out<M: GameMode>(mode: &mut M, spotters: &mut [Box<dyn Spotter>]) -> Vec<Turn> {
+        let mut turns = Vec::new();
+        while let Some(turn) = play_turn(mode, spotters).unwrap() {
+            turns.push(turn);
+        }
+        turns
+    }
+
+    #[test]
+    fn reactions_are_drawn_within_their_distribution() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let second = Duration::from_secs(1);
+        assert_eq!(Reaction::Fixed(second).sample(&mut rng), second);
+        let uniform = Reaction::Uniform {
+            min: second,
+            max: 2 * second,
+        };
+        let normal = Skill::Expert.reaction();
+        let mut total = Duration::ZERO;
+        for _ in 0..1000 {
+            let delay = uniform.sample(&mut rng);
+            assert!(second <= delay && delay <= 2 * second);
+            total += normal.sample(&mut rng);
+        }
+        let mean = total / 1000;
+        assert!(Duration::from_millis(850) < mean && mean < Duration::from_millis(950));
+    }
+    #[test]
+    fn skill_levels_parse_and_get_better() {
+        assert_eq!("Expert".parse::<Skill>(), Ok(Skill::Expert));
+        assert!("godlike".parse::<Skill>().is_err());
+        let skills: Vec<Skill> = Skill::iter().collect();
+        for pair in skills.windows(2) {
+            assert!(pair[0].error_rate() > pair[1].error_rate());
+        }
+    }
+    #[test]
+    fn bot_spots_the_match_after_its_delay() {
+        let deck = SpotItDeck::default();
+        let chances = [vec![&deck.cards[0], &deck.cards[1]]];
+        let delay = Duration::from_millis(700);
+        let mut bot = Bot::new("Bot", Skill::Beginner, 7)
+            .with_reaction(Reaction::Fixed(delay))
+            .with_error_rate(0.0);
+        assert_eq!(bot.name(), "Bot");
+        let sighting = bot.spot(&chances).unwrap();
+        assert_eq!(sighting.chance, 0);
+        assert_eq!(Some(sighting.symbol), shared_symbol(&chances[0]));
+        assert_eq!(sighting.after, delay);
+        assert_eq!(bot.spot(&[]), None);
+
+        let mut bot = bot.with_error_rate(2.0);
+        assert_eq!(bot.error_rate(), 1.0);
+        assert_ne!(
+            Some(bot.spot(&chances).unwrap().symbol),
+            shared_symbol(&chances[0])
+        );
+    }
+    #[test]
+    fn same_seeds_play_the_same_game() {
+        let play = |seed| {
+            let mut deck = SpotItDeck::generate_by_prime(3).unwrap();
+            deck.cards.shuffle(&mut StdRng::seed_from_u64(5));
+            let mut game = Game::new(deck, &["A", "B", "C"]).unwrap();
+            game.deal().unwrap();
+            let turns = play_out(&mut game, &mut bots(&[Skill::Beginner; 3], seed));
+            assert_eq!(game.phase(), Phase::Finished);
+            turns
+        };
+        assert_eq!(play(11), play(11));
+        assert_ne!(play(11), play(12));
+    }
+    #[test]
+    fn quicker_bots_win_more() {
+        let mut game = Game::new(SpotItDeck::default(), &["Slow", "Quick"]).unwrap();
+        game.deal().unwrap();
+        let turns = play_out(&mut game, &mut bots(&[Skill::Beginner, Skill::Expert], 3));
+        assert_eq!(game.phase(), Phase::Finished);
+        assert!(turns.iter().any(|t| matches!(t.verdict, Verdict::Wrong(_))));
+        assert_eq!(game.winners(), vec![1]);
+    }
+    #[test]
+    fn bots_play_every_mode() {
+        let names = ["A", "B", "C"];
+        let skills = [Skill::Intermediate; 3];
+        let deck = || SpotItDeck::generate_by_prime(3).unwrap();
+
+        let mut tower = Tower::new(deck(), &names).unwrap();
+        tower.deal().unwrap();
+        play_out(&mut tower, &mut bots(&skills, 1));
+        assert_eq!(tower.phase(), Phase::Finished);
+
+        let mut well = Well::new(deck(), &names).unwrap();
+        well.deal().unwrap();
+        play_out(&mut well, &mut bots(&skills, 1));
+        assert_eq!(well.phase(), Phase::Finished);
+
+        let mut gift = PoisonedGift::new(deck(), &names).unwrap();
+        gift.deal().unwrap();
+        play_out(&mut gift, &mut bots(&skills, 1));
+        assert_eq!(gift.phase(), Phase::Finished);
+
+        let mut triplet = Triplet::new(SpotItDeck::default(), &names).unwrap();
+        triplet.deal().unwrap();
+        let turns = play_out(&mut triplet, &mut bots(&skills, 1));
+        assert_eq!(triplet.phase(), Phase::Finished);
+        assert!(turns
+            .iter()
+            .any(|t| matches!(t.verdict, Verdict::Correct(_))));
+
+        // Every round is dealt in turn
+        let mut potato = HotPotato::new(deck(), &names, 2).unwrap();
+        play_game(&mut potato, &mut bots(&skills, 1), MAX_TURNS).unwrap();
+        assert_eq!(potato.phase(), Phase::Finished);
+        assert_eq!(potato.results().len(), 2);
+        let mut tower = Tower::new(deck(), &names).unwrap();
+        assert_eq!(
+            play_game(&mut tower, &mut bots(&skills, 1), 3)
+                .unwrap()
+                .len(),
+            3
+        );
+        assert_eq!(tower.phase(), Phase::Playing);
+
+        let clock = ManualClock::new();
+        let mut solo = Solo::new(deck(), "A", &clock).unwrap();
+        solo.deal().unwrap();
+        let mut spotters = bots(&[Skill::Expert], 1);
+        while let Some(turn) = play_turn(&mut solo, &mut spotters).unwrap() {
+            clock.advance(turn.sighting.after);
+        }
+        assert_eq!(solo.phase(), Phase::Finished);
+        assert_eq!(solo.report().matches, 12);
+    }
+}
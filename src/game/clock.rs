@@ -0,0 +1,101 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Clocks to time players of SpotIt! in `cardgame` crate.
+//!
+//! Modes which time players read the time from a `Clock` rather than from the system, so that tests and replays can drive the time by hand.
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// This trait defines a clock, i.e. a source of the time elapsed since some fixed start.
+pub trait Clock {
+    /// This function returns the time elapsed since the start of the clock.
+    fn now(&self) -> Duration;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+}
+
+/// This struct defines the clock on the wall, started when it is created.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    /// This function starts a clock.
+    pub fn new() -> Self {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// This struct defines a clock which only moves when told to, e.g. in tests.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    now: Cell<Duration>,
+}
+
+impl ManualClock {
+    /// This function creates a clock stopped at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// This function moves the clock forward.
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+
+    /// This function sets the clock to the time given.
+    pub fn set(&self, now: Duration) {
+        self.now.set(now);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_moves_only_when_told() {
+        let clock = ManualClock::new();
+        assert_eq!(clock.now(), Duration::ZERO);
+        clock.advance(Duration::from_millis(250));
+        clock.advance(Duration::from_millis(250));
+        let borrowed: &dyn Clock = &&clock;
+        assert_eq!(borrowed.now(), Duration::from_millis(500));
+        clock.set(Duration::from_secs(3));
+        assert_eq!(clock.now(), Duration::from_secs(3));
+    }
+    #[test]
+    fn system_clock_moves_forward() {
+        let clock = SystemClock::new();
+        let before = clock.now();
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(clock.now() > before);
+    }
+}
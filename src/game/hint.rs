@@ -0,0 +1,233 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Hints for players stuck on two cards in `cardgame` crate.
+//!
+//! `Hints` narrows the search for the symbol two cards share, one step at a time:
+//! first a fraction of the symbols which do not match are ruled out, then the kind of fruit or the first letter of the match is named, whichever rules out more, and last the match is revealed.
+//!
+//! Every hint taken costs `HINT_COST` points, and is counted against the player on the table. See `Table::take_hint`.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+
+use super::common_symbol;
+use crate::spotitcard::{SpotItCard, SpotItSymbol, SymbolCategory};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+
+/// This is the points a hint costs.
+pub const HINT_COST: i32 = 1;
+
+/// This is the share of the symbols which do not match ruled out by the first hint, unless set otherwise.
+pub const DEFAULT_FRACTION: f64 = 0.5;
+
+/// This enum defines a hint.
+#[derive(Debug, PartialEq, Clone, Eq)]
+pub enum Hint {
+    /// This is symbols which are not the match.
+    RuleOut(Vec<SpotItSymbol>),
+    /// This is the kind of fruit of the match.
+    Category(SymbolCategory),
+    /// This is the first letter of the name of the match.
+    Initial(char),
+    /// This is the match.
+    Reveal(SpotItSymbol),
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Hint::RuleOut(symbols) => {
+                let names: Vec<String> = symbols.iter().map(|s| s.to_string()).collect();
+                write!(f, "It is not {}.", names.join(", "))
+            }
+            Hint::Category(category) => {
+                let article = match category.to_string().starts_with(['a', 'e', 'i', 'o', 'u']) {
+                    true => "an",
+                    false => "a",
+                };
+                write!(f, "It is {} {}.", article, category)
+            }
+            Hint::Initial(letter) => write!(f, "It starts with {}.", letter),
+            Hint::Reveal(symbol) => write!(f, "It is {}.", symbol),
+        }
+    }
+}
+
+/// This struct defines the hints for two cards, given one after the other.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Hints {
+    cards: [SpotItCard; 2],
+    answer: SpotItSymbol,
+    // The symbols which do not match, in the order they are ruled out
+    others: Vec<SpotItSymbol>,
+    remaining: Vec<SpotItSymbol>,
+    fraction: f64,
+    given: usize,
+}
+
+impl Hints {
+    /// This function prepares the hints for the two cards. The rng picks which symbols are ruled out first.
+    /// It returns an error if the cards do not share exactly one symbol.
+    pub fn new<R: Rng>(card: &SpotItCard, other: &SpotItCard, rng: &mut R) -> Result<Self, String> {
+        let answer = common_symbol(card, other)
+            .ok_or_else(|| "The cards do not share exactly one symbol.".to_string())?;
+        let mut remaining: Vec<SpotItSymbol> = card.0.union(&other.0).copied().collect();
+        remaining.sort();
+        let mut others: Vec<SpotItSymbol> =
+            remaining.iter().copied().filter(|s| *s != answer).collect();
+        others.shuffle(rng);
+        Ok(Hints {
+            cards: [card.clone(), other.clone()],
+            answer,
+            others,
+            remaining,
+            fraction: DEFAULT_FRACTION,
+            given: 0,
+        })
+    }
+
+    /// This function sets the share of the symbols which do not match ruled out by the first hint, from 0 to 1.
+    pub fn with_fraction(mut self, fraction: f64) -> Self {
+        self.fraction = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// This function returns true if the hints are for the two cards, in either order.
+    pub fn is_for(&self, card: &SpotItCard, other: &SpotItCard) -> bool {
+        let [a, b] = &self.cards;
+        (a == card && b == other) || (a == other && b == card)
+    }
+
+    /// This function returns the number of hints given so far.
+    pub fn given(&self) -> usize {
+        self.given
+    }
+
+    /// This function returns the symbols which may still be the match after the hints given so far.
+    pub fn remaining(&self) -> &[SpotItSymbol] {
+        &self.remaining
+    }
+
+    /// This function gives the next hint, which narrows the search further than the one before. Once the match is revealed, it is revealed again.
+    pub fn next_hint(&mut self) -> Hint {
+        self.given += 1;
+        let hint = match self.given {
+            1 => {
+                let count = (self.others.len() as f64 * self.fraction).ceil() as usize;
+                let mut ruled_out: Vec<SpotItSymbol> = self.others[..count].to_vec();
+                ruled_out.sort();
+                Hint::RuleOut(ruled_out)
+            }
+            2 => {
+                let category = self.answer.category();
+                let initial = self.answer.initial();
+                let by_category = self
+                    .remaining
+                    .iter()
+                    .filter(|s| s.category() == category)
+                    .count();
+                let by_initial = self
+                    .remaining
+                    .iter()
+                    .filter(|s| s.initial() == initial)
+                    .count();
+                match by_initial < by_category {
+                    true => Hint::Initial(initial),
+                    false => Hint::Category(category),
+                }
+            }
+            _ => Hint::Reveal(self.answer),
+        };
+        self.remaining.retain(|s| match &hint {
+            Hint::RuleOut(ruled_out) => !ruled_out.contains(s),
+            Hint::Category(category) => s.category() == *category,
+            Hint::Initial(initial) => s.initial() == *initial,
+            Hint::Reveal(answer) => s == answer,
+        });
+        hint
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Table;
+    use crate::{Deck, SpotItDeck};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn hints_narrow_down_to_the_match() {
+        let deck = SpotItDeck::default();
+        let (card, other) = (&deck.cards[0], &deck.cards[1]);
+        let answer = common_symbol(card, other).unwrap();
+        let mut hints = Hints::new(card, other, &mut StdRng::seed_from_u64(1)).unwrap();
+        assert!(hints.is_for(other, card));
+        assert!(!hints.is_for(card, &deck.cards[2]));
+        assert_eq!(hints.remaining().len(), 15);
+
+        let Hint::RuleOut(ruled_out) = hints.next_hint() else {
+            panic!("The first hint rules symbols out");
+        };
+        // Half of the 14 symbols which do not match
+        assert_eq!(ruled_out.len(), 7);
+        assert!(!ruled_out.contains(&answer));
+        assert_eq!(hints.remaining().len(), 8);
+
+        let second = hints.next_hint();
+        assert!(matches!(second, Hint::Category(_) | Hint::Initial(_)));
+        assert!(hints.remaining().contains(&answer));
+        assert!(hints.remaining().len() < 8);
+
+        assert_eq!(hints.next_hint(), Hint::Reveal(answer));
+        assert_eq!(hints.remaining(), [answer]);
+        assert_eq!(hints.next_hint(), Hint::Reveal(answer));
+        assert_eq!(hints.given(), 4);
+        assert!(Hints::new(card, card, &mut StdRng::seed_from_u64(1)).is_err());
+    }
+    #[test]
+    fn fraction_sets_how_many_are_ruled_out() {
+        let deck = SpotItDeck::generate_by_prime(2).unwrap();
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut hints = Hints::new(&deck.cards[0], &deck.cards[1], &mut rng)
+            .unwrap()
+            .with_fraction(2.0);
+        // Cards of 3 symbols show 5 symbols between them, and all 4 which do not match are ruled out
+        let Hint::RuleOut(ruled_out) = hints.next_hint() else {
+            panic!("The first hint rules symbols out");
+        };
+        assert_eq!(ruled_out.len(), 4);
+        assert_eq!(hints.remaining().len(), 1);
+    }
+    #[test]
+    fn hints_are_shown_in_words() {
+        assert_eq!(
+            Hint::RuleOut(vec![SpotItSymbol::Apple, SpotItSymbol::Fig]).to_string(),
+            "It is not Apple, Fig."
+        );
+        assert_eq!(
+            Hint::Category(SymbolCategory::OrchardFruit).to_string(),
+            "It is an orchard fruit."
+        );
+        assert_eq!(
+            Hint::Category(SymbolCategory::Berry).to_string(),
+            "It is a berry."
+        );
+        assert_eq!(Hint::Initial('B').to_string(), "It starts with B.");
+        assert_eq!(
+            Hint::Reveal(SpotItSymbol::ChicoFruit).to_string(),
+            "It is Chico Fruit."
+        );
+    }
+    #[test]
+    fn hints_cost_points() {
+        let mut table = Table::new(SpotItDeck::default(), &["Ann"]).unwrap();
+        table.take_hint(0).unwrap();
+        table.take_hint(0).unwrap();
+        assert_eq!(table.players[0].hints, 2);
+        assert_eq!(table.players[0].score, -2 * HINT_COST);
+        assert!(table.take_hint(1).is_err());
+    }
+}
@@ -0,0 +1,363 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! "Hot Potato" mode of SpotIt! in `cardgame` crate.
+//!
+//! The game is played over several rounds, five in the official format.
+//! At the start of a round every player is dealt one card face down, and all players flip their card at the same time.
+//! A player who spots a symbol shared by their top card and an opponent's top card names it and passes all their cards onto that opponent's stack.
+//! The round ends when one player holds every card of the round. That player loses the round and sets the cards aside, and the player who set aside the fewest cards over all rounds wins.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt! Rules`]: https://www.ultraboardgames.com/spot-it/game-rules.php
+
+use super::{Claim, GameMode, Phase, Pile, PlayerId, Table, Verdict};
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use crate::SpotItDeck;
+use serde::{Deserialize, Serialize};
+
+/// This is the number of rounds in the official format.
+pub const OFFICIAL_ROUNDS: usize = 5;
+
+/// This struct defines the result of a round.
+#[derive(Debug, PartialEq, Clone, Eq, Serialize, Deserialize)]
+pub struct RoundResult {
+    /// This is the number of the round, starting from 1.
+    pub round: usize,
+    /// This is the number of cards every player held at the end of the round, in seating order.
+    pub cards: Vec<usize>,
+    /// This is the player who held the cards, and so lost the round.
+    pub loser: PlayerId,
+}
+
+/// This struct defines a game of "Hot Potato".
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct HotPotato {
+    table: Table,
+    phase: Phase,
+    rounds: usize,
+    results: Vec<RoundResult>,
+}
+
+impl HotPotato {
+    /// This function seats the players for a game of the given number of rounds, ready to deal the first round.
+    /// It returns an error if there are fewer than two players or not enough cards to deal every round.
+    pub fn new(deck: SpotItDeck, names: &[&str], rounds: usize) -> Result<Self, String> {
+        if names.len() < 2 {
+            return Err("At least two players are needed.".to_string());
+        }
+        if rounds == 0 {
+            return Err("At least one round is needed.".to_string());
+        }
+        if deck.cards.len() < names.len() * rounds {
+            return Err(format!(
+                "{} cards are not enough to deal {} rounds to {} players.",
+                deck.cards.len(),
+                rounds,
+                names.len()
+            ));
+        }
+        Ok(HotPotato {
+            table: Table::new(deck, names)?,
+            phase: Phase::Setup,
+            rounds,
+            results: Vec::new(),
+        })
+    }
+
+    /// This function seats the players for the official format of five rounds.
+    pub fn official(deck: SpotItDeck, names: &[&str]) -> Result<Self, String> {
+        Self::new(deck, names, OFFICIAL_ROUNDS)
+    }
+
+    /// This function returns the table.
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    /// This function returns the phase of the game. It is `Setup` between rounds.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// This function returns the number of the round being played or just finished, starting from 1 once dealt.
+    pub fn round(&self) -> usize {
+        match self.phase {
+            Phase::Playing => self.results.len() + 1,
+            _ => self.results.len(),
+        }
+    }
+
+    /// This function returns the number of rounds in the game.
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    /// This function deals the next round, i.e. one card to every player, flipped face up.
+    pub fn deal(&mut self) -> Result<(), String> {
+        if self.phase != Phase::Setup {
+            return Err("A round can only be dealt between rounds.".to_string());
+        }
+        self.table.deal_one_each()?;
+        self.phase = Phase::Playing;
+        Ok(())
+    }
+
+    /// This function judges a claim of the symbol shared by the player's top card and the target's top card.
+    /// A correct claim passes all the player's cards onto the target's stack, which may end the round.
+    pub fn claim(
+        &mut self,
+        player: PlayerId,
+        target: PlayerId,
+        symbol: SpotItSymbol,
+    ) -> Result<Verdict, String> {
+        if self.phase != Phase::Playing {
+            return Err("Claims are only accepted while a round is played.".to_string());
+        }
+        if player == target {
+            return Err("A player cannot pass cards to themselves.".to_string());
+        }
+        self.table.player(player)?;
+        self.table.player(target)?;
+        match self.targets(player).iter().position(|t| *t == target) {
+            Some(chance) => self.claim_chance(player, chance, symbol),
+            // A player without cards has nothing to pass or to match
+            None => Ok(Verdict::Wrong(Claim { player, symbol })),
+        }
+    }
+
+    /// This function ends the round once a single player holds every card, and the game after the last round.
+    fn end_round_if_over(&mut self) {
+        let holders: Vec<PlayerId> = (0..self.table.players.len())
+            .filter(|i| !self.table.players[*i].pile.is_empty())
+            .collect();
+        if holders.len() != 1 {
+            return;
+        }
+        let loser = holders[0];
+        let cards: Vec<usize> = self.table.players.iter().map(|p| p.pile.len()).collect();
+        // The loser sets the cards aside, which counts against them
+        self.table.players[loser].score -= cards[loser] as i32;
+        while self.table.move_top_card(Pile::Player(loser), Pile::Centre) {}
+        self.results.push(RoundResult {
+            round: self.results.len() + 1,
+            cards,
+            loser,
+        });
+        self.phase = match self.results.len() == self.rounds {
+            true => Phase::Finished,
+            false => Phase::Setup,
+        };
+    }
+
+    /// This function returns the top cards of the players, i.e. what everyone looks for a match between.
+    pub fn top_cards(&self) -> Vec<Option<&SpotItCard>> {
+        self.table.players.iter().map(|p| p.top_card()).collect()
+    }
+
+    /// This function returns the results of the finished rounds.
+    pub fn results(&self) -> &[RoundResult] {
+        &self.results
+    }
+
+    /// This function returns the number of cards every player has set aside over all finished rounds, in seating order.
+    pub fn totals(&self) -> Vec<usize> {
+        let mut totals = vec![0; self.table.players.len()];
+        for result in self.results.iter() {
+            totals[result.loser] += result.cards[result.loser];
+        }
+        totals
+    }
+
+    /// This function returns the winners once the game has finished, i.e. the players who set aside the fewest cards.
+    pub fn winners(&self) -> Vec<PlayerId> {
+        GameMode::winners(self)
+    }
+}
+
+impl HotPotato {
+    /// This function returns the players the player can pass their cards to, i.e. the others still holding cards, in seating order.
+    fn targets(&self, player: PlayerId) -> Vec<PlayerId> {
+        match self.phase == Phase::Playing && self.table.top_card(Pile::Player(player)).is_some() {
+            true => (0..self.table.players.len())
+                .filter(|t| *t != player && !self.table.players[*t].pile.is_empty())
+                .collect(),
+            false => Vec::new(),
+        }
+    }
+}
+
+impl GameMode for HotPotato {
+    fn table(&self) -> &Table {
+        &self.table
+    }
+
+    fn table_mut(&mut self) -> &mut Table {
+        &mut self.table
+    }
+
+    fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+
+    fn deal(&mut self) -> Result<(), String> {
+        self.deal()
+    }
+
+    /// Every other player still holding cards is a chance, in seating order.
+    fn chances(&self, player: PlayerId) -> Vec<Vec<&SpotItCard>> {
+        let top_cards = self.top_cards();
+        self.targets(player)
+            .into_iter()
+            .filter_map(|t| Some(vec![top_cards[player]?, top_cards[t]?]))
+            .collect()
+    }
+
+    /// The player's whole stack is passed onto the target, which may end the round.
+    fn resolve(&mut self, player: PlayerId, chance: usize) -> Result<(), String> {
+        let target = *self
+            .targets(player)
+            .get(chance)
+            .ok_or_else(|| format!("There is no chance {}.", chance))?;
+        let mut stack = std::mem::take(&mut self.table.players[player].pile);
+        self.table.players[target].pile.append(&mut stack);
+        self.end_round_if_over();
+        Ok(())
+    }
+
+    /// Passing cards scores nothing: only the cards set aside at the end of a round count.
+    fn score(&mut self, _player: PlayerId, _chance: usize) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn is_over(&self) -> bool {
+        self.results.len() == self.rounds
+    }
+
+    /// Cards held count against the player, so they take a card under their stack instead of losing one: a card set aside in an earlier round, or one the draw pile can spare from the rounds left.
+    fn lose_card(&mut self, player: PlayerId) -> Result<(), String> {
+        self.table.player(player)?;
+        if self.phase != Phase::Playing {
+            return Ok(());
+        }
+        let needed = self.table.players.len() * (self.rounds - self.results.len() - 1);
+        let card = match self.table.centre.pop() {
+            Some(card) => Some(card),
+            None if self.table.draw_pile.len() > needed => self.table.draw_pile.pop(),
+            None => None,
+        };
+        if let Some(card) = card {
+            self.table.players[player].pile.insert(0, card);
+        }
+        self.end_round_if_over();
+        Ok(())
+    }
+
+    /// Cards go back to the centre after every round, so the cards to show are those set aside over all rounds.
+    fn cards(&self) -> Vec<usize> {
+        self.totals()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::common_symbol;
+    use crate::Deck;
+
+    fn match_between(game: &HotPotato, player: PlayerId, target: PlayerId) -> SpotItSymbol {
+        let tops = game.top_cards();
+        common_symbol(tops[player].unwrap(), tops[target].unwrap()).unwrap()
+    }
+
+    #[test]
+    fn can_play_official_five_rounds() {
+        let mut game = HotPotato::official(SpotItDeck::default(), &["Ann", "Bob", "Cat"]).unwrap();
+        assert_eq!(game.rounds(), 5);
+        for round in 1..=5 {
+            assert_eq!(game.phase(), Phase::Setup);
+            game.deal().unwrap();
+            assert_eq!(game.round(), round);
+            // Bob passes to Cat, then Cat passes both cards to Ann
+            let symbol = match_between(&game, 1, 2);
+            game.claim(1, 2, symbol).unwrap();
+            assert_eq!(game.table().players[2].pile.len(), 2);
+            assert!(game.results().len() < round);
+            let symbol = match_between(&game, 2, 0);
+            assert_eq!(
+                game.claim(2, 0, symbol),
+                Ok(Verdict::Correct(Claim { player: 2, symbol }))
+            );
+            assert_eq!(game.results()[round - 1].loser, 0);
+            assert_eq!(game.results()[round - 1].cards, vec![3, 0, 0]);
+        }
+        assert_eq!(game.phase(), Phase::Finished);
+        assert_eq!(game.totals(), vec![15, 0, 0]);
+        assert_eq!(game.winners(), vec![1, 2]);
+        assert_eq!(game.table().centre.len(), 15);
+        assert!(game.deal().is_err());
+    }
+    #[test]
+    fn claim_must_match_both_top_cards() {
+        let mut game = HotPotato::new(SpotItDeck::default(), &["Ann", "Bob", "Cat"], 2).unwrap();
+        assert!(game.claim(0, 1, SpotItSymbol::Apple).is_err());
+        game.deal().unwrap();
+        assert!(game.deal().is_err());
+        let tops = game.top_cards();
+        let wrong = *tops[0]
+            .unwrap()
+            .0
+            .iter()
+            .find(|s| !tops[1].unwrap().0.contains(s))
+            .unwrap();
+        assert_eq!(
+            game.claim(0, 1, wrong),
+            Ok(Verdict::Wrong(Claim {
+                player: 0,
+                symbol: wrong
+            }))
+        );
+        assert!(game.claim(0, 0, wrong).is_err());
+        assert!(game.claim(0, 3, wrong).is_err());
+
+        // Ann's stack is passed to Bob, so Ann has nothing to match any more
+        let symbol = match_between(&game, 0, 1);
+        game.claim(0, 1, symbol).unwrap();
+        assert_eq!(
+            game.claim(0, 2, symbol),
+            Ok(Verdict::Wrong(Claim { player: 0, symbol }))
+        );
+        assert_eq!(game.round(), 1);
+        assert!(game.results().is_empty());
+    }
+    #[test]
+    fn two_players_lose_a_round_in_one_claim() {
+        let mut game = HotPotato::new(
+            SpotItDeck::generate_by_prime(2).unwrap(),
+            &["Ann", "Bob"],
+            3,
+        )
+        .unwrap();
+        for round in 1..=3 {
+            game.deal().unwrap();
+            let symbol = match_between(&game, round % 2, (round + 1) % 2);
+            game.claim(round % 2, (round + 1) % 2, symbol).unwrap();
+        }
+        assert_eq!(game.totals(), vec![4, 2]);
+        assert_eq!(game.winners(), vec![1]);
+        assert_eq!(game.table().players[0].score, -4);
+    }
+    #[test]
+    fn cannot_play_without_enough_cards() {
+        assert!(
+            HotPotato::official(SpotItDeck::generate_by_prime(2).unwrap(), &["Ann", "Bob"])
+                .is_err()
+        );
+        assert!(HotPotato::new(SpotItDeck::default(), &["Ann"], 1).is_err());
+        assert!(HotPotato::new(SpotItDeck::default(), &["Ann", "Bob"], 0).is_err());
+    }
+}
@@ -0,0 +1,716 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Game engine to play SpotIt! in `cardgame` crate.
+//!
+//! Provide a table of players around a draw pile and a centre pile, and a state machine which front ends (CLI, WASM, server) drive by dealing and claiming matches.
+//! The `Game` in this module plays the classic game described in the README: the top of the centre pile and the top of the draw pile are face up, and the first player to name their common symbol wins the round and gets a point.
+//! Official modes live in submodules, e.g. `tower`, `well`, `hot_potato`, `poisoned_gift` and `triplet`, `solo` is a time-attack mode for a single player timed by a `clock::Clock`, and `practice` deals a single player the symbols their `crate::profile::Profile` says they find hardest.
+//! Every mode implements the `GameMode` trait, whose hooks the engine calls to deal, judge claims, score and end the game, and `registry` lists the modes by name, next to any made outside this crate.
+//! Players behind the `Spotter` trait, e.g. the computer opponents in `bot`, can play any mode with `play_turn`, or a whole game with `play_game`.
+//! Claims shouted at the same time are refereed by an `arbiter::Arbiter`, and players stuck on two cards can ask `hint::Hints` for help, at a cost.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt!`]: https://www.amazon.com/Asmodee-SP411-Spot-It/dp/B0039S7NO6
+//! [`SpotIt! Rules`]: https://www.ultraboardgames.com/spot-it/game-rules.php
+
+pub mod arbiter;
+pub mod bot;
+pub mod clock;
+pub mod hint;
+pub mod hot_potato;
+pub mod poisoned_gift;
+pub mod practice;
+pub mod registry;
+pub mod solo;
+pub mod tower;
+pub mod triplet;
+pub mod well;
+
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use crate::SpotItDeck;
+use arbiter::Penalty;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// This is the index of a player around the table, starting from 0.
+pub type PlayerId = usize;
+
+/// This is the most claims in a game played with `play_game`, unless set otherwise, so that a game no one can finish is stopped.
+pub const MAX_TURNS: usize = 10_000;
+
+/// This struct defines a player sitting at the table.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Player {
+    /// This is the name shown to other players.
+    pub name: String,
+    /// This is the cards in front of the player. The last card is the top one, which is face up.
+    pub pile: Vec<SpotItCard>,
+    /// This is the points the player has scored.
+    pub score: i32,
+    /// This is the hints the player has taken. See `hint::Hints`.
+    #[serde(default)]
+    pub hints: usize,
+}
+
+impl Player {
+    /// This function creates a player with no card and no point.
+    pub fn new(name: &str) -> Self {
+        Player {
+            name: name.to_string(),
+            pile: Vec::new(),
+            score: 0,
+            hints: 0,
+        }
+    }
+    /// This function returns the face up card of the player, if any.
+    pub fn top_card(&self) -> Option<&SpotItCard> {
+        self.pile.last()
+    }
+}
+
+/// This enum defines where a card lies on the table.
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Hash)]
+pub enum Pile {
+    /// This is the face down pile cards are drawn from.
+    Draw,
+    /// This is the face up pile in the middle of the table.
+    Centre,
+    /// This is the pile in front of a player.
+    Player(PlayerId),
+}
+
+/// This struct defines the table, i.e. the players and the piles of cards between them.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Table {
+    /// This is the players, in seating order.
+    pub players: Vec<Player>,
+    /// This is the face down draw pile. The last card is the top one.
+    pub draw_pile: Vec<SpotItCard>,
+    /// This is the face up centre pile. The last card is the top one.
+    pub centre: Vec<SpotItCard>,
+}
+
+impl Table {
+    /// This function seats the players and puts the whole deck down as the draw pile.
+    /// It returns an error if there is no player or two players share a name.
+    pub fn new(deck: SpotItDeck, names: &[&str]) -> Result<Self, String> {
+        if names.is_empty() {
+            return Err("At least one player is needed.".to_string());
+        }
+        for (i, name) in names.iter().enumerate() {
+            if names[..i].contains(name) {
+                return Err(format!("There are two players called {}.", name));
+            }
+        }
+        Ok(Table {
+            players: names.iter().map(|name| Player::new(name)).collect(),
+            draw_pile: deck.cards,
+            centre: Vec::new(),
+        })
+    }
+
+    /// This function returns the player, or an error if there is no such player.
+    pub fn player(&self, player: PlayerId) -> Result<&Player, String> {
+        self.players
+            .get(player)
+            .ok_or_else(|| format!("There is no player {}.", player))
+    }
+
+    /// This function returns the player to be changed, or an error if there is no such player.
+    pub fn player_mut(&mut self, player: PlayerId) -> Result<&mut Player, String> {
+        self.players
+            .get_mut(player)
+            .ok_or_else(|| format!("There is no player {}.", player))
+    }
+
+    /// This function returns the top card of a pile, if the pile is not empty.
+    pub fn top_card(&self, pile: Pile) -> Option<&SpotItCard> {
+        match pile {
+            Pile::Draw => self.draw_pile.last(),
+            Pile::Centre => self.centre.last(),
+            Pile::Player(player) => self.players.get(player).and_then(|p| p.top_card()),
+        }
+    }
+
+    /// This function moves the top card of one pile onto another, and returns false if there is no card to move.
+    pub fn move_top_card(&mut self, from: Pile, to: Pile) -> bool {
+        let card = match from {
+            Pile::Draw => self.draw_pile.pop(),
+            Pile::Centre => self.centre.pop(),
+            Pile::Player(player) => self.players.get_mut(player).and_then(|p| p.pile.pop()),
+        };
+        let Some(card) = card else {
+            return false;
+        };
+        match to {
+            Pile::Draw => self.draw_pile.push(card),
+            Pile::Centre => self.centre.push(card),
+            Pile::Player(player) => match self.players.get_mut(player) {
+                Some(p) => p.pile.push(card),
+                None => {
+                    // put the card back rather than losing it
+                    self.move_back(from, card);
+                    return false;
+                }
+            },
+        }
+        true
+    }
+
+    fn move_back(&mut self, from: Pile, card: SpotItCard) {
+        match from {
+            Pile::Draw => self.draw_pile.push(card),
+            Pile::Centre => self.centre.push(card),
+            Pile::Player(player) => self.players[player].pile.push(card),
+        }
+    }
+
+    /// This function deals one card from the draw pile to every player in seating order.
+    /// It returns an error if the draw pile runs out.
+    pub fn deal_one_each(&mut self) -> Result<(), String> {
+        if self.draw_pile.len() < self.players.len() {
+            return Err("There are not enough cards to deal one to every player.".to_string());
+        }
+        for player in 0..self.players.len() {
+            self.move_top_card(Pile::Draw, Pile::Player(player));
+        }
+        Ok(())
+    }
+
+    /// This function counts a hint taken by the player, which costs them `hint::HINT_COST` points.
+    pub fn take_hint(&mut self, player: PlayerId) -> Result<(), String> {
+        let player = self.player_mut(player)?;
+        player.hints += 1;
+        player.score -= hint::HINT_COST;
+        Ok(())
+    }
+
+    /// This function returns the players with the highest score.
+    pub fn leaders(&self) -> Vec<PlayerId> {
+        self.leaders_by(|p| p.score)
+    }
+
+    /// This function returns the players with the highest key, e.g. `|p| p.pile.len()` for the players with the most cards.
+    pub fn leaders_by<K: Ord, F: Fn(&Player) -> K>(&self, key: F) -> Vec<PlayerId> {
+        let best = self.players.iter().map(&key).max();
+        (0..self.players.len())
+            .filter(|i| Some(key(&self.players[*i])) == best)
+            .collect()
+    }
+}
+
+/// This function returns the symbol which two cards have in common, or None if they do not have exactly one.
+pub fn common_symbol(card: &SpotItCard, other: &SpotItCard) -> Option<SpotItSymbol> {
+    match card.match_exactly_one_symbol(other) {
+        true => card.0.intersection(&other.0).next().copied(),
+        false => None,
+    }
+}
+
+/// This function returns the only symbol shown on every card, or None if there is not exactly one, e.g. for the three cards of a triplet.
+pub fn shared_symbol(cards: &[&SpotItCard]) -> Option<SpotItSymbol> {
+    let (first, others) = cards.split_first()?;
+    let mut shared = first
+        .0
+        .iter()
+        .filter(|symbol| others.iter().all(|card| card.0.contains(symbol)));
+    match (shared.next(), shared.next()) {
+        (Some(symbol), None) => Some(*symbol),
+        _ => None,
+    }
+}
+
+/// This enum defines the phases of a game. A game moves from `Setup` to `Playing` when it is dealt, and to `Finished` when it ends.
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Serialize, Deserialize)]
+pub enum Phase {
+    /// This is before the cards are dealt.
+    Setup,
+    /// This is while players claim matches.
+    Playing,
+    /// This is after the game has ended.
+    Finished,
+}
+
+/// This struct defines a claim, i.e. a player shouting the symbol they have spotted.
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Serialize, Deserialize)]
+pub struct Claim {
+    /// This is the player who claims.
+    pub player: PlayerId,
+    /// This is the symbol claimed.
+    pub symbol: SpotItSymbol,
+}
+
+/// This enum defines the verdict on a claim.
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+    /// This is a claim of the right symbol.
+    Correct(Claim),
+    /// This is a claim of a symbol which is not on both cards.
+    Wrong(Claim),
+    /// This is a claim of the right symbol, made at the same time as another right claim which was judged first.
+    Late(Claim),
+}
+
+/// This struct defines what a player has spotted, i.e. the symbol they are about to claim on one of their chances.
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub struct Sighting {
+    /// This is the position of the chance in the list given by `GameMode::chances`.
+    pub chance: usize,
+    /// This is the symbol to claim.
+    pub symbol: SpotItSymbol,
+    /// This is the time it took to spot, from when the cards were shown.
+    pub after: Duration,
+}
+
+/// This trait defines a player, human or computer, as seen by the game engine: someone who looks at cards and spots a symbol.
+pub trait Spotter {
+    /// This function returns the name of the player.
+    fn name(&self) -> &str;
+    /// This function looks at the chances, each a list of cards which must all show the symbol claimed, and returns what is spotted, or None if nothing is.
+    fn spot(&mut self, chances: &[Vec<&SpotItCard>]) -> Option<Sighting>;
+}
+
+/// This trait defines a game mode, built in or made up as a house rule, by hooks the engine calls to run it: setting up and dealing, the legal claims, resolving and scoring a right claim, and the end condition.
+/// Claims are judged by the engine in `claim_chance`: a claim is right when every card of the chance shows the symbol, and only then are the hooks which change the table called.
+/// Modes can be chosen by name from a `registry::Registry`, which also takes modes made outside this crate.
+pub trait GameMode {
+    /// This function returns the table.
+    fn table(&self) -> &Table;
+    /// This function returns the table to be changed, e.g. by house rules.
+    fn table_mut(&mut self) -> &mut Table;
+    /// This function returns the phase of the game.
+    fn phase(&self) -> Phase;
+    /// This function moves the game to the phase, e.g. to `Finished` once it is over.
+    fn set_phase(&mut self, phase: Phase);
+
+    /// This function sets up and deals the game, or the next round of a game played in rounds, and moves it to `Playing`.
+    fn deal(&mut self) -> Result<(), String>;
+    /// This function returns the legal claims of the player, i.e. their chances to claim a match, each a list of cards which must all show the symbol claimed.
+    /// It is empty when the player cannot claim, e.g. outside of play.
+    fn chances(&self, player: PlayerId) -> Vec<Vec<&SpotItCard>>;
+    /// This function resolves a right claim on the chance at the position given, as listed by `chances`, e.g. by moving cards.
+    fn resolve(&mut self, player: PlayerId, chance: usize) -> Result<(), String>;
+    /// This function scores a right claim once it is resolved. By default, the player gets a point.
+    fn score(&mut self, player: PlayerId, _chance: usize) -> Result<(), String> {
+        self.table_mut().player_mut(player)?.score += 1;
+        Ok(())
+    }
+    /// This function deals with a wrong claim. By default, nothing happens.
+    fn miss(&mut self, _player: PlayerId) -> Result<(), String> {
+        Ok(())
+    }
+    /// This function returns true once the game is over, which is checked after every right claim.
+    fn is_over(&self) -> bool;
+    /// This function returns the winners once the game has finished. By default, the players with the most points win.
+    fn winners(&self) -> Vec<PlayerId> {
+        match self.phase() {
+            Phase::Finished => self.table().leaders(),
+            _ => Vec::new(),
+        }
+    }
+    /// This function returns the cards every player has to show for the game, in seating order. By default, it is the cards in their piles.
+    fn cards(&self) -> Vec<usize> {
+        self.table().players.iter().map(|p| p.pile.len()).collect()
+    }
+
+    /// This function judges a claim of the symbol on the chance at the position given, as listed by `chances`.
+    /// A right claim is resolved and scored, and finishes the game if it is over. A wrong claim is a miss.
+    fn claim_chance(
+        &mut self,
+        player: PlayerId,
+        chance: usize,
+        symbol: SpotItSymbol,
+    ) -> Result<Verdict, String> {
+        if self.phase() != Phase::Playing {
+            return Err("Claims are only accepted while playing.".to_string());
+        }
+        self.table().player(player)?;
+        let chances = self.chances(player);
+        let cards = chances
+            .get(chance)
+            .ok_or_else(|| match chances.is_empty() {
+                true => format!("Player {} has nothing to claim.", player),
+                false => format!("There is no chance {}.", chance),
+            })?;
+        let right = cards.iter().all(|card| card.0.contains(&symbol));
+        let claim = Claim { player, symbol };
+        if !right {
+            self.miss(player)?;
+            return Ok(Verdict::Wrong(claim));
+        }
+
+        self.resolve(player, chance)?;
+        self.score(player, chance)?;
+        if self.is_over() {
+            self.set_phase(Phase::Finished);
+        }
+        Ok(Verdict::Correct(claim))
+    }
+
+    /// This function gives the player a penalty which changes the table, and finishes the game if that is the end of it. Sitting out is kept track of by the arbiter, so it changes nothing here.
+    fn penalise(&mut self, player: PlayerId, penalty: Penalty) -> Result<(), String> {
+        self.table().player(player)?;
+        match penalty {
+            Penalty::LoseCard => self.lose_card(player)?,
+            Penalty::Deduct(points) => self.table_mut().players[player].score -= points,
+            Penalty::Nothing | Penalty::SitOut(_) => {}
+        }
+        if self.phase() == Phase::Playing && self.is_over() {
+            self.set_phase(Phase::Finished);
+        }
+        Ok(())
+    }
+    /// This function costs the player a card for `Penalty::LoseCard`, in whatever way counts against them in the mode.
+    /// By default, the top card of their pile goes to the bottom of the draw pile.
+    fn lose_card(&mut self, player: PlayerId) -> Result<(), String> {
+        let table = self.table_mut();
+        if let Some(card) = table.player_mut(player)?.pile.pop() {
+            table.draw_pile.insert(0, card);
+        }
+        Ok(())
+    }
+}
+
+/// A boxed mode, e.g. one chosen from a `registry::Registry`, is played like the mode inside.
+impl<M: GameMode + ?Sized> GameMode for Box<M> {
+    fn table(&self) -> &Table {
+        (**self).table()
+    }
+    fn table_mut(&mut self) -> &mut Table {
+        (**self).table_mut()
+    }
+    fn phase(&self) -> Phase {
+        (**self).phase()
+    }
+    fn set_phase(&mut self, phase: Phase) {
+        (**self).set_phase(phase)
+    }
+    fn deal(&mut self) -> Result<(), String> {
+        (**self).deal()
+    }
+    fn chances(&self, player: PlayerId) -> Vec<Vec<&SpotItCard>> {
+        (**self).chances(player)
+    }
+    fn resolve(&mut self, player: PlayerId, chance: usize) -> Result<(), String> {
+        (**self).resolve(player, chance)
+    }
+    fn score(&mut self, player: PlayerId, chance: usize) -> Result<(), String> {
+        (**self).score(player, chance)
+    }
+    fn miss(&mut self, player: PlayerId) -> Result<(), String> {
+        (**self).miss(player)
+    }
+    fn is_over(&self) -> bool {
+        (**self).is_over()
+    }
+    fn winners(&self) -> Vec<PlayerId> {
+        (**self).winners()
+    }
+    fn cards(&self) -> Vec<usize> {
+        (**self).cards()
+    }
+    fn claim_chance(
+        &mut self,
+        player: PlayerId,
+        chance: usize,
+        symbol: SpotItSymbol,
+    ) -> Result<Verdict, String> {
+        (**self).claim_chance(player, chance, symbol)
+    }
+    fn penalise(&mut self, player: PlayerId, penalty: Penalty) -> Result<(), String> {
+        (**self).penalise(player, penalty)
+    }
+    fn lose_card(&mut self, player: PlayerId) -> Result<(), String> {
+        (**self).lose_card(player)
+    }
+}
+
+/// This struct defines a turn played with `play_turn`.
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub struct Turn {
+    /// This is the quickest player, who claimed.
+    pub player: PlayerId,
+    /// This is what the player spotted.
+    pub sighting: Sighting,
+    /// This is the verdict on the claim.
+    pub verdict: Verdict,
+}
+
+/// This function shows every spotter, in seating order, their chances, and judges the claim of the quickest one. Ties go to the first in seating order.
+/// It returns None if no one spots anything, e.g. when the game has finished.
+pub fn play_turn<M: GameMode + ?Sized>(
+    mode: &mut M,
+    spotters: &mut [Box<dyn Spotter>],
+) -> Result<Option<Turn>, String> {
+    let mut quickest: Option<(PlayerId, Sighting)> = None;
+    for (player, spotter) in spotters.iter_mut().enumerate() {
+        let Some(sighting) = spotter.spot(&mode.chances(player)) else {
+            continue;
+        };
+        if quickest.is_none_or(|(_, q)| sighting.after < q.after) {
+            quickest = Some((player, sighting));
+        }
+    }
+    let Some((player, sighting)) = quickest else {
+        return Ok(None);
+    };
+    let verdict = mode.claim_chance(player, sighting.chance, sighting.symbol)?;
+    Ok(Some(Turn {
+        player,
+        sighting,
+        verdict,
+    }))
+}
+
+/// This function deals the game and plays turns with `play_turn` until no one spots anything or `max_turns` claims have been made, and returns the turns played.
+/// A game played in rounds, e.g. "The Hot Potato", goes back to setup after every round, and the next round is dealt.
+pub fn play_game<M: GameMode + ?Sized>(
+    mode: &mut M,
+    spotters: &mut [Box<dyn Spotter>],
+    max_turns: usize,
+) -> Result<Vec<Turn>, String> {
+    let mut turns = Vec::new();
+    while mode.phase() == Phase::Setup && turns.len() < max_turns {
+        mode.deal()?;
+        while turns.len() < max_turns {
+            let Some(turn) = play_turn(mode, spotters)? else {
+                break;
+            };
+            turns.push(turn);
+        }
+    }
+    Ok(turns)
+}
+
+/// This struct defines the classic game: the first player to name the symbol shared by the two face up cards gets a point.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Game {
+    table: Table,
+    phase: Phase,
+    round: usize,
+}
+
+impl Game {
+    /// This function seats the players at a table with the deck as the draw pile, ready to deal.
+    /// The deck is used in the order given, so shuffle it first for a fair game.
+    pub fn new(deck: SpotItDeck, names: &[&str]) -> Result<Self, String> {
+        if deck.cards.len() < 2 {
+            return Err("At least two cards are needed to play.".to_string());
+        }
+        Ok(Game {
+            table: Table::new(deck, names)?,
+            phase: Phase::Setup,
+            round: 0,
+        })
+    }
+
+    /// This function returns the table.
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    /// This function returns the phase of the game.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// This function returns the number of the current round, starting from 1 once dealt.
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    /// This function deals the game, i.e. turns the first card over onto the centre pile, so that it and the top of the draw pile are face up.
+    pub fn deal(&mut self) -> Result<(), String> {
+        if self.phase != Phase::Setup {
+            return Err("The game has already been dealt.".to_string());
+        }
+        self.table.move_top_card(Pile::Draw, Pile::Centre);
+        self.phase = Phase::Playing;
+        self.round = 1;
+        Ok(())
+    }
+
+    /// This function returns the two face up cards players look for a match between, i.e. the top of the centre pile and of the draw pile.
+    pub fn face_up_cards(&self) -> Option<(&SpotItCard, &SpotItCard)> {
+        match self.phase {
+            Phase::Playing => Some((
+                self.table.top_card(Pile::Centre)?,
+                self.table.top_card(Pile::Draw)?,
+            )),
+            _ => None,
+        }
+    }
+
+    /// This function judges a claim.
+    /// A correct claim scores a point and takes the centre card, then the top of the draw pile is turned over onto the centre pile for the next round.
+    /// The game finishes when the draw pile runs out. A wrong claim changes nothing.
+    pub fn claim(&mut self, player: PlayerId, symbol: SpotItSymbol) -> Result<Verdict, String> {
+        self.claim_chance(player, 0, symbol)
+    }
+
+    /// This function returns the winners once the game has finished, i.e. the players with the most points.
+    pub fn winners(&self) -> Vec<PlayerId> {
+        GameMode::winners(self)
+    }
+}
+
+impl GameMode for Game {
+    fn table(&self) -> &Table {
+        &self.table
+    }
+
+    fn table_mut(&mut self) -> &mut Table {
+        &mut self.table
+    }
+
+    fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+
+    fn deal(&mut self) -> Result<(), String> {
+        self.deal()
+    }
+
+    fn chances(&self, _player: PlayerId) -> Vec<Vec<&SpotItCard>> {
+        self.face_up_cards()
+            .map(|(centre, draw)| vec![vec![centre, draw]])
+            .unwrap_or_default()
+    }
+
+    fn resolve(&mut self, player: PlayerId, _chance: usize) -> Result<(), String> {
+        self.table.move_top_card(Pile::Centre, Pile::Player(player));
+        self.table.move_top_card(Pile::Draw, Pile::Centre);
+        if !self.table.draw_pile.is_empty() {
+            self.round += 1;
+        }
+        Ok(())
+    }
+
+    fn is_over(&self) -> bool {
+        self.table.draw_pile.is_empty()
+    }
+
+    /// The player's last card won is set aside under the centre pile with its point, so that the draw pile, and the end of the game, are left alone.
+    fn lose_card(&mut self, player: PlayerId) -> Result<(), String> {
+        let loser = self.table.player_mut(player)?;
+        if let Some(card) = loser.pile.pop() {
+            loser.score -= 1;
+            self.table.centre.insert(0, card);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Deck;
+    use std::collections::HashSet;
+
+    fn match_of(game: &Game) -> SpotItSymbol {
+        let (centre, draw) = game.face_up_cards().unwrap();
+        common_symbol(centre, draw).unwrap()
+    }
+
+    #[test]
+    fn can_find_common_symbol() {
+        let card1 = SpotItCard(HashSet::from([SpotItSymbol::Apple, SpotItSymbol::Banana]));
+        let card2 = SpotItCard(HashSet::from([SpotItSymbol::Apple, SpotItSymbol::Yuzu]));
+        let card3 = SpotItCard(HashSet::from([SpotItSymbol::Apple, SpotItSymbol::Banana]));
+        assert_eq!(common_symbol(&card1, &card2), Some(SpotItSymbol::Apple));
+        assert_eq!(common_symbol(&card1, &card3), None);
+    }
+    #[test]
+    fn cannot_seat_invalid_players() {
+        assert!(Game::new(SpotItDeck::default(), &[]).is_err());
+        assert!(Game::new(SpotItDeck::default(), &["Ann", "Ann"]).is_err());
+        assert!(Game::new(SpotItDeck::new(), &["Ann"]).is_err());
+    }
+    #[test]
+    fn game_moves_through_phases() {
+        let mut game =
+            Game::new(SpotItDeck::generate_by_prime(2).unwrap(), &["Ann", "Bob"]).unwrap();
+        assert_eq!(game.phase(), Phase::Setup);
+        assert_eq!(game.face_up_cards(), None);
+        assert!(game.claim(0, SpotItSymbol::Apple).is_err());
+
+        game.deal().unwrap();
+        assert_eq!(game.phase(), Phase::Playing);
+        assert_eq!(game.round(), 1);
+        assert!(game.deal().is_err());
+
+        // 7 cards make 6 rounds
+        for round in 1..=6 {
+            assert_eq!(game.round(), round);
+            let symbol = match_of(&game);
+            let claim = Claim {
+                player: round % 2,
+                symbol,
+            };
+            assert_eq!(
+                game.claim(claim.player, symbol),
+                Ok(Verdict::Correct(claim))
+            );
+        }
+        assert_eq!(game.phase(), Phase::Finished);
+        assert!(game.claim(0, SpotItSymbol::Apple).is_err());
+        assert_eq!(game.table().players[0].score, 3);
+        assert_eq!(game.table().players[1].score, 3);
+        assert_eq!(game.winners(), vec![0, 1]);
+    }
+    #[test]
+    fn wrong_claim_changes_nothing() {
+        let mut game = Game::new(SpotItDeck::default(), &["Ann", "Bob"]).unwrap();
+        game.deal().unwrap();
+        let before = game.clone();
+        let (centre, draw) = game.face_up_cards().unwrap();
+        let wrong = *centre.0.difference(&draw.0).next().unwrap();
+        assert_eq!(
+            game.claim(1, wrong).unwrap(),
+            Verdict::Wrong(Claim {
+                player: 1,
+                symbol: wrong
+            })
+        );
+        assert_eq!(game, before);
+        assert!(game.claim(2, wrong).is_err());
+    }
+    #[test]
+    fn winner_takes_centre_card_and_next_card_is_turned_over() {
+        let mut game = Game::new(SpotItDeck::default(), &["Ann", "Bob"]).unwrap();
+        game.deal().unwrap();
+        let (centre, draw) = game.face_up_cards().unwrap();
+        let (centre, draw) = (centre.clone(), draw.clone());
+        game.claim(1, match_of(&game)).unwrap();
+        assert_eq!(game.table().players[1].top_card(), Some(&centre));
+        assert_eq!(game.table().top_card(Pile::Centre), Some(&draw));
+        assert_eq!(game.table().draw_pile.len(), 55);
+        assert_eq!(game.table().leaders(), vec![1]);
+        assert_eq!(game.winners(), Vec::<PlayerId>::new());
+    }
+    #[test]
+    fn can_move_and_deal_cards_on_table() {
+        let mut table = Table::new(
+            SpotItDeck::generate_by_prime(2).unwrap(),
+            &["Ann", "Bob", "Cat"],
+        )
+        .unwrap();
+        table.deal_one_each().unwrap();
+        table.deal_one_each().unwrap();
+        assert_eq!(table.draw_pile.len(), 1);
+        assert!(table.deal_one_each().is_err());
+        assert!(table.players.iter().all(|p| p.pile.len() == 2));
+        assert!(!table.move_top_card(Pile::Centre, Pile::Draw));
+        assert!(!table.move_top_card(Pile::Draw, Pile::Player(9)));
+        assert_eq!(table.draw_pile.len(), 1);
+        assert!(table.move_top_card(Pile::Player(0), Pile::Centre));
+        assert_eq!(table.players[0].pile.len(), 1);
+        assert_eq!(table.centre.len(), 1);
+        assert!(table.player(3).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! "The Poisoned Gift" mode of SpotIt! in `cardgame` crate.
+//!
+//! Every player is dealt one card face up, and the rest of the deck is the centre pile with its top card face up as the gift.
+//! A player who spots the symbol shared by the gift and an opponent's card names it and hands the gift to that opponent, whose pile it goes on top of.
+//! Only the target's card counts: the claimant's own card plays no part in the match.
+//! The game ends when the centre pile runs out, and the player with the fewest cards wins.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt! Rules`]: https://www.ultraboardgames.com/spot-it/game-rules.php
+
+use super::{GameMode, Phase, Pile, PlayerId, Table, Verdict};
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use crate::SpotItDeck;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+/// This struct defines a game of "The Poisoned Gift".
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PoisonedGift {
+    table: Table,
+    phase: Phase,
+}
+
+impl PoisonedGift {
+    /// This function seats the players at a table with the deck as the centre pile, ready to deal.
+    /// It returns an error if there are fewer than two players or not enough cards to deal one to every player and leave a gift.
+    pub fn new(deck: SpotItDeck, names: &[&str]) -> Result<Self, String> {
+        if names.len() < 2 {
+            return Err("At least two players are needed.".to_string());
+        }
+        if deck.cards.len() <= names.len() {
+            return Err("There must be more cards than players.".to_string());
+        }
+        Ok(PoisonedGift {
+            table: Table::new(deck, names)?,
+            phase: Phase::Setup,
+        })
+    }
+
+    /// This function returns the table.
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    /// This function returns the phase of the game.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// This function deals one card face up to every player.
+    pub fn deal(&mut self) -> Result<(), String> {
+        if self.phase != Phase::Setup {
+            return Err("The game has already been dealt.".to_string());
+        }
+        self.table.deal_one_each()?;
+        self.phase = Phase::Playing;
+        Ok(())
+    }
+
+    /// This function returns the gift, i.e. the face up card on top of the centre pile.
+    pub fn gift_card(&self) -> Option<&SpotItCard> {
+        match self.phase {
+            Phase::Playing => self.table.top_card(Pile::Draw),
+            _ => None,
+        }
+    }
+
+    /// This function judges a claim that the symbol is shared by the gift and the target's card.
+    /// The claimant's own card is not looked at, so a symbol only on the claimant's card is wrong.
+    /// A correct claim moves the gift onto the target's pile, and the game finishes when the centre pile runs out.
+    pub fn claim(
+        &mut self,
+        player: PlayerId,
+        target: PlayerId,
+        symbol: SpotItSymbol,
+    ) -> Result<Verdict, String> {
+        if self.phase != Phase::Playing {
+            return Err("Claims are only accepted while playing.".to_string());
+        }
+        self.table.player(player)?;
+        if player == target {
+            return Err("A player cannot give the gift to themselves.".to_string());
+        }
+        self.table.player(target)?;
+        let chance = self
+            .targets(player)
+            .iter()
+            .position(|t| *t == target)
+            .ok_or_else(|| format!("Player {} has no card.", target))?;
+        self.claim_chance(player, chance, symbol)
+    }
+
+    /// This function returns the number of cards every player holds, in seating order.
+    pub fn pile_sizes(&self) -> Vec<usize> {
+        self.table.players.iter().map(|p| p.pile.len()).collect()
+    }
+
+    /// This function returns the winners once the game has finished, i.e. the players holding the fewest cards.
+    pub fn winners(&self) -> Vec<PlayerId> {
+        GameMode::winners(self)
+    }
+}
+
+impl PoisonedGift {
+    /// This function returns the players the player can give the gift to, i.e. the others holding a card, in seating order.
+    fn targets(&self, player: PlayerId) -> Vec<PlayerId> {
+        match self.phase {
+            Phase::Playing => (0..self.table.players.len())
+                .filter(|t| *t != player && !self.table.players[*t].pile.is_empty())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl GameMode for PoisonedGift {
+    fn table(&self) -> &Table {
+        &self.table
+    }
+
+    fn table_mut(&mut self) -> &mut Table {
+        &mut self.table
+    }
+
+    fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+
+    fn deal(&mut self) -> Result<(), String> {
+        self.deal()
+    }
+
+    /// Every other player holding a card is a chance, in seating order.
+    fn chances(&self, player: PlayerId) -> Vec<Vec<&SpotItCard>> {
+        let Some(gift) = self.gift_card() else {
+            return Vec::new();
+        };
+        self.targets(player)
+            .into_iter()
+            .filter_map(|t| Some(vec![self.table.top_card(Pile::Player(t))?, gift]))
+            .collect()
+    }
+
+    /// The gift goes onto the target's pile, and the claimant scores the point.
+    fn resolve(&mut self, player: PlayerId, chance: usize) -> Result<(), String> {
+        let target = *self
+            .targets(player)
+            .get(chance)
+            .ok_or_else(|| format!("There is no chance {}.", chance))?;
+        self.table.move_top_card(Pile::Draw, Pile::Player(target));
+        Ok(())
+    }
+
+    fn is_over(&self) -> bool {
+        self.table.draw_pile.is_empty()
+    }
+
+    /// Fewer cards are better, so the player takes the gift onto their own pile instead of losing a card.
+    fn lose_card(&mut self, player: PlayerId) -> Result<(), String> {
+        self.table.player(player)?;
+        if self.phase == Phase::Playing {
+            self.table.move_top_card(Pile::Draw, Pile::Player(player));
+        }
+        Ok(())
+    }
+
+    fn winners(&self) -> Vec<PlayerId> {
+        if self.phase != Phase::Finished {
+            return Vec::new();
+        }
+        self.table.leaders_by(|p| Reverse(p.pile.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{common_symbol, Claim};
+    use crate::Deck;
+
+    fn match_for(game: &PoisonedGift, target: PlayerId) -> SpotItSymbol {
+        let theirs = game.table().players[target].top_card().unwrap();
+        common_symbol(theirs, game.gift_card().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn can_play_poisoned_gift_to_the_end() {
+        let mut game = PoisonedGift::new(SpotItDeck::default(), &["Ann", "Bob", "Cat"]).unwrap();
+        assert_eq!(game.gift_card(), None);
+        game.deal().unwrap();
+        assert_eq!(game.pile_sizes(), vec![1, 1, 1]);
+
+        // Ann hands every gift to Bob or Cat in turn
+        let mut turn = 0;
+        while game.phase() == Phase::Playing {
+            let target = 1 + turn % 2;
+            let symbol = match_for(&game, target);
+            assert_eq!(
+                game.claim(0, target, symbol),
+                Ok(Verdict::Correct(Claim { player: 0, symbol }))
+            );
+            turn += 1;
+        }
+        assert_eq!(turn, 54);
+        assert_eq!(game.pile_sizes(), vec![1, 28, 28]);
+        assert_eq!(game.table().players[0].score, 54);
+        assert_eq!(game.winners(), vec![0]);
+        assert!(game.claim(0, 1, SpotItSymbol::Apple).is_err());
+    }
+    #[test]
+    fn claim_must_match_target_card() {
+        let mut game = PoisonedGift::new(SpotItDeck::default(), &["Ann", "Bob"]).unwrap();
+        assert!(game.claim(0, 1, SpotItSymbol::Apple).is_err());
+        game.deal().unwrap();
+        let anns = match_for(&game, 0);
+        let bobs = match_for(&game, 1);
+        if anns != bobs {
+            // The gift matches Ann's own card on this symbol, not Bob's
+            assert_eq!(
+                game.claim(0, 1, anns),
+                Ok(Verdict::Wrong(Claim {
+                    player: 0,
+                    symbol: anns
+                }))
+            );
+        }
+        assert!(game.claim(0, 0, anns).is_err());
+        assert!(game.claim(0, 2, bobs).is_err());
+        assert!(game.claim(2, 1, bobs).is_err());
+        assert_eq!(game.pile_sizes(), vec![1, 1]);
+        game.claim(0, 1, bobs).unwrap();
+        assert_eq!(game.pile_sizes(), vec![1, 2]);
+        assert_eq!(game.winners(), Vec::<PlayerId>::new());
+    }
+    #[test]
+    fn cannot_play_without_gift() {
+        assert!(
+            PoisonedGift::new(SpotItDeck::generate_by_prime(1).unwrap(), &["A", "B", "C"]).is_err()
+        );
+        assert!(PoisonedGift::new(SpotItDeck::default(), &["A"]).is_err());
+        let mut game =
+            PoisonedGift::new(SpotItDeck::generate_by_prime(1).unwrap(), &["A", "B"]).unwrap();
+        game.deal().unwrap();
+        assert!(game.deal().is_err());
+        let symbol = match_for(&game, 0);
+        game.claim(1, 0, symbol).unwrap();
+        assert_eq!(game.phase(), Phase::Finished);
+        assert_eq!(game.winners(), vec![1]);
+    }
+}
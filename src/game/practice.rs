@@ -0,0 +1,273 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Adaptive practice mode of SpotIt! in `cardgame` crate.
+//!
+//! A single player is shown two cards at a time, as in the solo mode, but the pairs are not dealt in the order of the deck.
+//! Every pair is chosen so that its common symbol is the one the player's `profile::Profile` says to practise next, i.e. one of their weakest symbols due for review by spaced repetition.
+//! The time to spot every symbol and the misses are recorded in the profile, which the front end saves for the next session.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+
+use super::clock::{Clock, SystemClock};
+use super::{GameMode, Phase, PlayerId, Table, Verdict};
+use crate::profile::Profile;
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use crate::SpotItDeck;
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// This is the number of pairs in a practice session, unless set otherwise.
+pub const PRACTICE_ROUNDS: usize = 20;
+
+/// This struct defines a practice session, timed by the clock `C`.
+#[derive(Debug, Clone)]
+pub struct Practice<C: Clock = SystemClock> {
+    table: Table,
+    phase: Phase,
+    clock: C,
+    profile: Profile,
+    rng: ChaCha12Rng,
+    rounds: usize,
+    played: usize,
+    target: Option<SpotItSymbol>,
+    shown_at: Duration,
+}
+
+impl<C: Clock> Practice<C> {
+    /// This function sits the player down with the deck and their profile, ready to deal. The seed picks among pairs which are as good as each other.
+    pub fn new(
+        deck: SpotItDeck,
+        name: &str,
+        profile: Profile,
+        clock: C,
+        seed: u64,
+    ) -> Result<Self, String> {
+        if deck.cards.len() < 2 {
+            return Err("At least two cards are needed to practise.".to_string());
+        }
+        Ok(Practice {
+            table: Table::new(deck, &[name])?,
+            phase: Phase::Setup,
+            clock,
+            profile,
+            rng: ChaCha12Rng::seed_from_u64(seed),
+            rounds: PRACTICE_ROUNDS,
+            played: 0,
+            target: None,
+            shown_at: Duration::ZERO,
+        })
+    }
+
+    /// This function sets the number of pairs in the session.
+    pub fn with_rounds(mut self, rounds: usize) -> Self {
+        self.rounds = rounds;
+        self
+    }
+
+    /// This function returns the table.
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    /// This function returns the phase of the session.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// This function returns the profile, with what has been practised so far.
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    /// This function returns the profile, to be kept for the next session.
+    pub fn into_profile(self) -> Profile {
+        self.profile
+    }
+
+    /// This function returns the number of pairs spotted so far, and the number in the session.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.played, self.rounds)
+    }
+
+    /// This function deals the first pair.
+    pub fn deal(&mut self) -> Result<(), String> {
+        if self.phase != Phase::Setup {
+            return Err("The game has already been dealt.".to_string());
+        }
+        self.show_next()?;
+        self.phase = match self.rounds {
+            0 => Phase::Finished,
+            _ => Phase::Playing,
+        };
+        Ok(())
+    }
+
+    /// This function returns the two cards shown.
+    pub fn pair(&self) -> Option<(&SpotItCard, &SpotItCard)> {
+        match (self.phase, self.table.centre.as_slice()) {
+            (Phase::Playing, [card, other]) => Some((card, other)),
+            _ => None,
+        }
+    }
+
+    /// This function returns the time spent on the pair shown so far.
+    pub fn elapsed(&self) -> Duration {
+        self.clock.now().saturating_sub(self.shown_at)
+    }
+
+    /// This function judges an answer. A right answer records the time it took in the profile and shows the next pair, and a wrong answer records a miss.
+    pub fn claim(&mut self, symbol: SpotItSymbol) -> Result<Verdict, String> {
+        self.claim_chance(0, 0, symbol)
+    }
+
+    /// This function puts the pair shown back under the draw pile, and shows two cards sharing the symbol to practise next.
+    fn show_next(&mut self) -> Result<(), String> {
+        let shown = std::mem::take(&mut self.table.centre);
+        self.table.draw_pile.splice(0..0, shown);
+        let mut counts: BTreeMap<SpotItSymbol, usize> = BTreeMap::new();
+        for symbol in self.table.draw_pile.iter().flat_map(|c| c.0.iter()) {
+            *counts.entry(*symbol).or_default() += 1;
+        }
+        // Only symbols on two cards or more make a pair, and new symbols come in a random order
+        let mut symbols: Vec<SpotItSymbol> = counts
+            .into_iter()
+            .filter(|(_, count)| *count >= 2)
+            .map(|(symbol, _)| symbol)
+            .collect();
+        symbols.shuffle(&mut self.rng);
+        let target = self
+            .profile
+            .next_symbol(&symbols)
+            .ok_or_else(|| "No two cards share a symbol.".to_string())?;
+        let mut chosen = (0..self.table.draw_pile.len())
+            .filter(|i| self.table.draw_pile[*i].0.contains(&target))
+            .choose_multiple(&mut self.rng, 2);
+        chosen.sort();
+        for i in chosen.into_iter().rev() {
+            let card = self.table.draw_pile.remove(i);
+            self.table.centre.push(card);
+        }
+        self.target = Some(target);
+        self.shown_at = self.clock.now();
+        Ok(())
+    }
+}
+
+impl<C: Clock> GameMode for Practice<C> {
+    fn table(&self) -> &Table {
+        &self.table
+    }
+
+    fn table_mut(&mut self) -> &mut Table {
+        &mut self.table
+    }
+
+    fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+
+    fn deal(&mut self) -> Result<(), String> {
+        self.deal()
+    }
+
+    fn chances(&self, player: PlayerId) -> Vec<Vec<&SpotItCard>> {
+        match (player, self.pair()) {
+            (0, Some((card, other))) => vec![vec![card, other]],
+            _ => Vec::new(),
+        }
+    }
+
+    /// The time is recorded against the symbol practised, and the next pair is shown unless the session is over.
+    fn resolve(&mut self, _player: PlayerId, _chance: usize) -> Result<(), String> {
+        if let Some(target) = self.target {
+            self.profile.record_spot(target, self.elapsed());
+        }
+        self.played += 1;
+        match self.is_over() {
+            true => Ok(()),
+            false => self.show_next(),
+        }
+    }
+
+    fn miss(&mut self, _player: PlayerId) -> Result<(), String> {
+        if let Some(target) = self.target {
+            self.profile.record_miss(target);
+        }
+        Ok(())
+    }
+
+    fn is_over(&self) -> bool {
+        self.played >= self.rounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::ManualClock;
+    use crate::game::common_symbol;
+    use crate::Deck;
+
+    fn answer<C: Clock>(practice: &Practice<C>) -> SpotItSymbol {
+        let (card, other) = practice.pair().unwrap();
+        common_symbol(card, other).unwrap()
+    }
+
+    #[test]
+    fn pairs_share_the_weakest_symbol_due() {
+        let mut profile = Profile::default();
+        profile.record_spot(SpotItSymbol::Apple, Duration::from_secs(1));
+        profile.record_spot(SpotItSymbol::Fig, Duration::from_secs(9));
+        profile.record_miss(SpotItSymbol::Fig);
+        let clock = ManualClock::new();
+        let mut practice = Practice::new(SpotItDeck::default(), "Ann", profile, &clock, 1).unwrap();
+        assert_eq!(practice.pair(), None);
+        practice.deal().unwrap();
+        assert_eq!(answer(&practice), SpotItSymbol::Fig);
+
+        clock.advance(Duration::from_secs(2));
+        practice.claim(SpotItSymbol::Fig).unwrap();
+        let fig = practice.profile().stats(SpotItSymbol::Fig);
+        assert_eq!(fig.spotted, 2);
+        assert_eq!(fig.total_time, Duration::from_secs(11));
+        // The deck is kept whole
+        assert_eq!(practice.table().draw_pile.len(), 55);
+        assert_eq!(practice.table().centre.len(), 2);
+    }
+    #[test]
+    fn misses_are_recorded_and_sessions_end() {
+        let clock = ManualClock::new();
+        let mut practice = Practice::new(
+            SpotItDeck::generate_by_prime(3).unwrap(),
+            "Ann",
+            Profile::default(),
+            &clock,
+            2,
+        )
+        .unwrap()
+        .with_rounds(3);
+        practice.deal().unwrap();
+        let target = answer(&practice);
+        let (card, other) = practice.pair().unwrap();
+        let wrong = *card.0.iter().find(|s| !other.0.contains(s)).unwrap();
+        assert!(matches!(practice.claim(wrong), Ok(Verdict::Wrong(_))));
+        assert_eq!(practice.profile().stats(target).misses, 1);
+        for _ in 0..3 {
+            practice.claim(answer(&practice)).unwrap();
+        }
+        assert_eq!(practice.phase(), Phase::Finished);
+        assert_eq!(practice.progress(), (3, 3));
+        assert_eq!(practice.table().players[0].score, 3);
+        assert_eq!(practice.profile().step, 3);
+        assert!(practice.claim(target).is_err());
+        assert!(Practice::new(SpotItDeck::new(), "Ann", Profile::default(), &clock, 0).is_err());
+    }
+}
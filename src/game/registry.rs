@@ -0,0 +1,363 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Registry of the game modes of SpotIt! in `cardgame` crate.
+//!
+//! A `Registry` lists game modes by name, with a line describing each, and seats players at a game of the one chosen, e.g. by `cardgame simulate --mode`.
+//! `Registry::builtin` holds every mode of this crate. House rules made outside it implement `GameMode` and are registered next to them with `Registry::register`.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+
+use super::clock::SystemClock;
+use super::hot_potato::{HotPotato, OFFICIAL_ROUNDS};
+use super::poisoned_gift::PoisonedGift;
+use super::solo::Solo;
+use super::tower::Tower;
+use super::triplet::Triplet;
+use super::well::Well;
+use super::{Game, GameMode};
+use crate::SpotItDeck;
+use std::fmt;
+
+/// This struct defines the options a game is set up with, which modes use or not as they need.
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub struct ModeOptions {
+    /// This is the number of rounds, for modes played in rounds.
+    pub rounds: usize,
+}
+
+impl Default for ModeOptions {
+    fn default() -> Self {
+        ModeOptions {
+            rounds: OFFICIAL_ROUNDS,
+        }
+    }
+}
+
+/// This is a function which seats the players at a game of a mode with the deck as the draw pile, ready to deal.
+pub type Factory =
+    Box<dyn Fn(SpotItDeck, &[&str], &ModeOptions) -> Result<Box<dyn GameMode>, String>>;
+
+/// This struct defines a game mode in a registry.
+pub struct ModeEntry {
+    name: String,
+    description: String,
+    factory: Factory,
+}
+
+impl ModeEntry {
+    /// This function returns the name the mode is chosen by.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// This function returns the line describing the mode.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// This function seats the players at a game of the mode, ready to deal.
+    pub fn start(
+        &self,
+        deck: SpotItDeck,
+        names: &[&str],
+        options: &ModeOptions,
+    ) -> Result<Box<dyn GameMode>, String> {
+        (self.factory)(deck, names, options)
+    }
+}
+
+impl fmt::Debug for ModeEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ModeEntry")
+            .field("name", &self.name)
+            .field("description", &self.description)
+            .finish()
+    }
+}
+
+/// This struct defines a registry of game modes, in the order they were registered.
+#[derive(Debug, Default)]
+pub struct Registry {
+    modes: Vec<ModeEntry>,
+}
+
+impl Registry {
+    /// This function creates a registry with no mode.
+    pub fn new() -> Self {
+        Registry { modes: Vec::new() }
+    }
+
+    /// This function creates a registry with every mode of this crate.
+    pub fn builtin() -> Self {
+        let mut registry = Registry::new();
+        let modes: [(&str, &str, Factory); 7] = [
+            (
+                "classic",
+                "Name the symbol shared by the two face up cards to win them.",
+                Box::new(|deck, names, _| Ok(Box::new(Game::new(deck, names)?))),
+            ),
+            (
+                "solo",
+                "Go through the deck alone against the clock.",
+                Box::new(|deck, names, _| match names {
+                    [name] => Ok(Box::new(Solo::new(deck, name, SystemClock::new())?)),
+                    _ => Err("Solo is played by a single player.".to_string()),
+                }),
+            ),
+            (
+                "tower",
+                "Match your card with the draw pile to build the tallest tower.",
+                Box::new(|deck, names, _| Ok(Box::new(Tower::new(deck, names)?))),
+            ),
+            (
+                "well",
+                "Match your card with the well to be the first to empty your pile.",
+                Box::new(|deck, names, _| Ok(Box::new(Well::new(deck, names)?))),
+            ),
+            (
+                "hot-potato",
+                "Pass your cards on over several rounds, and set aside the fewest.",
+                Box::new(|deck, names, options| {
+                    Ok(Box::new(HotPotato::new(deck, names, options.rounds)?))
+                }),
+            ),
+            (
+                "poisoned-gift",
+                "Give the gift away to end up holding the fewest cards.",
+                Box::new(|deck, names, _| Ok(Box::new(PoisonedGift::new(deck, names)?))),
+            ),
+            (
+                "triplet",
+                "Find three cards of the layout which share a symbol.",
+                Box::new(|deck, names, _| Ok(Box::new(Triplet::new(deck, names)?))),
+            ),
+        ];
+        for (name, description, factory) in modes {
+            registry.modes.push(ModeEntry {
+                name: name.to_string(),
+                description: description.to_string(),
+                factory,
+            });
+        }
+        registry
+    }
+
+    /// This function adds a mode under the name, which is lower cased with spaces and underscores turned into dashes.
+    /// It returns an error if the name is empty or already taken.
+    pub fn register<F>(&mut self, name: &str, description: &str, factory: F) -> Result<(), String>
+    where
+        F: Fn(SpotItDeck, &[&str], &ModeOptions) -> Result<Box<dyn GameMode>, String> + 'static,
+    {
+        let name = normalise(name);
+        if name.is_empty() {
+            return Err("A game mode needs a name.".to_string());
+        }
+        if self.get(&name).is_some() {
+            return Err(format!("There is already a game mode called {}.", name));
+        }
+        self.modes.push(ModeEntry {
+            name,
+            description: description.to_string(),
+            factory: Box::new(factory),
+        });
+        Ok(())
+    }
+
+    /// This function returns the modes, in the order they were registered.
+    pub fn modes(&self) -> &[ModeEntry] {
+        &self.modes
+    }
+
+    /// This function returns the names of the modes, in the order they were registered.
+    pub fn names(&self) -> Vec<&str> {
+        self.modes.iter().map(|m| m.name()).collect()
+    }
+
+    /// This function returns the mode with the name, ignoring case, or None if there is none.
+    pub fn get(&self, name: &str) -> Option<&ModeEntry> {
+        let wanted = normalise(name);
+        self.modes.iter().find(|m| m.name == wanted)
+    }
+
+    /// This function returns the mode with the name, or an error if there is none.
+    pub fn find(&self, name: &str) -> Result<&ModeEntry, String> {
+        self.get(name)
+            .ok_or_else(|| format!("Unknown game mode: {}.", name))
+    }
+
+    /// This function seats the players at a game of the mode with the name, ready to deal.
+    pub fn start(
+        &self,
+        name: &str,
+        deck: SpotItDeck,
+        names: &[&str],
+        options: &ModeOptions,
+    ) -> Result<Box<dyn GameMode>, String> {
+        self.find(name)?.start(deck, names, options)
+    }
+}
+
+impl fmt::Display for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self.modes.iter().map(|m| m.name.len()).max().unwrap_or(0);
+        for (i, mode) in self.modes.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{:<width$}  {}",
+                mode.name,
+                mode.description,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// This function returns the name a mode is registered under, e.g. "hot-potato" for "Hot_Potato".
+fn normalise(name: &str) -> String {
+    name.trim().to_lowercase().replace(['_', ' '], "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Phase, PlayerId, Table};
+    use crate::spotitcard::SpotItCard;
+    use crate::Deck;
+
+    /// This is a house rule: the classic game, where a right claim is worth two points and a wrong one costs a point.
+    struct Doubles(Game);
+
+    impl GameMode for Doubles {
+        fn table(&self) -> &Table {
+            GameMode::table(&self.0)
+        }
+        fn table_mut(&mut self) -> &mut Table {
+            self.0.table_mut()
+        }
+        fn phase(&self) -> Phase {
+            GameMode::phase(&self.0)
+        }
+        fn set_phase(&mut self, phase: Phase) {
+            self.0.set_phase(phase)
+        }
+        fn deal(&mut self) -> Result<(), String> {
+            self.0.deal()
+        }
+        fn chances(&self, player: PlayerId) -> Vec<Vec<&SpotItCard>> {
+            self.0.chances(player)
+        }
+        fn resolve(&mut self, player: PlayerId, chance: usize) -> Result<(), String> {
+            self.0.resolve(player, chance)
+        }
+        fn score(&mut self, player: PlayerId, _chance: usize) -> Result<(), String> {
+            self.table_mut().player_mut(player)?.score += 2;
+            Ok(())
+        }
+        fn miss(&mut self, player: PlayerId) -> Result<(), String> {
+            self.table_mut().player_mut(player)?.score -= 1;
+            Ok(())
+        }
+        fn is_over(&self) -> bool {
+            self.0.is_over()
+        }
+    }
+
+    #[test]
+    fn builtin_modes_are_listed_and_started() {
+        let registry = Registry::builtin();
+        assert_eq!(
+            registry.names(),
+            [
+                "classic",
+                "solo",
+                "tower",
+                "well",
+                "hot-potato",
+                "poisoned-gift",
+                "triplet"
+            ]
+        );
+        for name in registry.names() {
+            let players: &[&str] = match name {
+                "solo" => &["Ann"],
+                _ => &["Ann", "Bob"],
+            };
+            let mut game = registry
+                .start(
+                    name,
+                    SpotItDeck::default(),
+                    players,
+                    &ModeOptions::default(),
+                )
+                .unwrap();
+            assert_eq!(game.phase(), Phase::Setup, "{}", name);
+            game.deal().unwrap();
+            assert_eq!(game.phase(), Phase::Playing, "{}", name);
+            assert!(!game.chances(0).is_empty(), "{}", name);
+        }
+        assert_eq!(registry.get("Hot_Potato").unwrap().name(), "hot-potato");
+        assert_eq!(
+            registry.find("chess").unwrap_err(),
+            "Unknown game mode: chess."
+        );
+        assert!(registry
+            .start(
+                "solo",
+                SpotItDeck::default(),
+                &["Ann", "Bob"],
+                &ModeOptions::default()
+            )
+            .is_err());
+        assert!(registry.to_string().lines().count() == 7);
+    }
+    #[test]
+    fn house_rules_are_registered_and_played() {
+        let mut registry = Registry::builtin();
+        registry
+            .register("Doubles", "Two points a match.", |deck, names, _| {
+                Ok(Box::new(Doubles(Game::new(deck, names)?)))
+            })
+            .unwrap();
+        assert!(registry
+            .register("classic", "Again.", |deck, names, _| Ok(Box::new(
+                Game::new(deck, names)?
+            )))
+            .is_err());
+        assert!(registry
+            .register(" ", "", |_, _, _| Err(String::new()))
+            .is_err());
+        assert_eq!(
+            registry.modes().last().unwrap().description(),
+            "Two points a match."
+        );
+
+        let mut game = registry
+            .start(
+                "doubles",
+                SpotItDeck::generate_by_prime(2).unwrap(),
+                &["Ann", "Bob"],
+                &ModeOptions::default(),
+            )
+            .unwrap();
+        game.deal().unwrap();
+        let cards = game.chances(0).remove(0);
+        let symbol = crate::game::shared_symbol(&cards).unwrap();
+        let wrong = *cards[0].0.iter().find(|s| **s != symbol).unwrap();
+        game.claim_chance(1, 0, wrong).unwrap();
+        // 7 cards make 6 rounds
+        for _ in 0..6 {
+            let cards = game.chances(0).remove(0);
+            let symbol = crate::game::shared_symbol(&cards).unwrap();
+            game.claim_chance(0, 0, symbol).unwrap();
+        }
+        assert_eq!(game.phase(), Phase::Finished);
+        assert_eq!(game.table().players[0].score, 12);
+        assert_eq!(game.table().players[1].score, -1);
+        assert_eq!(game.winners(), vec![0]);
+    }
+}
@@ -0,0 +1,321 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Solo time-attack mode of SpotIt! in `cardgame` crate.
+//!
+//! A single player goes through the deck against the clock, as in the first goal of the README.
+//! Two cards are face up, i.e. the top of the centre pile and of the draw pile, and the time to name their common symbol is the reaction time for that match.
+//! A wrong answer adds a penalty to the reaction time of the match and counts against accuracy.
+//! At the end, a report gives the median and best reaction times and the accuracy.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt! Rules`]: https://www.ultraboardgames.com/spot-it/game-rules.php
+
+use super::clock::{Clock, SystemClock};
+use super::{GameMode, Phase, Pile, PlayerId, Table, Verdict};
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use crate::SpotItDeck;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// This is the time added to a match for every wrong answer, unless set otherwise.
+pub const DEFAULT_PENALTY: Duration = Duration::from_secs(2);
+
+/// This struct defines the report of a solo game.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SoloReport {
+    /// This is the number of matches found.
+    pub matches: usize,
+    /// This is the number of wrong answers.
+    pub wrong: usize,
+    /// This is the median reaction time, penalties included, or zero if no match was found.
+    pub median: Duration,
+    /// This is the best reaction time, penalties included, or zero if no match was found.
+    pub best: Duration,
+    /// This is the share of answers which were right, from 0 to 1, or 0 if there was no answer.
+    pub accuracy: f64,
+}
+
+/// This struct defines a solo game without its clock, e.g. to save it. See `Solo::state`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SoloState {
+    table: Table,
+    phase: Phase,
+    penalty: Duration,
+    elapsed: Duration,
+    times: Vec<Duration>,
+    wrong: usize,
+}
+
+/// This struct defines a solo time-attack game, timed by the clock `C`.
+#[derive(Debug, Clone)]
+pub struct Solo<C: Clock = SystemClock> {
+    table: Table,
+    phase: Phase,
+    clock: C,
+    penalty: Duration,
+    shown_at: Duration,
+    penalties: Duration,
+    times: Vec<Duration>,
+    wrong: usize,
+}
+
+impl<C: Clock> Solo<C> {
+    /// This function sits the player down with the deck as the draw pile, ready to deal.
+    /// The deck is used in the order given, so shuffle it first for a fresh game.
+    pub fn new(deck: SpotItDeck, name: &str, clock: C) -> Result<Self, String> {
+        if deck.cards.len() < 2 {
+            return Err("At least two cards are needed to play.".to_string());
+        }
+        Ok(Solo {
+            table: Table::new(deck, &[name])?,
+            phase: Phase::Setup,
+            clock,
+            penalty: DEFAULT_PENALTY,
+            shown_at: Duration::ZERO,
+            penalties: Duration::ZERO,
+            times: Vec::new(),
+            wrong: 0,
+        })
+    }
+
+    /// This function sets the time added to a match for every wrong answer.
+    pub fn with_penalty(mut self, penalty: Duration) -> Self {
+        self.penalty = penalty;
+        self
+    }
+
+    /// This function returns the table.
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    /// This function returns the phase of the game.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// This function turns the first card over onto the centre pile and starts timing the first match.
+    pub fn deal(&mut self) -> Result<(), String> {
+        if self.phase != Phase::Setup {
+            return Err("The game has already been dealt.".to_string());
+        }
+        self.table.move_top_card(Pile::Draw, Pile::Centre);
+        self.phase = Phase::Playing;
+        self.shown_at = self.clock.now();
+        Ok(())
+    }
+
+    /// This function returns the two face up cards, i.e. the top of the centre pile and of the draw pile.
+    pub fn face_up_cards(&self) -> Option<(&SpotItCard, &SpotItCard)> {
+        match self.phase {
+            Phase::Playing => Some((
+                self.table.top_card(Pile::Centre)?,
+                self.table.top_card(Pile::Draw)?,
+            )),
+            _ => None,
+        }
+    }
+
+    /// This function returns the time spent on the current match so far, penalties included.
+    pub fn elapsed(&self) -> Duration {
+        match self.phase {
+            Phase::Playing => self.clock.now().saturating_sub(self.shown_at) + self.penalties,
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// This function judges an answer.
+    /// A right answer records the reaction time of the match, takes the centre card and turns the next card over, and the game finishes when the draw pile runs out.
+    /// A wrong answer adds the penalty to the current match.
+    pub fn claim(&mut self, symbol: SpotItSymbol) -> Result<Verdict, String> {
+        self.claim_chance(0, 0, symbol)
+    }
+
+    /// This function returns the game without its clock. The time spent on the current match is kept in it.
+    pub fn state(&self) -> SoloState {
+        SoloState {
+            table: self.table.clone(),
+            phase: self.phase,
+            penalty: self.penalty,
+            elapsed: self.elapsed(),
+            times: self.times.clone(),
+            wrong: self.wrong,
+        }
+    }
+
+    /// This function goes on with a game from its state, timed by the clock from now on.
+    pub fn resume(state: SoloState, clock: C) -> Self {
+        Solo {
+            table: state.table,
+            phase: state.phase,
+            shown_at: clock.now(),
+            clock,
+            penalty: state.penalty,
+            // The time already spent counts as if it were penalties, which are cleared with the match
+            penalties: state.elapsed,
+            times: state.times,
+            wrong: state.wrong,
+        }
+    }
+
+    /// This function returns the reaction time of every match found so far, penalties included, in order.
+    pub fn reaction_times(&self) -> &[Duration] {
+        &self.times
+    }
+
+    /// This function returns the report of the matches found so far.
+    pub fn report(&self) -> SoloReport {
+        let mut sorted = self.times.clone();
+        sorted.sort();
+        let median = match sorted.len() {
+            0 => Duration::ZERO,
+            n if n % 2 == 1 => sorted[n / 2],
+            n => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+        };
+        let answers = self.times.len() + self.wrong;
+        SoloReport {
+            matches: self.times.len(),
+            wrong: self.wrong,
+            median,
+            best: sorted.first().copied().unwrap_or_default(),
+            accuracy: match answers {
+                0 => 0.0,
+                n => self.times.len() as f64 / n as f64,
+            },
+        }
+    }
+}
+
+impl<C: Clock> GameMode for Solo<C> {
+    fn table(&self) -> &Table {
+        &self.table
+    }
+
+    fn table_mut(&mut self) -> &mut Table {
+        &mut self.table
+    }
+
+    fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+
+    fn deal(&mut self) -> Result<(), String> {
+        self.deal()
+    }
+
+    fn chances(&self, player: PlayerId) -> Vec<Vec<&SpotItCard>> {
+        match (player, self.face_up_cards()) {
+            (0, Some((centre, draw))) => vec![vec![centre, draw]],
+            _ => Vec::new(),
+        }
+    }
+
+    /// The reaction time of the match is recorded before the next card is turned over and timed.
+    fn resolve(&mut self, player: PlayerId, _chance: usize) -> Result<(), String> {
+        self.times.push(self.elapsed());
+        self.table.move_top_card(Pile::Centre, Pile::Player(player));
+        self.table.move_top_card(Pile::Draw, Pile::Centre);
+        self.penalties = Duration::ZERO;
+        self.shown_at = self.clock.now();
+        Ok(())
+    }
+
+    fn miss(&mut self, _player: PlayerId) -> Result<(), String> {
+        self.wrong += 1;
+        self.penalties += self.penalty;
+        Ok(())
+    }
+
+    fn is_over(&self) -> bool {
+        self.table.draw_pile.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::ManualClock;
+    use crate::game::{common_symbol, Claim};
+    use crate::Deck;
+
+    fn answer<C: Clock>(solo: &Solo<C>) -> SpotItSymbol {
+        let (centre, draw) = solo.face_up_cards().unwrap();
+        common_symbol(centre, draw).unwrap()
+    }
+    fn wrong_answer<C: Clock>(solo: &Solo<C>) -> SpotItSymbol {
+        let (centre, draw) = solo.face_up_cards().unwrap();
+        *centre.0.iter().find(|s| !draw.0.contains(s)).unwrap()
+    }
+
+    #[test]
+    fn reports_reaction_times_and_accuracy() {
+        let clock = ManualClock::new();
+        let mut solo = Solo::new(SpotItDeck::generate_by_prime(2).unwrap(), "Ann", &clock).unwrap();
+        clock.advance(Duration::from_secs(5));
+        solo.deal().unwrap();
+
+        let seconds = [3, 1, 4, 1, 5, 9];
+        for (i, s) in seconds.iter().enumerate() {
+            clock.advance(Duration::from_secs(*s));
+            if i == 2 {
+                // One wrong answer on the third match
+                assert_eq!(
+                    solo.claim(wrong_answer(&solo)),
+                    Ok(Verdict::Wrong(Claim {
+                        player: 0,
+                        symbol: wrong_answer(&solo)
+                    }))
+                );
+                assert_eq!(solo.elapsed(), Duration::from_secs(6));
+            }
+            let symbol = answer(&solo);
+            assert_eq!(
+                solo.claim(symbol),
+                Ok(Verdict::Correct(Claim { player: 0, symbol }))
+            );
+        }
+        assert_eq!(solo.phase(), Phase::Finished);
+        assert!(solo.claim(SpotItSymbol::Apple).is_err());
+        assert_eq!(
+            solo.reaction_times(),
+            [3, 1, 6, 1, 5, 9].map(Duration::from_secs)
+        );
+        let report = solo.report();
+        assert_eq!(report.matches, 6);
+        assert_eq!(report.wrong, 1);
+        assert_eq!(report.median, Duration::from_millis(4000));
+        assert_eq!(report.best, Duration::from_secs(1));
+        assert_eq!(report.accuracy, 6.0 / 7.0);
+        assert_eq!(solo.table().players[0].score, 6);
+    }
+    #[test]
+    fn penalty_can_be_set() {
+        let clock = ManualClock::new();
+        let mut solo = Solo::new(SpotItDeck::default(), "Ann", &clock)
+            .unwrap()
+            .with_penalty(Duration::from_millis(500));
+        assert!(solo.claim(SpotItSymbol::Apple).is_err());
+        solo.deal().unwrap();
+        solo.claim(wrong_answer(&solo)).unwrap();
+        solo.claim(wrong_answer(&solo)).unwrap();
+        clock.advance(Duration::from_millis(700));
+        solo.claim(answer(&solo)).unwrap();
+        assert_eq!(solo.reaction_times(), [Duration::from_millis(1700)]);
+        assert_eq!(solo.elapsed(), Duration::ZERO);
+        assert_eq!(solo.report().median, Duration::from_millis(1700));
+    }
+    #[test]
+    fn empty_report_has_no_times() {
+        let solo = Solo::new(SpotItDeck::default(), "Ann", SystemClock::new()).unwrap();
+        let report = solo.report();
+        assert_eq!(report.matches, 0);
+        assert_eq!(report.median, Duration::ZERO);
+        assert_eq!(report.accuracy, 0.0);
+        assert!(Solo::new(SpotItDeck::new(), "Ann", SystemClock::new()).is_err());
+    }
+}
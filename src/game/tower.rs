@@ -0,0 +1,199 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! "The Tower" mode of SpotIt! in `cardgame` crate.
+//!
+//! Every player is dealt one card face up, and the rest of the deck is the draw pile with its top card face up.
+//! The first player to name the symbol shared by their own card and the top of the draw pile takes that card onto their tower, which makes it their new card.
+//! The game ends when the draw pile runs out, and the player with the most cards wins.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt! Rules`]: https://www.ultraboardgames.com/spot-it/game-rules.php
+
+use super::{GameMode, Phase, Pile, PlayerId, Table, Verdict};
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use crate::SpotItDeck;
+use serde::{Deserialize, Serialize};
+
+/// This struct defines a game of "The Tower".
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Tower {
+    table: Table,
+    phase: Phase,
+}
+
+impl Tower {
+    /// This function seats the players at a table with the deck as the draw pile, ready to deal.
+    /// It returns an error if there are not enough cards to deal one to every player and leave a draw pile.
+    pub fn new(deck: SpotItDeck, names: &[&str]) -> Result<Self, String> {
+        if deck.cards.len() <= names.len() {
+            return Err("There must be more cards than players.".to_string());
+        }
+        Ok(Tower {
+            table: Table::new(deck, names)?,
+            phase: Phase::Setup,
+        })
+    }
+
+    /// This function returns the table.
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    /// This function returns the phase of the game.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// This function deals one card face up to every player.
+    pub fn deal(&mut self) -> Result<(), String> {
+        if self.phase != Phase::Setup {
+            return Err("The game has already been dealt.".to_string());
+        }
+        self.table.deal_one_each()?;
+        self.phase = Phase::Playing;
+        Ok(())
+    }
+
+    /// This function returns the face up card on top of the draw pile, which every player looks for a match with.
+    pub fn face_up_card(&self) -> Option<&SpotItCard> {
+        match self.phase {
+            Phase::Playing => self.table.top_card(Pile::Draw),
+            _ => None,
+        }
+    }
+
+    /// This function judges a claim. The symbol must be on both the player's own card and the top of the draw pile.
+    /// A correct claim moves the top of the draw pile onto the player's tower, and the game finishes when the draw pile runs out.
+    pub fn claim(&mut self, player: PlayerId, symbol: SpotItSymbol) -> Result<Verdict, String> {
+        self.claim_chance(player, 0, symbol)
+    }
+
+    /// This function returns the height of every player's tower, in seating order.
+    pub fn tower_heights(&self) -> Vec<usize> {
+        self.table.players.iter().map(|p| p.pile.len()).collect()
+    }
+
+    /// This function returns the winners once the game has finished, i.e. the players with the tallest tower.
+    pub fn winners(&self) -> Vec<PlayerId> {
+        GameMode::winners(self)
+    }
+}
+
+impl GameMode for Tower {
+    fn table(&self) -> &Table {
+        &self.table
+    }
+
+    fn table_mut(&mut self) -> &mut Table {
+        &mut self.table
+    }
+
+    fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+
+    fn deal(&mut self) -> Result<(), String> {
+        self.deal()
+    }
+
+    fn chances(&self, player: PlayerId) -> Vec<Vec<&SpotItCard>> {
+        let own = self.table.top_card(Pile::Player(player));
+        match (own, self.face_up_card()) {
+            (Some(own), Some(face_up)) => vec![vec![own, face_up]],
+            _ => Vec::new(),
+        }
+    }
+
+    fn resolve(&mut self, player: PlayerId, _chance: usize) -> Result<(), String> {
+        self.table.move_top_card(Pile::Draw, Pile::Player(player));
+        Ok(())
+    }
+
+    fn is_over(&self) -> bool {
+        self.table.draw_pile.is_empty()
+    }
+
+    fn winners(&self) -> Vec<PlayerId> {
+        if self.phase != Phase::Finished {
+            return Vec::new();
+        }
+        self.table.leaders_by(|p| p.pile.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{common_symbol, Claim};
+    use crate::Deck;
+
+    fn match_for(tower: &Tower, player: PlayerId) -> SpotItSymbol {
+        let own = tower.table().players[player].top_card().unwrap();
+        common_symbol(own, tower.face_up_card().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn can_play_tower_to_the_end() {
+        let mut tower = Tower::new(SpotItDeck::default(), &["Ann", "Bob", "Cat"]).unwrap();
+        assert_eq!(tower.face_up_card(), None);
+        tower.deal().unwrap();
+        assert_eq!(tower.tower_heights(), vec![1, 1, 1]);
+        assert_eq!(tower.table().draw_pile.len(), 54);
+
+        // Ann spots every other card, Bob the rest
+        let mut turn = 0;
+        while tower.phase() == Phase::Playing {
+            let player = turn % 2;
+            let symbol = match_for(&tower, player);
+            assert_eq!(
+                tower.claim(player, symbol),
+                Ok(Verdict::Correct(Claim { player, symbol }))
+            );
+            turn += 1;
+        }
+        assert_eq!(turn, 54);
+        assert_eq!(tower.tower_heights(), vec![28, 28, 1]);
+        assert_eq!(tower.winners(), vec![0, 1]);
+        assert!(tower.claim(0, SpotItSymbol::Apple).is_err());
+    }
+    #[test]
+    fn claim_must_match_own_card() {
+        let mut tower = Tower::new(SpotItDeck::default(), &["Ann", "Bob"]).unwrap();
+        assert!(tower.claim(0, SpotItSymbol::Apple).is_err());
+        tower.deal().unwrap();
+        let bobs = match_for(&tower, 1);
+        let anns = match_for(&tower, 0);
+        if bobs != anns {
+            // Bob's match is not on Ann's card, so Ann cannot claim it
+            assert_eq!(
+                tower.claim(0, bobs),
+                Ok(Verdict::Wrong(Claim {
+                    player: 0,
+                    symbol: bobs
+                }))
+            );
+        }
+        assert!(tower.claim(2, anns).is_err());
+        assert_eq!(tower.tower_heights(), vec![1, 1]);
+        assert_eq!(tower.winners(), Vec::<PlayerId>::new());
+        tower.claim(1, bobs).unwrap();
+        assert_eq!(tower.tower_heights(), vec![1, 2]);
+        assert_eq!(tower.table().players[1].score, 1);
+    }
+    #[test]
+    fn cannot_play_without_draw_pile() {
+        assert!(Tower::new(SpotItDeck::generate_by_prime(1).unwrap(), &["A", "B", "C"]).is_err());
+        let mut tower = Tower::new(SpotItDeck::generate_by_prime(1).unwrap(), &["A", "B"]).unwrap();
+        tower.deal().unwrap();
+        assert!(tower.deal().is_err());
+        let symbol = match_for(&tower, 0);
+        tower.claim(0, symbol).unwrap();
+        assert_eq!(tower.phase(), Phase::Finished);
+        assert_eq!(tower.winners(), vec![0]);
+    }
+}
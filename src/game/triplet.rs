@@ -0,0 +1,265 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! "Triplet" mode of SpotIt! in `cardgame` crate.
+//!
+//! Nine cards are laid out face up in the centre, and the rest of the deck is the draw pile.
+//! The first player to point at three cards of the layout which all share the same symbol, and to name it, takes the three cards and gets a point.
+//! The taken cards are replaced from the draw pile. When the layout has no triplet, cards are added from the draw pile until it has one.
+//! The game ends when no triplet can be found and the draw pile has run out, and the player with the most points wins.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt! Rules`]: https://www.ultraboardgames.com/spot-it/game-rules.php
+
+use super::{GameMode, Phase, Pile, PlayerId, Table, Verdict};
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use crate::SpotItDeck;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+/// This is the number of cards laid out when the draw pile allows.
+pub const LAYOUT_SIZE: usize = 9;
+
+/// This struct defines a game of "Triplet".
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Triplet {
+    table: Table,
+    phase: Phase,
+}
+
+impl Triplet {
+    /// This function seats the players at a table with the deck as the draw pile, ready to deal.
+    /// It returns an error if there are not enough cards for a layout.
+    pub fn new(deck: SpotItDeck, names: &[&str]) -> Result<Self, String> {
+        if deck.cards.len() < LAYOUT_SIZE {
+            return Err(format!("At least {} cards are needed.", LAYOUT_SIZE));
+        }
+        Ok(Triplet {
+            table: Table::new(deck, names)?,
+            phase: Phase::Setup,
+        })
+    }
+
+    /// This function returns the table.
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    /// This function returns the phase of the game.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// This function lays out nine cards in the centre, and more if they have no triplet.
+    pub fn deal(&mut self) -> Result<(), String> {
+        if self.phase != Phase::Setup {
+            return Err("The game has already been dealt.".to_string());
+        }
+        for _ in 0..LAYOUT_SIZE {
+            self.table.move_top_card(Pile::Draw, Pile::Centre);
+        }
+        self.phase = Phase::Playing;
+        self.refill();
+        Ok(())
+    }
+
+    /// This function returns the layout, i.e. the face up cards in the centre. Claims refer to cards by their position in it.
+    pub fn layout(&self) -> &[SpotItCard] {
+        &self.table.centre
+    }
+
+    /// This function returns every triplet in the layout. See `SpotItDeck::triplets`.
+    pub fn triplets(&self) -> Vec<(SpotItSymbol, [usize; 3])> {
+        SpotItDeck::triplets(self.layout())
+    }
+
+    /// This function judges a claim that the three cards at the positions all show the symbol.
+    /// It returns an error if a position is outside the layout or given twice.
+    /// A correct claim gives the three cards to the player, who scores a point, and refills the layout.
+    pub fn claim(
+        &mut self,
+        player: PlayerId,
+        mut cards: [usize; 3],
+        symbol: SpotItSymbol,
+    ) -> Result<Verdict, String> {
+        if self.phase != Phase::Playing {
+            return Err("Claims are only accepted while playing.".to_string());
+        }
+        self.table.player(player)?;
+        let layout = self.layout();
+        if let Some(position) = cards.iter().find(|i| **i >= layout.len()) {
+            return Err(format!("There is no card at position {}.", position));
+        }
+        if cards[0] == cards[1] || cards[0] == cards[2] || cards[1] == cards[2] {
+            return Err("The three cards must be different.".to_string());
+        }
+        cards.sort();
+        let chance = self
+            .positions()
+            .iter()
+            .position(|c| *c == cards)
+            .ok_or_else(|| "The cards cannot be claimed.".to_string())?;
+        self.claim_chance(player, chance, symbol)
+    }
+
+    /// This function adds cards from the draw pile until the layout has a triplet, and finishes the game if it never does.
+    fn refill(&mut self) {
+        while self.triplets().is_empty() {
+            if !self.table.move_top_card(Pile::Draw, Pile::Centre) {
+                self.phase = Phase::Finished;
+                return;
+            }
+        }
+    }
+
+    /// This function returns the winners once the game has finished, i.e. the players with the most points.
+    pub fn winners(&self) -> Vec<PlayerId> {
+        GameMode::winners(self)
+    }
+}
+
+impl Triplet {
+    /// This function returns every three positions in the layout, in order.
+    fn positions(&self) -> Vec<[usize; 3]> {
+        match self.phase {
+            Phase::Playing => (0..self.layout().len())
+                .combinations(3)
+                .map(|c| [c[0], c[1], c[2]])
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl GameMode for Triplet {
+    fn table(&self) -> &Table {
+        &self.table
+    }
+
+    fn table_mut(&mut self) -> &mut Table {
+        &mut self.table
+    }
+
+    fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+
+    fn deal(&mut self) -> Result<(), String> {
+        self.deal()
+    }
+
+    /// Every three cards of the layout are a chance, whether they share a symbol or not.
+    fn chances(&self, _player: PlayerId) -> Vec<Vec<&SpotItCard>> {
+        let layout = self.layout();
+        self.positions()
+            .into_iter()
+            .map(|cards| cards.iter().map(|i| &layout[*i]).collect())
+            .collect()
+    }
+
+    /// The three cards go to the player and are replaced from the draw pile, then the layout is refilled.
+    fn resolve(&mut self, player: PlayerId, chance: usize) -> Result<(), String> {
+        let positions = *self
+            .positions()
+            .get(chance)
+            .ok_or_else(|| format!("There is no chance {}.", chance))?;
+        // Take from the back so that the other positions stay put
+        for position in positions.into_iter().rev() {
+            let card = match self.table.draw_pile.pop() {
+                Some(card) if self.table.centre.len() <= LAYOUT_SIZE => {
+                    std::mem::replace(&mut self.table.centre[position], card)
+                }
+                drawn => {
+                    self.table.draw_pile.extend(drawn);
+                    self.table.centre.remove(position)
+                }
+            };
+            self.table.players[player].pile.push(card);
+        }
+        self.refill();
+        Ok(())
+    }
+
+    fn is_over(&self) -> bool {
+        self.triplets().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Claim;
+    use crate::Deck;
+
+    #[test]
+    fn can_play_triplet_to_the_end() {
+        let mut game = Triplet::new(SpotItDeck::default(), &["Ann", "Bob"]).unwrap();
+        assert!(game.layout().is_empty());
+        game.deal().unwrap();
+        assert!(game.layout().len() >= LAYOUT_SIZE);
+        assert!(!game.triplets().is_empty());
+
+        let mut turn = 0;
+        while game.phase() == Phase::Playing {
+            let (symbol, cards) = game.triplets()[0];
+            let player = turn % 2;
+            assert_eq!(
+                game.claim(player, cards, symbol),
+                Ok(Verdict::Correct(Claim { player, symbol }))
+            );
+            turn += 1;
+        }
+        assert!(game.triplets().is_empty());
+        assert!(game.table().draw_pile.is_empty());
+        let taken: usize = game.table().players.iter().map(|p| p.pile.len()).sum();
+        assert_eq!(taken, turn * 3);
+        assert_eq!(taken + game.layout().len(), 57);
+        assert!(game.claim(0, [0, 1, 2], SpotItSymbol::Apple).is_err());
+    }
+    #[test]
+    fn taken_cards_are_replaced_in_place() {
+        let mut game = Triplet::new(SpotItDeck::default(), &["Ann"]).unwrap();
+        game.deal().unwrap();
+        let before = game.layout().to_vec();
+        let (symbol, cards) = game.triplets()[0];
+        game.claim(0, cards, symbol).unwrap();
+        for (i, card) in before.iter().enumerate() {
+            assert_eq!(cards.contains(&i), &game.layout()[i] != card);
+        }
+        assert_eq!(game.table().players[0].pile.len(), 3);
+        assert_eq!(game.table().players[0].score, 1);
+    }
+    #[test]
+    fn claim_must_show_symbol_on_all_three_cards() {
+        let mut game = Triplet::new(SpotItDeck::default(), &["Ann"]).unwrap();
+        assert!(game.claim(0, [0, 1, 2], SpotItSymbol::Apple).is_err());
+        game.deal().unwrap();
+        let (symbol, cards) = game.triplets()[0];
+        let other = (0..game.layout().len())
+            .find(|i| !game.layout()[*i].0.contains(&symbol))
+            .unwrap();
+        assert_eq!(
+            game.claim(0, [cards[0], cards[1], other], symbol),
+            Ok(Verdict::Wrong(Claim { player: 0, symbol }))
+        );
+        assert!(game
+            .claim(0, [cards[0], cards[0], cards[1]], symbol)
+            .is_err());
+        assert!(game.claim(0, [cards[0], cards[1], 99], symbol).is_err());
+        assert!(game.claim(1, cards, symbol).is_err());
+        assert_eq!(game.table().players[0].score, 0);
+    }
+    #[test]
+    fn cannot_play_without_a_layout() {
+        assert!(Triplet::new(SpotItDeck::generate_by_prime(2).unwrap(), &["Ann"]).is_err());
+        // 13 cards: every symbol is on 4 of them, so a triplet always exists
+        let mut game = Triplet::new(SpotItDeck::generate_by_prime(3).unwrap(), &["Ann"]).unwrap();
+        game.deal().unwrap();
+        assert!(game.deal().is_err());
+        assert_eq!(game.phase(), Phase::Playing);
+    }
+}
@@ -0,0 +1,306 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! "The Well" mode of SpotIt! in `cardgame` crate.
+//!
+//! One card is put face up in the centre as the well and the rest of the deck is dealt out to the players, who turn their piles face up.
+//! A player discards the top card of their pile onto the well by naming the symbol it shares with the card on top of the well.
+//! The first player to empty their pile wins.
+//!
+//! Claims shouted at the same moment are all judged against the same card on top of the well, and only the first right one is taken.
+//! The other right claims are late, since the well has changed under them, and those players keep their card.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt! Rules`]: https://www.ultraboardgames.com/spot-it/game-rules.php
+
+use super::{Claim, GameMode, Phase, Pile, PlayerId, Table, Verdict};
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use crate::SpotItDeck;
+use serde::{Deserialize, Serialize};
+
+/// This struct defines a game of "The Well".
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Well {
+    table: Table,
+    phase: Phase,
+}
+
+impl Well {
+    /// This function seats the players at a table with the deck as the draw pile, ready to deal.
+    /// It returns an error if there are not enough cards to give every player at least one and keep one for the well.
+    pub fn new(deck: SpotItDeck, names: &[&str]) -> Result<Self, String> {
+        if deck.cards.len() <= names.len() {
+            return Err("There must be more cards than players.".to_string());
+        }
+        Ok(Well {
+            table: Table::new(deck, names)?,
+            phase: Phase::Setup,
+        })
+    }
+
+    /// This function returns the table.
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    /// This function returns the phase of the game.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// This function deals the whole deck out to the players one card at a time, except the last card which starts the well.
+    /// Piles may differ by one card when the deck does not share out evenly.
+    pub fn deal(&mut self) -> Result<(), String> {
+        if self.phase != Phase::Setup {
+            return Err("The game has already been dealt.".to_string());
+        }
+        let mut player = 0;
+        while self.table.draw_pile.len() > 1 {
+            self.table.move_top_card(Pile::Draw, Pile::Player(player));
+            player = (player + 1) % self.table.players.len();
+        }
+        self.table.move_top_card(Pile::Draw, Pile::Centre);
+        self.phase = Phase::Playing;
+        Ok(())
+    }
+
+    /// This function returns the card on top of the well.
+    pub fn well_card(&self) -> Option<&SpotItCard> {
+        self.table.top_card(Pile::Centre)
+    }
+
+    /// This function judges a claim. See `claim_together`.
+    pub fn claim(&mut self, player: PlayerId, symbol: SpotItSymbol) -> Result<Verdict, String> {
+        let verdicts = self.claim_together(&[Claim { player, symbol }])?;
+        Ok(verdicts[0])
+    }
+
+    /// This function judges claims shouted at the same moment, in the order given, and returns a verdict for each of them.
+    /// Every claim is checked against the well as it was when they were shouted. The first right claim discards the player's top card onto the well, and later right claims are late.
+    /// The game finishes as soon as a player's pile runs out, and any claim after that is late too.
+    pub fn claim_together(&mut self, claims: &[Claim]) -> Result<Vec<Verdict>, String> {
+        if self.phase != Phase::Playing {
+            return Err("Claims are only accepted while playing.".to_string());
+        }
+        for claim in claims {
+            self.table.player(claim.player)?;
+        }
+        let well = self
+            .well_card()
+            .cloned()
+            .ok_or_else(|| "There is no card in the well.".to_string())?;
+        // Top cards are taken before any discard, as that is what the players were looking at
+        let own_cards: Vec<Option<SpotItCard>> = claims
+            .iter()
+            .map(|c| self.table.players[c.player].top_card().cloned())
+            .collect();
+
+        let mut taken = false;
+        let mut verdicts = Vec::new();
+        for (claim, own) in claims.iter().zip(own_cards) {
+            let right = own.is_some_and(|own| own.0.contains(&claim.symbol))
+                && well.0.contains(&claim.symbol);
+            let verdict = match (right, taken) {
+                (false, _) => Verdict::Wrong(*claim),
+                (true, true) => Verdict::Late(*claim),
+                (true, false) => {
+                    taken = true;
+                    self.resolve(claim.player, 0)?;
+                    self.score(claim.player, 0)?;
+                    if self.is_over() {
+                        self.phase = Phase::Finished;
+                    }
+                    Verdict::Correct(*claim)
+                }
+            };
+            verdicts.push(verdict);
+        }
+        Ok(verdicts)
+    }
+
+    /// This function returns the number of cards left in every player's pile, in seating order.
+    pub fn pile_sizes(&self) -> Vec<usize> {
+        self.table.players.iter().map(|p| p.pile.len()).collect()
+    }
+
+    /// This function returns the winner once the game has finished, i.e. the player who emptied their pile.
+    pub fn winners(&self) -> Vec<PlayerId> {
+        GameMode::winners(self)
+    }
+}
+
+impl GameMode for Well {
+    fn table(&self) -> &Table {
+        &self.table
+    }
+
+    fn table_mut(&mut self) -> &mut Table {
+        &mut self.table
+    }
+
+    fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+
+    fn deal(&mut self) -> Result<(), String> {
+        self.deal()
+    }
+
+    fn chances(&self, player: PlayerId) -> Vec<Vec<&SpotItCard>> {
+        if self.phase != Phase::Playing {
+            return Vec::new();
+        }
+        let own = self.table.top_card(Pile::Player(player));
+        match (own, self.well_card()) {
+            (Some(own), Some(well)) => vec![vec![own, well]],
+            _ => Vec::new(),
+        }
+    }
+
+    fn resolve(&mut self, player: PlayerId, _chance: usize) -> Result<(), String> {
+        self.table.move_top_card(Pile::Player(player), Pile::Centre);
+        Ok(())
+    }
+
+    /// Discarding is its own reward, so no point is scored.
+    fn score(&mut self, _player: PlayerId, _chance: usize) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn is_over(&self) -> bool {
+        self.table.players.iter().any(|p| p.pile.is_empty())
+    }
+
+    /// Losing a card would bring the player closer to winning, so they draw the top card of the well under their pile instead, as long as the well keeps a card.
+    fn lose_card(&mut self, player: PlayerId) -> Result<(), String> {
+        self.table.player(player)?;
+        if self.table.centre.len() > 1 {
+            if let Some(card) = self.table.centre.pop() {
+                self.table.players[player].pile.insert(0, card);
+            }
+        }
+        Ok(())
+    }
+
+    fn winners(&self) -> Vec<PlayerId> {
+        match self.phase {
+            Phase::Finished => (0..self.table.players.len())
+                .filter(|i| self.table.players[*i].pile.is_empty())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::common_symbol;
+    use crate::Deck;
+
+    fn match_for(well: &Well, player: PlayerId) -> SpotItSymbol {
+        let own = well.table().players[player].top_card().unwrap();
+        common_symbol(own, well.well_card().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn deals_all_but_one_card() {
+        let mut well = Well::new(SpotItDeck::default(), &["Ann", "Bob", "Cat"]).unwrap();
+        assert_eq!(well.well_card(), None);
+        well.deal().unwrap();
+        assert_eq!(well.pile_sizes(), vec![19, 19, 18]);
+        assert_eq!(well.table().centre.len(), 1);
+        assert!(well.table().draw_pile.is_empty());
+        assert!(well.deal().is_err());
+    }
+    #[test]
+    fn first_to_empty_pile_wins() {
+        let mut well =
+            Well::new(SpotItDeck::generate_by_prime(2).unwrap(), &["Ann", "Bob"]).unwrap();
+        assert!(well.claim(0, SpotItSymbol::Apple).is_err());
+        well.deal().unwrap();
+        assert_eq!(well.pile_sizes(), vec![3, 3]);
+        for _ in 0..3 {
+            let symbol = match_for(&well, 1);
+            assert_eq!(
+                well.claim(1, symbol),
+                Ok(Verdict::Correct(Claim { player: 1, symbol }))
+            );
+        }
+        assert_eq!(well.pile_sizes(), vec![3, 0]);
+        assert_eq!(well.phase(), Phase::Finished);
+        assert_eq!(well.winners(), vec![1]);
+        assert!(well.claim(0, SpotItSymbol::Apple).is_err());
+    }
+    #[test]
+    fn only_first_of_simultaneous_claims_is_taken() {
+        let mut well = Well::new(SpotItDeck::default(), &["Ann", "Bob", "Cat"]).unwrap();
+        well.deal().unwrap();
+        let well_card = well.well_card().unwrap().clone();
+        let claims = [
+            Claim {
+                player: 2,
+                symbol: *well_card
+                    .0
+                    .iter()
+                    .find(|s| !well.table().players[2].top_card().unwrap().0.contains(s))
+                    .unwrap(),
+            },
+            Claim {
+                player: 1,
+                symbol: match_for(&well, 1),
+            },
+            Claim {
+                player: 0,
+                symbol: match_for(&well, 0),
+            },
+        ];
+        let verdicts = well.claim_together(&claims).unwrap();
+        assert_eq!(
+            verdicts,
+            vec![
+                Verdict::Wrong(claims[0]),
+                Verdict::Correct(claims[1]),
+                Verdict::Late(claims[2]),
+            ]
+        );
+        assert_eq!(well.pile_sizes(), vec![19, 18, 18]);
+        assert_eq!(well.table().centre.len(), 2);
+        assert!(well
+            .claim_together(&[Claim {
+                player: 3,
+                symbol: SpotItSymbol::Apple
+            }])
+            .is_err());
+    }
+    #[test]
+    fn game_ends_when_pile_runs_out_mid_round() {
+        // 3 cards: one each and one in the well
+        let mut well =
+            Well::new(SpotItDeck::generate_by_prime(1).unwrap(), &["Ann", "Bob"]).unwrap();
+        well.deal().unwrap();
+        assert_eq!(well.pile_sizes(), vec![1, 1]);
+        let claims = [
+            Claim {
+                player: 0,
+                symbol: match_for(&well, 0),
+            },
+            Claim {
+                player: 1,
+                symbol: match_for(&well, 1),
+            },
+        ];
+        let verdicts = well.claim_together(&claims).unwrap();
+        assert_eq!(
+            verdicts,
+            vec![Verdict::Correct(claims[0]), Verdict::Late(claims[1])]
+        );
+        assert_eq!(well.phase(), Phase::Finished);
+        assert_eq!(well.winners(), vec![0]);
+        assert_eq!(well.pile_sizes(), vec![0, 1]);
+    }
+}
@@ -19,7 +19,24 @@ use frenchcard::{FrenchCard, FrenchRank, FrenchSuit};
 pub mod spotitcard;
 use spotitcard::{SpotItCard, SpotItSymbol};
 
+pub mod cli;
+pub mod deckfile;
+pub mod difficulty;
+pub mod game;
+pub mod locale;
+pub mod profile;
+pub mod raster;
+pub mod replay;
+pub mod save;
+pub mod simulation;
+pub mod svg;
+pub mod terminal;
+pub mod theme;
+pub mod tournament;
+pub mod tui;
+
 use fraction::Fraction;
+use itertools::Itertools;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 use std::collections::HashSet;
@@ -141,14 +158,19 @@ impl Deck<SpotItCard> for SpotItDeck {
 
 impl SpotItDeck {
     /// This function generates a deck of SpotIt Cards by a prime number n.
+    /// It returns an error if n is 0, or too large for the symbols there are.
     pub fn generate_by_prime(n: u8) -> Result<SpotItDeck, String> {
+        // The size is computed in usize, since n^2 + n + 1 overflows u8 from n = 16
+        let size = n as usize * n as usize + n as usize + 1;
+        if n == 0 {
+            Err("The order must be at least 1.".to_string())
         // Check if the n is too large for defaul symbols to generate deck
         // n^2 + n + 1 <= default symbols
-        if (n * n + n + 1) as usize > SpotItSymbol::iter().count() {
+        } else if size > SpotItSymbol::iter().count() {
             Err("n is too large, hence not enough symobls to generate deck.".to_string())
         // Check if the n is prime
         } else if let (false, _) = prime_checker::is_prime(n as u64) {
-            Err("{} is not prime".to_string())
+            Err(format!("{} is not prime", n))
 
         // handle n = 1 edge case
         } else if n == 1 {
@@ -183,44 +205,33 @@ impl SpotItDeck {
             let slopes = SpotItDeck::cal_slope(n);
 
             for slope in slopes.iter() {
-                println!("slope: {:?}", slope);
                 let line_symbol = *(line_iter.next().unwrap());
                 for c in 0..n {
                     let mut symbol_on_plane = HashSet::new();
-                    // loop around an enlarged plane to find the symbols on the line
-                    // FIXME: this is a hacky way to loop around the plane, find a better way to do this
-                    for x in 0..n * n {
-                        for y in 0..n * n {
+                    // find the symbols on the line y = slope * x + c, in modulo n arithmetic
+                    for x in 0..n {
+                        for y in 0..n {
                             match slope {
                                 fraction::GenericFraction::NaN => {}
                                 fraction::GenericFraction::Infinity(_) => {
-                                    if x as u64 == c as u64 {
-                                        println!("x: {}, y: {}, a: inf, c: {}", x, y, c);
-                                        symbol_on_plane
-                                            .insert(plane[(y % n) as usize][(x % n) as usize]);
+                                    if x == c {
+                                        symbol_on_plane.insert(plane[y as usize][x as usize]);
                                     }
                                 }
                                 fraction::GenericFraction::Rational(_, slope) => {
-                                    if y as u64
-                                        == slope.numer() / slope.denom() * x as u64 + c as u64
+                                    // the slope is a fraction, so compare denom * y with numer * x + denom * c
+                                    let (numer, denom) = (*slope.numer(), *slope.denom());
+                                    if (denom * y as u64) % n as u64
+                                        == (numer * x as u64 + denom * c as u64) % n as u64
                                     {
-                                        println!(
-                                            "x: {}, y: {}, a: {:.2}, c: {}",
-                                            x,
-                                            y,
-                                            (slope.numer() / slope.denom()),
-                                            c
-                                        );
-                                        symbol_on_plane
-                                            .insert(plane[(y % n) as usize][(x % n) as usize]);
+                                        symbol_on_plane.insert(plane[y as usize][x as usize]);
                                     }
                                 }
                             }
                         }
                     }
                     symbol_on_plane.insert(line_symbol);
-                    deck.push_card(SpotItCard(symbol_on_plane.clone()));
-                    dbg!(&symbol_on_plane);
+                    deck.push_card(SpotItCard(symbol_on_plane));
                 }
             }
             deck.push_card(SpotItCard(line.into_iter().collect()));
@@ -229,6 +240,24 @@ impl SpotItDeck {
         }
     }
 
+    /// This function enumerates every triplet in a layout, i.e. three cards which share the same symbol, as the symbol and the positions of the cards.
+    /// In a projective-plane deck, the cards of a triplet lie on the same symbol line. A symbol on more than three cards gives a triplet for every three of them.
+    /// Triplets are sorted by symbol, then by positions.
+    pub fn triplets(layout: &[SpotItCard]) -> Vec<(SpotItSymbol, [usize; 3])> {
+        let mut symbols: Vec<SpotItSymbol> =
+            layout.iter().flat_map(|c| c.0.iter().copied()).collect();
+        symbols.sort();
+        symbols.dedup();
+        let mut triplets = Vec::new();
+        for symbol in symbols {
+            let positions = (0..layout.len()).filter(|i| layout[*i].0.contains(&symbol));
+            for three in positions.combinations(3) {
+                triplets.push((symbol, [three[0], three[1], three[2]]));
+            }
+        }
+        triplets
+    }
+
     /// This function fills symbols onto a plane [y][x] (note: inverted x and y) where x and y are both in range of 0..n.
     fn gen_projective_plane(n: u8) -> (Vec<Vec<SpotItSymbol>>, Vec<SpotItSymbol>) {
         let mut symbol = SpotItSymbol::iter();
@@ -288,6 +317,17 @@ mod tests {
         assert_eq!(deck.pop_card(), Some(card));
     }
     #[test]
+    fn every_symbol_of_default_spotitdeck_makes_56_triplets() {
+        // every symbol is on 8 cards, and 8 choose 3 is 56
+        let deck = SpotItDeck::default();
+        let triplets = SpotItDeck::triplets(&deck.cards);
+        assert_eq!(triplets.len(), 57 * 56);
+        for (symbol, cards) in triplets {
+            assert!(cards.iter().all(|i| deck.cards[*i].0.contains(&symbol)));
+        }
+        assert!(SpotItDeck::triplets(&deck.cards[..2]).is_empty());
+    }
+    #[test]
     fn default_spotitdeck_has_57_cards() {
         let deck: SpotItDeck = SpotItDeck::default();
         assert_eq!(deck.cards.len(), 57);
@@ -336,6 +376,25 @@ mod tests {
         assert!(SpotItDeck::generate_by_prime(6).is_err());
     }
     #[test]
+    fn orders_out_of_range_are_errors() {
+        assert!(SpotItDeck::generate_by_prime(0).is_err());
+        // n^2 + n + 1 does not fit in a u8 from n = 16
+        assert!(SpotItDeck::generate_by_prime(16).is_err());
+        assert!(SpotItDeck::generate_by_prime(u8::MAX).is_err());
+    }
+    #[test]
+    fn every_two_cards_generated_by_prime_match_exactly_one_symbol() {
+        for n in [1, 2, 3, 5, 7] {
+            let deck = SpotItDeck::generate_by_prime(n).unwrap();
+            for (i, card) in deck.cards.iter().enumerate() {
+                assert_eq!(card.0.len(), n as usize + 1);
+                for other in deck.cards.iter().skip(i + 1) {
+                    assert!(card.match_exactly_one_symbol(other), "n = {}", n);
+                }
+            }
+        }
+    }
+    #[test]
     fn can_generate_right_deck_of_card_by_prime_3() {
         let deck = SpotItDeck::generate_by_prime(3).unwrap();
         assert_eq!(deck.cards.len(), 3 * 3 + 3 + 1);
@@ -0,0 +1,345 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Support library to localize the names of symbols and cards in `cardgame` crate.
+//!
+//! Names are looked up in locale catalogs, which are text files of `key = value` lines such as `symbol.Apple = Pomme` or `suit.Spade = Pique`.
+//! French (`fr`) and Cantonese as written in Hong Kong (`zh-HK`) are built in, other catalogs can be loaded from files.
+//! A name missing from the chosen locale falls back to its language (`fr-CA` to `fr`), then to English.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt!`]: https://www.amazon.com/Asmodee-SP411-Spot-It/dp/B0039S7NO6
+//! [`French Card Game`]: https://en.wikipedia.org/wiki/French_playing_cards
+
+use crate::frenchcard::{FrenchRank, FrenchSuit};
+use crate::spotitcard::SpotItSymbol;
+use crate::theme::Theme;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use strum::IntoEnumIterator;
+
+/// This is the locale every name falls back to.
+pub const FALLBACK_LOCALE: &str = "en";
+
+const FR: &str = include_str!("../locales/fr.txt");
+const ZH_HK: &str = include_str!("../locales/zh-HK.txt");
+
+/// This struct defines a locale catalog, which maps keys such as `symbol.Apple` to localized names.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Catalog {
+    /// This is the locale tag of the catalog, e.g. `fr` or `zh-HK`.
+    pub locale: String,
+    entries: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// This function parses a catalog from `key = value` lines.
+    /// Blank lines and lines starting with `#` are ignored. Unknown keys are reported as errors to catch typos.
+    pub fn parse(locale: &str, text: &str) -> Result<Self, String> {
+        let keys = Self::keys();
+        let mut entries = HashMap::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                format!("{} line {}: expected `key = value`.", locale, number + 1)
+            })?;
+            let (key, value) = (key.trim(), value.trim());
+            if !keys.iter().any(|k| k == key) {
+                return Err(format!(
+                    "{} line {}: unknown key {}.",
+                    locale,
+                    number + 1,
+                    key
+                ));
+            }
+            if value.is_empty() {
+                return Err(format!(
+                    "{} line {}: {} has no value.",
+                    locale,
+                    number + 1,
+                    key
+                ));
+            }
+            entries.insert(key.to_string(), value.to_string());
+        }
+        Ok(Catalog {
+            locale: normalize(locale),
+            entries,
+        })
+    }
+
+    /// This function loads a catalog from a file, taking the locale from the file name, e.g. `de.txt` or `pt-BR.txt`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let locale = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| format!("{} is not a valid catalog file name.", path.display()))?;
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::parse(locale, &text)
+    }
+
+    /// This function creates the English catalog from the names of the enums.
+    pub fn english() -> Self {
+        let mut entries = HashMap::new();
+        let fruits = Theme::fruits();
+        for symbol in SpotItSymbol::iter() {
+            entries.insert(
+                symbol_key(symbol),
+                fruits.label(symbol).unwrap().to_string(),
+            );
+        }
+        for rank in FrenchRank::iter() {
+            entries.insert(rank_key(rank), format!("{:?}", rank));
+        }
+        for suit in FrenchSuit::iter() {
+            entries.insert(suit_key(suit), format!("{:?}", suit));
+        }
+        Catalog {
+            locale: FALLBACK_LOCALE.to_string(),
+            entries,
+        }
+    }
+
+    /// This function returns every key a catalog can have.
+    pub fn keys() -> Vec<String> {
+        SpotItSymbol::iter()
+            .map(symbol_key)
+            .chain(FrenchRank::iter().map(rank_key))
+            .chain(FrenchSuit::iter().map(suit_key))
+            .collect()
+    }
+
+    /// This function returns the localized name of the key, if the catalog has it.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(|value| value.as_str())
+    }
+
+    /// This function returns the number of names in the catalog.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// This function returns true if the catalog has no name.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn symbol_key(symbol: SpotItSymbol) -> String {
+    format!("symbol.{:?}", symbol)
+}
+fn rank_key(rank: FrenchRank) -> String {
+    format!("rank.{:?}", rank)
+}
+fn suit_key(suit: FrenchSuit) -> String {
+    format!("suit.{:?}", suit)
+}
+
+/// This function normalizes a locale tag, e.g. `fr_fr.UTF-8` to `fr-FR`.
+fn normalize(locale: &str) -> String {
+    let locale = locale
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .replace('_', "-");
+    let mut parts = locale.split('-');
+    let mut tag = parts.next().unwrap_or_default().to_lowercase();
+    for part in parts {
+        tag.push('-');
+        tag.push_str(&part.to_uppercase());
+    }
+    tag
+}
+
+/// This struct defines a localizer, which picks a locale and looks names up in its catalogs.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Localizer {
+    locale: String,
+    catalogs: Vec<Catalog>,
+}
+
+impl Default for Localizer {
+    /// This function creates an English localizer with the built-in catalogs.
+    fn default() -> Self {
+        Localizer {
+            locale: FALLBACK_LOCALE.to_string(),
+            catalogs: vec![
+                Catalog::english(),
+                Catalog::parse("fr", FR).unwrap(),
+                Catalog::parse("zh-HK", ZH_HK).unwrap(),
+            ],
+        }
+    }
+}
+
+impl Localizer {
+    /// This function creates a localizer with the built-in catalogs and picks the locale.
+    pub fn new(locale: &str) -> Self {
+        let mut localizer = Self::default();
+        localizer.set_locale(locale);
+        localizer
+    }
+
+    /// This function creates a localizer for the locale of the environment, i.e. `LC_ALL`, `LC_MESSAGES` or `LANG`.
+    pub fn from_env() -> Self {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+            .unwrap_or_else(|| FALLBACK_LOCALE.to_string());
+        Self::new(&locale)
+    }
+
+    /// This function picks the locale. A locale without a catalog falls back to English.
+    pub fn set_locale(&mut self, locale: &str) {
+        self.locale = normalize(locale);
+    }
+
+    /// This function returns the locale picked.
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// This function returns the locales which have a catalog.
+    pub fn locales(&self) -> Vec<&str> {
+        self.catalogs.iter().map(|c| c.locale.as_str()).collect()
+    }
+
+    /// This function adds a catalog, replacing any catalog of the same locale.
+    pub fn add_catalog(&mut self, catalog: Catalog) {
+        match self
+            .catalogs
+            .iter_mut()
+            .find(|c| c.locale == catalog.locale)
+        {
+            Some(existing) => *existing = catalog,
+            None => self.catalogs.push(catalog),
+        }
+    }
+
+    /// This function loads a catalog from a file and adds it, returning its locale.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<String, String> {
+        let catalog = Catalog::from_file(path)?;
+        let locale = catalog.locale.clone();
+        self.add_catalog(catalog);
+        Ok(locale)
+    }
+
+    /// This function looks the key up in the locale, then its language, then English.
+    fn lookup(&self, key: &str) -> Option<&str> {
+        let language = self.locale.split('-').next().unwrap_or_default();
+        [self.locale.as_str(), language, FALLBACK_LOCALE]
+            .iter()
+            .filter_map(|locale| self.catalogs.iter().find(|c| c.locale == *locale))
+            .find_map(|catalog| catalog.get(key))
+    }
+
+    /// This function returns the localized name of a SpotIt! symbol.
+    pub fn symbol_name(&self, symbol: SpotItSymbol) -> String {
+        self.lookup(&symbol_key(symbol))
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("{:?}", symbol))
+    }
+
+    /// This function returns the localized name of a French card rank.
+    pub fn rank_name(&self, rank: FrenchRank) -> String {
+        self.lookup(&rank_key(rank))
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("{:?}", rank))
+    }
+
+    /// This function returns the localized name of a French card suit.
+    pub fn suit_name(&self, suit: FrenchSuit) -> String {
+        self.lookup(&suit_key(suit))
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("{:?}", suit))
+    }
+
+    /// This function returns the fruits theme in the locale, so that decks can be rendered with localized names.
+    pub fn fruits_theme(&self) -> Result<Theme, String> {
+        let labels = SpotItSymbol::iter()
+            .map(|symbol| self.symbol_name(symbol))
+            .collect();
+        Theme::new(&format!("fruits-{}", self.locale), labels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_catalogs_are_complete() {
+        let localizer = Localizer::default();
+        assert_eq!(localizer.locales(), vec!["en", "fr", "zh-HK"]);
+        for catalog in localizer.catalogs.iter() {
+            assert_eq!(catalog.len(), Catalog::keys().len(), "{}", catalog.locale);
+        }
+        for locale in localizer.locales() {
+            // names must be unique, or players could not tell two symbols apart
+            assert!(Localizer::new(locale).fruits_theme().is_ok(), "{}", locale);
+        }
+    }
+    #[test]
+    fn can_localize_names() {
+        let localizer = Localizer::new("fr");
+        assert_eq!(localizer.symbol_name(SpotItSymbol::Apple), "Pomme");
+        assert_eq!(localizer.rank_name(FrenchRank::Queen), "Dame");
+        assert_eq!(localizer.suit_name(FrenchSuit::Spade), "Pique");
+
+        let localizer = Localizer::new("zh-HK");
+        assert_eq!(localizer.symbol_name(SpotItSymbol::Strawberry), "士多啤梨");
+        assert_eq!(localizer.rank_name(FrenchRank::Ace), "煙士");
+        assert_eq!(localizer.suit_name(FrenchSuit::Spade), "葵扇");
+    }
+    #[test]
+    fn can_fall_back_to_language_then_english() {
+        let localizer = Localizer::new("fr_CA.UTF-8");
+        assert_eq!(localizer.locale(), "fr-CA");
+        assert_eq!(localizer.symbol_name(SpotItSymbol::Pear), "Poire");
+
+        let localizer = Localizer::new("de");
+        assert_eq!(
+            localizer.symbol_name(SpotItSymbol::ChicoFruit),
+            "Chico Fruit"
+        );
+        assert_eq!(localizer.rank_name(FrenchRank::Ace), "Ace");
+
+        let mut localizer = Localizer::default();
+        localizer.add_catalog(Catalog::parse("de", "symbol.Apple = Apfel").unwrap());
+        localizer.set_locale("de-AT");
+        assert_eq!(localizer.symbol_name(SpotItSymbol::Apple), "Apfel");
+        assert_eq!(localizer.symbol_name(SpotItSymbol::Pear), "Pear");
+    }
+    #[test]
+    fn can_load_catalog_from_file() {
+        let dir = std::env::temp_dir().join(format!("cardgame-locale-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("nl.txt");
+        fs::write(&path, "# Dutch\nsuit.Heart = Harten\n").unwrap();
+        let mut localizer = Localizer::default();
+        let locale = localizer.load_file(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(locale, "nl");
+        localizer.set_locale(&locale);
+        assert_eq!(localizer.suit_name(FrenchSuit::Heart), "Harten");
+        assert_eq!(localizer.suit_name(FrenchSuit::Club), "Club");
+    }
+    #[test]
+    fn cannot_parse_invalid_catalog() {
+        assert!(Catalog::parse("fr", "symbol.Apple Pomme").is_err());
+        assert!(Catalog::parse("fr", "symbol.Apel = Pomme").is_err());
+        assert!(Catalog::parse("fr", "symbol.Apple =").is_err());
+    }
+    #[test]
+    fn can_normalize_locale() {
+        assert_eq!(normalize("zh_hk"), "zh-HK");
+        assert_eq!(normalize("FR"), "fr");
+        assert_eq!(normalize("en_GB.UTF-8"), "en-GB");
+    }
+}
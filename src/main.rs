@@ -1,29 +1,667 @@
+use cardgame::cli::{self, complete, DeckOrder, LineSource, Mode, Options, Script, Session};
+use cardgame::deckfile::DeckFormat;
+use cardgame::difficulty::{Difficulty, Layout};
+use cardgame::game::bot::Skill;
+use cardgame::game::clock::SystemClock;
+use cardgame::game::registry::Registry;
+use cardgame::game::Phase;
+use cardgame::save::SavedGame;
+use cardgame::simulation::Simulation;
+use cardgame::spotitcard::SpotItSymbol;
+use cardgame::svg::SvgCard;
+use cardgame::terminal::{TermCard, TermStyle};
+use cardgame::tournament::{Format as TournamentFormat, GameResult, Tournament};
+use cardgame::tui::App;
 use cardgame::SpotItDeck;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use rustyline::completion::Completer;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
 
-fn main() {
-    // let mut french_deck = FrenchDeck::default();
-    // french_deck.shuffle();
+/// This is the exit code of a deck which is not fit to play, or cannot be read as a deck.
+const INVALID_DECK: u8 = 1;
+/// This is the exit code of wrong arguments, the same as clap uses.
+const USAGE_ERROR: u8 = 2;
+/// This is the exit code of a file which cannot be read or written.
+const IO_ERROR: u8 = 3;
 
-    // println!("French deck has these cards: \n");
+/// This is the size of an exported SVG card, in millimetres.
+const SVG_CARD_SIZE: f64 = 80.0;
 
-    // dbg!(&french_deck.cards[..]);
-    // dbg!(&french_deck.cards.len());
+/// Play SpotIt! in the terminal, and make, check and convert decks.
+#[derive(Parser)]
+#[command(
+    name = "cardgame",
+    version,
+    args_conflicts_with_subcommands = true,
+    after_help = "Exit codes: 0 on success, 1 for an invalid deck, 2 for wrong arguments, 3 when a file cannot be read or written."
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    play: PlayArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Play a game in the terminal. This is what runs without a command.
+    Play(PlayArgs),
+    /// Play "The Tower" in a full-screen terminal UI.
+    Tui {
+        /// The deck order: ordered or shuffled.
+        #[arg(long, default_value = "shuffled")]
+        order: DeckOrder,
+        /// The seed of the shuffle, to play the same deck again.
+        #[arg(long)]
+        seed: Option<u64>,
+        /// The name of the player.
+        #[arg(long, default_value = "Player")]
+        name: String,
+        /// The difficulty: toddler, easy, junior, standard or expert.
+        #[arg(long, default_value = "standard")]
+        difficulty: Difficulty,
+        /// The file to save the game to when leaving before the end. The game is resumed from it if it is there.
+        #[arg(long)]
+        save: Option<PathBuf>,
+    },
+    /// Generate a deck from a projective plane and write it out.
+    Generate {
+        #[command(flatten)]
+        spec: DeckSpec,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Check that a deck file can be played, i.e. every two cards share exactly one symbol.
+    Validate {
+        /// The deck file, in text, JSON (.json) or CSV (.csv).
+        file: PathBuf,
+    },
+    /// Print statistics of a deck.
+    Stats {
+        #[command(flatten)]
+        source: DeckSource,
+    },
+    /// Draw the cards of a deck in the terminal.
+    Render {
+        #[command(flatten)]
+        source: DeckSource,
+        /// Draw with plain ASCII and no colours.
+        #[arg(long)]
+        plain: bool,
+    },
+    /// Write a deck in another format.
+    Export {
+        #[command(flatten)]
+        source: DeckSource,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Play many games between bots and print win rates by seat, game length and cards per player.
+    Simulate(SimulateArgs),
+    /// List the game modes which can be simulated.
+    Modes,
+    /// Run a round robin or a bracket of matches, kept in a file.
+    Tournament {
+        #[command(subcommand)]
+        command: TournamentCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum TournamentCommand {
+    /// Enter the players, the best seed first, pair them off and write the tournament to the file.
+    New {
+        /// The file to keep the tournament in.
+        file: PathBuf,
+        /// The players, comma separated, the best seed first.
+        #[arg(long, value_delimiter = ',', required = true)]
+        players: Vec<String>,
+        /// How players are paired off: round-robin or single-elimination.
+        #[arg(long, default_value = "round-robin")]
+        format: TournamentFormat,
+        /// The game mode, as listed by the modes command.
+        #[arg(long, default_value = "classic")]
+        mode: String,
+        /// The number of games of every match.
+        #[arg(long, default_value_t = 1)]
+        games: usize,
+        /// The name of the tournament.
+        #[arg(long, default_value = "Tournament")]
+        name: String,
+    },
+    /// Record the scores of a game of a match, as numbered by the show command.
+    Record {
+        /// The file the tournament is kept in.
+        file: PathBuf,
+        /// The number of the match, from 1.
+        #[arg(
+            long = "match",
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        number: usize,
+        /// The scores of the two players, comma separated, in the order of the match.
+        #[arg(long, value_delimiter = ',', allow_negative_numbers = true)]
+        scores: Vec<i32>,
+    },
+    /// Play every game left between bots.
+    Play {
+        /// The file the tournament is kept in.
+        file: PathBuf,
+        /// The skill of every bot: beginner, intermediate or expert.
+        #[arg(long, default_value = "intermediate")]
+        skill: Skill,
+        /// The seed of the shuffles and the bots.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Print the matches and the standings.
+    Show {
+        /// The file the tournament is kept in.
+        file: PathBuf,
+    },
+}
+
+impl TournamentCommand {
+    fn run(self) -> Result<(), Failure> {
+        let (file, tournament) = match self {
+            TournamentCommand::New {
+                file,
+                players,
+                format,
+                mode,
+                games,
+                name,
+            } => {
+                if file.exists() {
+                    return Err(Failure::Usage(format!(
+                        "{} already exists.",
+                        file.display()
+                    )));
+                }
+                let registry = Registry::builtin();
+                let mode = registry
+                    .find(&mode)
+                    .map_err(Failure::Usage)?
+                    .name()
+                    .to_string();
+                let players: Vec<&str> = players.iter().map(|p| p.trim()).collect();
+                let tournament = Tournament::new(&name, format, &mode, &players, games)
+                    .map_err(Failure::Usage)?;
+                // A mode which cannot seat the two players of a match is refused before the file is written
+                tournament.check_mode(&registry).map_err(Failure::Usage)?;
+                (file, tournament)
+            }
+            TournamentCommand::Record {
+                file,
+                number,
+                scores,
+            } => {
+                let mut tournament = Tournament::load(&file).map_err(Failure::Io)?;
+                let [a, b] = scores[..] else {
+                    return Err(Failure::Usage(
+                        "A game has two scores, e.g. --scores 12,9.".to_string(),
+                    ));
+                };
+                tournament
+                    .record_game(number - 1, GameResult::from_scores([a, b]))
+                    .map_err(Failure::Usage)?;
+                (file, tournament)
+            }
+            TournamentCommand::Play { file, skill, seed } => {
+                let mut tournament = Tournament::load(&file).map_err(Failure::Io)?;
+                let registry = Registry::builtin();
+                let mut seed = seed;
+                while let Some(id) = tournament.pending().first().copied() {
+                    tournament
+                        .play_game(&registry, id, skill, seed)
+                        .map_err(Failure::Usage)?;
+                    seed = seed.wrapping_add(1);
+                }
+                (file, tournament)
+            }
+            TournamentCommand::Show { file } => {
+                let tournament = Tournament::load(&file).map_err(Failure::Io)?;
+                println!("{}", tournament);
+                return Ok(());
+            }
+        };
+        tournament.save(&file).map_err(Failure::Io)?;
+        println!("{}", tournament);
+        Ok(())
+    }
+}
+
+#[derive(Args)]
+struct PlayArgs {
+    /// The game mode: classic, solo, tower, well, triplet or practice. Asked for if left out.
+    #[arg(long)]
+    mode: Option<Mode>,
+    /// The deck order: ordered or shuffled. Asked for if left out.
+    #[arg(long)]
+    order: Option<DeckOrder>,
+    /// The seed of the shuffle, to play the same deck again.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// The name of the player.
+    #[arg(long, default_value = "Player")]
+    name: String,
+    /// Read answers from standard input without prompts or colours.
+    #[arg(long)]
+    script: bool,
+    /// The difficulty: toddler, easy, junior, standard or expert. It sets the deck and how much hints help.
+    #[arg(long, default_value = "standard")]
+    difficulty: Difficulty,
+    /// The file of learning profiles to practise from, and to record the symbols spotted in solo and practice.
+    #[arg(long)]
+    profile: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct SimulateArgs {
+    /// The game mode, as listed by the modes command.
+    #[arg(long, default_value = "classic")]
+    mode: String,
+    /// The skill of the bot in every seat, comma separated: beginner, intermediate or expert.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        default_value = "intermediate,intermediate"
+    )]
+    bots: Vec<Skill>,
+    /// The number of games.
+    #[arg(long, default_value_t = 1000)]
+    games: usize,
+    /// The order n of the projective plane the deck is generated from, a prime.
+    #[arg(long, default_value_t = 7, value_parser = clap::value_parser!(u8).range(1..))]
+    order: u8,
+    /// The number of rounds of hot-potato.
+    #[arg(long, default_value_t = 5)]
+    rounds: usize,
+    /// The most claims in a game before it is stopped.
+    #[arg(long, default_value_t = 10_000)]
+    max_turns: usize,
+    /// The seed of the shuffles and the bots.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Print the report as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+impl SimulateArgs {
+    fn run(&self) -> Result<(), Failure> {
+        SpotItDeck::generate_by_prime(self.order).map_err(Failure::InvalidDeck)?;
+        let simulation = Simulation {
+            mode: self.mode.clone(),
+            bots: self.bots.clone(),
+            order: self.order,
+            games: self.games,
+            rounds: self.rounds,
+            max_turns: self.max_turns,
+            seed: self.seed,
+        };
+        let report = simulation.run().map_err(Failure::Usage)?;
+        match self.json {
+            true => print!("{}", report.to_json()),
+            false => println!("{}", report),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Args)]
+struct DeckSpec {
+    /// The order n of the projective plane, a prime: the deck has n^2 + n + 1 cards of n + 1 symbols.
+    #[arg(
+        long,
+        default_value_t = 7,
+        conflicts_with = "symbols_per_card",
+        value_parser = clap::value_parser!(u8).range(1..)
+    )]
+    order: u8,
+    /// The number of symbols on every card, one more than the order.
+    #[arg(long, value_parser = clap::value_parser!(u8).range(2..))]
+    symbols_per_card: Option<u8>,
+    /// The difficulty, which sets the order and how SVG cards are laid out: toddler, easy, junior, standard or expert.
+    #[arg(long, conflicts_with_all = ["order", "symbols_per_card"])]
+    difficulty: Option<Difficulty>,
+    /// Shuffle the cards with this seed. The cards are in generated order if left out.
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+impl DeckSpec {
+    fn generate(&self) -> Result<SpotItDeck, String> {
+        let mut deck = match self.difficulty {
+            Some(difficulty) => difficulty.deck(),
+            None => SpotItDeck::generate_by_prime(self.order()?)?,
+        };
+        if let Some(seed) = self.seed {
+            deck.cards.shuffle(&mut StdRng::seed_from_u64(seed));
+        }
+        Ok(deck)
+    }
+
+    fn order(&self) -> Result<u8, String> {
+        match self.symbols_per_card {
+            Some(symbols) => symbols
+                .checked_sub(1)
+                .ok_or_else(|| "A card needs at least one symbol.".to_string()),
+            None => Ok(self.order),
+        }
+    }
+
+    /// This function returns the layout of the difficulty, or the plain one.
+    fn layout(&self) -> Layout {
+        self.difficulty.map(|d| d.layout()).unwrap_or_default()
+    }
+}
+
+#[derive(Args)]
+struct DeckSource {
+    /// The deck file, in text, JSON (.json) or CSV (.csv). A deck is generated if left out.
+    #[arg(conflicts_with_all = ["order", "symbols_per_card", "difficulty"])]
+    file: Option<PathBuf>,
+    #[command(flatten)]
+    spec: DeckSpec,
+}
+
+#[derive(Args)]
+struct OutputArgs {
+    /// The format to write.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// The file to write, or the directory for SVG. Standard output if left out.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+    /// One SVG file per card, written to the output directory.
+    Svg,
+}
 
-    // let mut spotit_deck = SpotItDeck::default();
-    // spotit_deck.shuffle();
+/// This enum defines why a command failed, which gives the exit code.
+enum Failure {
+    InvalidDeck(String),
+    Usage(String),
+    Io(String),
+}
+
+impl From<Failure> for ExitCode {
+    fn from(failure: Failure) -> Self {
+        let (message, code) = match failure {
+            Failure::InvalidDeck(message) => (message, INVALID_DECK),
+            Failure::Usage(message) => (message, USAGE_ERROR),
+            Failure::Io(message) => (message, IO_ERROR),
+        };
+        eprintln!("{}", message);
+        ExitCode::from(code)
+    }
+}
 
-    // println!("SpotIt default deck has these cards: \n");
-    // dbg!(&spotit_deck.cards);
-    // dbg!(&spotit_deck.cards.len());
+/// This function reads the deck from the file, or generates it.
+fn load(source: &DeckSource) -> Result<SpotItDeck, Failure> {
+    let Some(file) = &source.file else {
+        return source.spec.generate().map_err(Failure::InvalidDeck);
+    };
+    let text = std::fs::read_to_string(file)
+        .map_err(|e| Failure::Io(format!("Cannot read {}: {}", file.display(), e)))?;
+    let mut deck = SpotItDeck::import(&text, DeckFormat::from_path(file))
+        .map_err(|e| Failure::InvalidDeck(format!("{}: {}", file.display(), e)))?;
+    if let Some(seed) = source.spec.seed {
+        deck.cards.shuffle(&mut StdRng::seed_from_u64(seed));
+    }
+    Ok(deck)
+}
 
-    // let mut spotit_deck = SpotItDeck::generate_by_prime(2).unwrap();
+fn write(path: &Path, contents: &str) -> Result<(), Failure> {
+    std::fs::write(path, contents)
+        .map_err(|e| Failure::Io(format!("Cannot write {}: {}", path.display(), e)))
+}
+
+fn export(deck: &SpotItDeck, layout: Layout, output: &OutputArgs) -> Result<(), Failure> {
+    let format = match output.format {
+        Format::Text => DeckFormat::Text,
+        Format::Json => DeckFormat::Json,
+        Format::Csv => DeckFormat::Csv,
+        Format::Svg => {
+            let dir = output.output.as_deref().ok_or_else(|| {
+                Failure::Usage("SVG needs an output directory, see --output.".to_string())
+            })?;
+            std::fs::create_dir_all(dir)
+                .map_err(|e| Failure::Io(format!("Cannot create {}: {}", dir.display(), e)))?;
+            let digits = deck.cards.len().to_string().len();
+            for (i, card) in deck.lay_out(layout).iter().enumerate() {
+                let path = dir.join(format!("card-{:0width$}.svg", i + 1, width = digits));
+                write(&path, &card.to_svg(SVG_CARD_SIZE, SVG_CARD_SIZE))?;
+            }
+            return Ok(());
+        }
+    };
+    match &output.output {
+        Some(path) => write(path, &deck.export(format)),
+        None => {
+            print!("{}", deck.export(format));
+            Ok(())
+        }
+    }
+}
+
+fn validate(file: &Path) -> Result<(), Failure> {
+    let deck = load(&DeckSource {
+        file: Some(file.to_path_buf()),
+        spec: DeckSpec {
+            order: 7,
+            symbols_per_card: None,
+            difficulty: None,
+            seed: None,
+        },
+    })?;
+    let problems = deck.problems();
+    if problems.is_empty() {
+        println!("{}: {} cards, valid.", file.display(), deck.cards.len());
+        return Ok(());
+    }
+    for problem in problems.iter() {
+        println!("{}", problem);
+    }
+    let noun = match problems.len() {
+        1 => "problem",
+        _ => "problems",
+    };
+    Err(Failure::InvalidDeck(format!(
+        "{}: {} {}, not valid.",
+        file.display(),
+        problems.len(),
+        noun
+    )))
+}
+
+fn render(deck: &SpotItDeck, plain: bool) {
+    let style = match plain {
+        true => TermStyle::plain(),
+        false => TermStyle::detect(),
+    };
+    for (i, card) in deck.cards.iter().enumerate() {
+        println!("Card {}", i + 1);
+        println!("{}", card.render(&style));
+    }
+}
+
+/// This struct completes the names of the symbols which make sense at the prompt.
+#[derive(Default)]
+struct SymbolHelper {
+    candidates: Vec<SpotItSymbol>,
+}
 
-    // println!("SpotIt prime of 2 has a deck of these cards: \n");
-    // dbg!(&spotit_deck.cards);
-    // dbg!(&spotit_deck.cards.len());
+impl Completer for SymbolHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        // Card numbers come before the symbol in Triplet, so complete what follows them
+        let typed = &line[..pos];
+        let mut start = 0;
+        for word in typed.split_inclusive(' ') {
+            if !word.trim().chars().all(|c| c.is_ascii_digit()) || !word.ends_with(' ') {
+                break;
+            }
+            start += word.len();
+        }
+        Ok((start, complete(&typed[start..], &self.candidates)))
+    }
+}
+
+impl Hinter for SymbolHelper {
+    type Hint = String;
+}
+impl Highlighter for SymbolHelper {}
+impl Validator for SymbolHelper {}
+impl Helper for SymbolHelper {}
+
+/// This struct reads lines typed at the terminal, with tab completion of symbol names.
+struct Terminal {
+    editor: Editor<SymbolHelper, DefaultHistory>,
+}
+
+impl LineSource for Terminal {
+    fn read_line(&mut self, prompt: &str, candidates: &[SpotItSymbol]) -> Option<String> {
+        if let Some(helper) = self.editor.helper_mut() {
+            helper.candidates = candidates.to_vec();
+        }
+        self.editor.readline(prompt).ok()
+    }
+}
+
+fn play(args: PlayArgs) -> Result<(), Failure> {
+    let options = Options {
+        mode: args.mode,
+        order: args.order,
+        seed: args.seed,
+        name: args.name,
+        script: args.script,
+        difficulty: args.difficulty,
+        profile: args.profile,
+        style: match args.script {
+            true => TermStyle::plain(),
+            false => TermStyle::detect(),
+        },
+    };
+    let mut out = std::io::stdout();
+    let result = match options.script {
+        true => {
+            let mut input = Script::new(std::io::stdin().lock());
+            Session::new(&options, &mut input, &mut out).run()
+        }
+        false => {
+            let mut editor = Editor::new()
+                .map_err(|e| Failure::Io(format!("Cannot read from the terminal: {}", e)))?;
+            editor.set_helper(Some(SymbolHelper::default()));
+            let mut input = Terminal { editor };
+            Session::new(&options, &mut input, &mut out).run()
+        }
+    };
+    result.map_err(|e| Failure::Io(e.to_string()))
+}
+
+fn tui(
+    difficulty: Difficulty,
+    order: DeckOrder,
+    seed: Option<u64>,
+    name: &str,
+    save: Option<&Path>,
+) -> Result<(), Failure> {
+    let mut app = match save.filter(|path| path.exists()) {
+        Some(path) => SavedGame::load(path)
+            .and_then(|saved| App::resume(saved, SystemClock::new()))
+            .map_err(Failure::InvalidDeck)?,
+        None => App::new(cli::deck(difficulty, order, seed), name, SystemClock::new())
+            .map_err(Failure::InvalidDeck)?,
+    };
+    let mut terminal = ratatui::init();
+    let result = (|| -> std::io::Result<()> {
+        while !app.is_done() {
+            terminal.draw(|frame| app.draw(frame))?;
+            // Redraw often enough for the timer to tick
+            if event::poll(Duration::from_millis(100))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        app.handle_key(key.code);
+                    }
+                }
+            }
+        }
+        Ok(())
+    })();
+    ratatui::restore();
+    result.map_err(|e| Failure::Io(e.to_string()))?;
+    let Some(path) = save else {
+        return Ok(());
+    };
+    match app.game().phase() {
+        Phase::Finished => match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(Failure::Io(format!(
+                "Cannot remove {}: {}",
+                path.display(),
+                e
+            ))),
+            _ => Ok(()),
+        },
+        _ => app.saved().save(path).map_err(Failure::Io),
+    }
+}
 
-    let spotit_deck = SpotItDeck::generate_by_prime(7).unwrap();
-    println!("SpotIt prime of 5 has a deck of these cards: \n");
-    dbg!(&spotit_deck.cards);
-    dbg!(&spotit_deck.cards.len());
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        None => play(cli.play),
+        Some(Command::Play(args)) => play(args),
+        Some(Command::Tui {
+            order,
+            seed,
+            name,
+            difficulty,
+            save,
+        }) => tui(difficulty, order, seed, &name, save.as_deref()),
+        Some(Command::Generate { spec, output }) => spec
+            .generate()
+            .map_err(Failure::InvalidDeck)
+            .and_then(|deck| export(&deck, spec.layout(), &output)),
+        Some(Command::Validate { file }) => validate(&file),
+        Some(Command::Stats { source }) => load(&source).map(|deck| println!("{}", deck.stats())),
+        Some(Command::Render { source, plain }) => load(&source).map(|deck| render(&deck, plain)),
+        Some(Command::Export { source, output }) => {
+            load(&source).and_then(|deck| export(&deck, source.spec.layout(), &output))
+        }
+        Some(Command::Simulate(args)) => args.run(),
+        Some(Command::Modes) => {
+            println!("{}", Registry::builtin());
+            Ok(())
+        }
+        Some(Command::Tournament { command }) => command.run(),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => failure.into(),
+    }
 }
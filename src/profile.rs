@@ -0,0 +1,282 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Learning profiles of SpotIt! players in `cardgame` crate.
+//!
+//! A `Profile` keeps, for every `SpotItSymbol`, how long a player takes to spot it and how often they miss it, so that practice can go over the symbols they find hardest.
+//! It also schedules the symbols for review by spaced repetition, as in a Leitner box: a symbol spotted quickly goes up a level and comes back twice as late, and a symbol missed goes back to the first level and comes back at once.
+//! Time is counted in steps, i.e. symbols practised, rather than days, as children practise in short sessions.
+//!
+//! The profiles of all the players on a device are kept in one local JSON file with a version, like a `save::SavedGame`.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+
+use crate::save::{read_versioned, write_atomically};
+use crate::spotitcard::SpotItSymbol;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// This is the version of the profile format written by this crate. Profiles of later versions cannot be read.
+pub const PROFILE_VERSION: u64 = 1;
+
+/// This is the longest a symbol can take to spot and still count as known, i.e. move up a level.
+pub const QUICK: Duration = Duration::from_secs(3);
+
+/// This is the time to spot a miss counts as in the weakness of a symbol.
+const MISS_WEIGHT: Duration = Duration::from_secs(5);
+
+/// This struct defines what is known of a player and one symbol.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct SymbolStats {
+    /// This is the number of times the symbol was spotted.
+    pub spotted: u32,
+    /// This is the number of wrong answers when the symbol was the match.
+    pub misses: u32,
+    /// This is the time it took to spot the symbol, added up over every time it was spotted.
+    pub total_time: Duration,
+    /// This is the level of the symbol in spaced repetition, from 0.
+    pub level: u32,
+    /// This is the step from which the symbol is due for review.
+    pub due: u64,
+}
+
+impl SymbolStats {
+    /// This function returns true if the symbol has never been practised.
+    pub fn is_new(&self) -> bool {
+        self.spotted == 0 && self.misses == 0
+    }
+
+    /// This function returns the mean time to spot the symbol, or None if it was never spotted.
+    pub fn mean_time(&self) -> Option<Duration> {
+        match self.spotted {
+            0 => None,
+            n => Some(self.total_time / n),
+        }
+    }
+
+    /// This function returns the share of answers which were misses, from 0 to 1.
+    pub fn miss_rate(&self) -> f64 {
+        match self.spotted + self.misses {
+            0 => 0.0,
+            n => self.misses as f64 / n as f64,
+        }
+    }
+
+    /// This function returns how weak the player is on the symbol, i.e. the mean time to spot it in seconds, with misses counted as slow spots.
+    pub fn weakness(&self) -> f64 {
+        let misses = self.misses as f64 * MISS_WEIGHT.as_secs_f64();
+        let answers = (self.spotted + self.misses).max(1) as f64;
+        (self.total_time.as_secs_f64() + misses) / answers
+    }
+}
+
+/// This struct defines the learning profile of a player.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    /// This is what is known of every symbol practised.
+    pub symbols: BTreeMap<SpotItSymbol, SymbolStats>,
+    /// This is the number of symbols practised, which is the clock of spaced repetition.
+    pub step: u64,
+}
+
+impl Profile {
+    /// This function returns what is known of the symbol.
+    pub fn stats(&self, symbol: SpotItSymbol) -> SymbolStats {
+        self.symbols.get(&symbol).cloned().unwrap_or_default()
+    }
+
+    /// This function records the symbol spotted after the time, and schedules it: a quick spot moves it up a level, and a slow one down.
+    pub fn record_spot(&mut self, symbol: SpotItSymbol, time: Duration) {
+        self.step += 1;
+        let step = self.step;
+        let stats = self.symbols.entry(symbol).or_default();
+        stats.spotted += 1;
+        stats.total_time += time;
+        stats.level = match time <= QUICK {
+            true => stats.level + 1,
+            false => stats.level.saturating_sub(1),
+        };
+        stats.due = step + (1 << stats.level.min(16));
+    }
+
+    /// This function records a wrong answer when the symbol was the match, which sends it back to the first level, due at once.
+    pub fn record_miss(&mut self, symbol: SpotItSymbol) {
+        let step = self.step;
+        let stats = self.symbols.entry(symbol).or_default();
+        stats.misses += 1;
+        stats.level = 0;
+        stats.due = step;
+    }
+
+    /// This function returns the symbols practised, the weakest first.
+    pub fn weakest(&self) -> Vec<SpotItSymbol> {
+        let mut symbols: Vec<SpotItSymbol> = self.symbols.keys().copied().collect();
+        symbols.sort_by(|a, b| {
+            self.symbols[b]
+                .weakness()
+                .total_cmp(&self.symbols[a].weakness())
+        });
+        symbols
+    }
+
+    /// This function picks the next symbol to practise among those given, e.g. the symbols of a deck:
+    /// the weakest symbol due for review, else a new symbol, else the symbol due soonest.
+    /// It returns None if no symbol is given.
+    pub fn next_symbol(&self, symbols: &[SpotItSymbol]) -> Option<SpotItSymbol> {
+        let weakest = |a: &&SpotItSymbol, b: &&SpotItSymbol| {
+            self.stats(**a)
+                .weakness()
+                .total_cmp(&self.stats(**b).weakness())
+        };
+        let (new, seen): (Vec<&SpotItSymbol>, Vec<&SpotItSymbol>) =
+            symbols.iter().partition(|s| self.stats(**s).is_new());
+        seen.iter()
+            .filter(|s| self.stats(***s).due <= self.step)
+            .max_by(|a, b| weakest(a, b))
+            .or(new.first())
+            .or_else(|| seen.iter().min_by_key(|s| self.stats(***s).due))
+            .map(|s| **s)
+    }
+}
+
+/// This struct defines the profiles of the players on a device, by name.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Profiles {
+    /// This is the version of the format. See `PROFILE_VERSION`.
+    pub version: u64,
+    /// This is the profile of every player.
+    pub players: BTreeMap<String, Profile>,
+}
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Profiles {
+            version: PROFILE_VERSION,
+            players: BTreeMap::new(),
+        }
+    }
+}
+
+impl Profiles {
+    /// This function returns the profile of the player, or an empty one for a new player.
+    pub fn player(&self, name: &str) -> Profile {
+        self.players.get(name).cloned().unwrap_or_default()
+    }
+
+    /// This function keeps the profile of the player, in place of the one before.
+    pub fn set_player(&mut self, name: &str, profile: Profile) {
+        self.players.insert(name.to_string(), profile);
+    }
+
+    /// This function writes the profiles as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default() + "\n"
+    }
+
+    /// This function reads profiles written by `to_json`.
+    /// It returns an error if the text is not profiles, or was written by a later version of the format.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let value = read_versioned(text, "a profile file", PROFILE_VERSION)?;
+        serde_json::from_value(value).map_err(|e| format!("Not valid profiles: {}", e))
+    }
+
+    /// This function reads the profiles from a file, or returns none if there is no file yet.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Profiles::default());
+        }
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::from_json(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// This function writes the profiles to a file. See `save::write_atomically`.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        write_atomically(path, &self.to_json())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SpotItSymbol::*;
+
+    #[test]
+    fn spots_and_misses_are_tracked() {
+        let mut profile = Profile::default();
+        profile.record_spot(Apple, Duration::from_secs(2));
+        profile.record_spot(Apple, Duration::from_secs(4));
+        profile.record_miss(Apple);
+        let apple = profile.stats(Apple);
+        assert_eq!(apple.spotted, 2);
+        assert_eq!(apple.misses, 1);
+        assert_eq!(apple.mean_time(), Some(Duration::from_secs(3)));
+        assert_eq!(apple.miss_rate(), 1.0 / 3.0);
+        assert_eq!(apple.weakness(), (6.0 + 5.0) / 3.0);
+        assert!(profile.stats(Fig).is_new());
+        assert_eq!(profile.stats(Fig).mean_time(), None);
+
+        profile.record_spot(Fig, Duration::from_secs(1));
+        assert_eq!(profile.weakest(), [Apple, Fig]);
+        assert_eq!(profile.step, 3);
+    }
+    #[test]
+    fn symbols_are_scheduled_by_spaced_repetition() {
+        let mut profile = Profile::default();
+        profile.record_spot(Apple, Duration::from_secs(1));
+        assert_eq!(profile.stats(Apple).level, 1);
+        assert_eq!(profile.stats(Apple).due, 3);
+        profile.record_spot(Apple, Duration::from_secs(1));
+        assert_eq!(profile.stats(Apple).due, 6);
+        profile.record_spot(Apple, Duration::from_secs(9));
+        assert_eq!(profile.stats(Apple).level, 1);
+        profile.record_miss(Apple);
+        assert_eq!(profile.stats(Apple).level, 0);
+        assert_eq!(profile.stats(Apple).due, 3);
+    }
+    #[test]
+    fn next_symbol_reviews_before_new_ones() {
+        let mut profile = Profile::default();
+        let symbols = [Apple, Banana, Cherry];
+        assert_eq!(profile.next_symbol(&symbols), Some(Apple));
+        profile.record_spot(Apple, Duration::from_secs(1));
+        // Apple is not due yet, so Banana is new
+        assert_eq!(profile.next_symbol(&symbols), Some(Banana));
+        profile.record_spot(Banana, Duration::from_secs(8));
+        profile.record_miss(Banana);
+        // Banana is due again at once
+        assert_eq!(profile.next_symbol(&symbols), Some(Banana));
+        profile.record_spot(Cherry, Duration::from_secs(1));
+        profile.record_spot(Banana, Duration::from_secs(1));
+        profile.record_spot(Cherry, Duration::from_secs(1));
+        // Nothing is due and nothing is new, so the symbol due soonest comes back
+        profile.step = 0;
+        assert_eq!(profile.next_symbol(&symbols), Some(Apple));
+        assert_eq!(profile.next_symbol(&[]), None);
+    }
+    #[test]
+    fn profiles_are_kept_in_a_file() {
+        let dir = std::env::temp_dir().join(format!("cardgame-profile-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("profiles.json");
+        assert_eq!(Profiles::load(&path), Ok(Profiles::default()));
+
+        let mut profiles = Profiles::default();
+        let mut profile = profiles.player("Ann");
+        profile.record_spot(ChicoFruit, Duration::from_millis(1500));
+        profiles.set_player("Ann", profile.clone());
+        profiles.save(&path).unwrap();
+        let read = Profiles::load(&path).unwrap();
+        assert_eq!(read.player("Ann"), profile);
+        assert_eq!(read.player("Bob"), Profile::default());
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("\"ChicoFruit\""));
+
+        std::fs::write(&path, "{\"version\": 2, \"players\": {}}").unwrap();
+        assert!(Profiles::load(&path).unwrap_err().contains("version 2"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,400 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Support library to rasterize cards of `cardgame` crate as PNG thumbnails.
+//!
+//! Provide CPU-only rendering of SpotIt! and French cards into PNG images of any resolution.
+//! Symbols are drawn as coloured shapes rather than text, so no font is needed and the output is the same on every machine, which makes the thumbnails suitable for snapshot tests.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt!`]: https://www.amazon.com/Asmodee-SP411-Spot-It/dp/B0039S7NO6
+//! [`French Card Game`]: https://en.wikipedia.org/wiki/French_playing_cards
+//! [`tiny-skia`]: https://github.com/RazrFalcon/tiny-skia
+
+use crate::difficulty::{LaidOutCard, Layout};
+use crate::frenchcard::{FrenchCard, FrenchRank, FrenchSuit};
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use crate::svg::ring_layout;
+use crate::{FrenchDeck, SpotItDeck};
+use tiny_skia::{Color, FillRule, Paint, Path, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+/// This struct defines a rasterized card image.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Thumbnail {
+    pixmap: Pixmap,
+}
+
+impl Thumbnail {
+    /// This function creates a blank, transparent thumbnail.
+    fn new(width: u32, height: u32) -> Result<Self, String> {
+        match Pixmap::new(width, height) {
+            Some(pixmap) => Ok(Thumbnail { pixmap }),
+            None => Err(format!(
+                "{} x {} is not a valid thumbnail size.",
+                width, height
+            )),
+        }
+    }
+    /// This function returns the width of the thumbnail in pixels.
+    pub fn width(&self) -> u32 {
+        self.pixmap.width()
+    }
+    /// This function returns the height of the thumbnail in pixels.
+    pub fn height(&self) -> u32 {
+        self.pixmap.height()
+    }
+    /// This function returns the RGBA colour of a pixel, or None if it is out of the image.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+        self.pixmap.pixel(x, y).map(|pixel| {
+            let c = pixel.demultiply();
+            [c.red(), c.green(), c.blue(), c.alpha()]
+        })
+    }
+    /// This function encodes the thumbnail as a PNG file.
+    pub fn encode_png(&self) -> Result<Vec<u8>, String> {
+        self.pixmap.encode_png().map_err(|e| e.to_string())
+    }
+
+    fn fill_path(&mut self, path: &Path, colour: Color) {
+        let mut paint = Paint::default();
+        paint.set_color(colour);
+        paint.anti_alias = true;
+        self.pixmap
+            .fill_path(path, &paint, FillRule::Winding, Transform::identity(), None);
+    }
+    fn stroke_path(&mut self, path: &Path, colour: Color, width: f32) {
+        let mut paint = Paint::default();
+        paint.set_color(colour);
+        paint.anti_alias = true;
+        let stroke = Stroke {
+            width,
+            ..Stroke::default()
+        };
+        self.pixmap
+            .stroke_path(path, &paint, &stroke, Transform::identity(), None);
+    }
+}
+
+/// This trait defines a card which can be rasterized into a thumbnail.
+pub trait RasterCard {
+    /// This function draws the card on a thumbnail of `width` x `height` pixels.
+    fn rasterize(&self, width: u32, height: u32) -> Result<Thumbnail, String>;
+
+    /// This function draws the card and encodes it as a PNG file.
+    fn to_png(&self, width: u32, height: u32) -> Result<Vec<u8>, String> {
+        self.rasterize(width, height)?.encode_png()
+    }
+}
+
+impl RasterCard for SpotItCard {
+    /// This function draws a round SpotIt! card, with every symbol as a shape of its own colour.
+    fn rasterize(&self, width: u32, height: u32) -> Result<Thumbnail, String> {
+        spotit_thumbnail(self, &Layout::default(), width, height)
+    }
+}
+
+impl RasterCard for LaidOutCard<'_> {
+    /// This function draws a round SpotIt! card, with every symbol sized and turned by the layout.
+    fn rasterize(&self, width: u32, height: u32) -> Result<Thumbnail, String> {
+        spotit_thumbnail(self.card, &self.layout, width, height)
+    }
+}
+
+/// This function draws a round SpotIt! card with the symbols placed by the layout.
+fn spotit_thumbnail(
+    card: &SpotItCard,
+    layout: &Layout,
+    width: u32,
+    height: u32,
+) -> Result<Thumbnail, String> {
+    let mut thumbnail = Thumbnail::new(width, height)?;
+    let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+    let radius = cx.min(cy) * 0.94;
+    let face = circle(cx, cy, radius)?;
+    thumbnail.fill_path(&face, Color::from_rgba8(0xff, 0xfd, 0xf5, 0xff));
+    thumbnail.stroke_path(
+        &face,
+        Color::from_rgba8(0x33, 0x33, 0x33, 0xff),
+        radius * 0.02,
+    );
+
+    let symbols = card.sorted_symbols();
+    let centres = ring_layout(symbols.len(), cx as f64, cy as f64, radius as f64);
+    for ((symbol, (x, y)), placement) in symbols.iter().zip(centres).zip(layout.placements(card)) {
+        let (x, y) = (x as f32, y as f32);
+        let size = radius * 0.16 * placement.scale as f32;
+        let path = symbol_shape(*symbol, x, y, size)?
+            .transform(Transform::from_rotate_at(placement.angle as f32, x, y))
+            .ok_or_else(|| "Cannot turn a symbol.".to_string())?;
+        thumbnail.fill_path(&path, symbol_colour(*symbol));
+    }
+    Ok(thumbnail)
+}
+
+impl RasterCard for FrenchCard {
+    /// This function draws a French card with as many suit pips as its rank, or one large pip for an ace or a court card.
+    fn rasterize(&self, width: u32, height: u32) -> Result<Thumbnail, String> {
+        let mut thumbnail = Thumbnail::new(width, height)?;
+        let (w, h) = (width as f32, height as f32);
+        let border = w.min(h) * 0.03;
+        let face = rect(border, border, w - 2.0 * border, h - 2.0 * border)?;
+        thumbnail.fill_path(&face, Color::WHITE);
+        thumbnail.stroke_path(&face, Color::from_rgba8(0x99, 0x99, 0x99, 0xff), border);
+
+        let colour = if self.suit().is_red() {
+            Color::from_rgba8(0xc0, 0x39, 0x2b, 0xff)
+        } else {
+            Color::from_rgba8(0x11, 0x11, 0x11, 0xff)
+        };
+        let pips = match self.rank() {
+            FrenchRank::Jack | FrenchRank::Queen | FrenchRank::King => {
+                // Court cards have a frame around a large pip
+                let frame = rect(w * 0.2, h * 0.2, w * 0.6, h * 0.6)?;
+                thumbnail.stroke_path(&frame, colour, border);
+                1
+            }
+            FrenchRank::Ace => 1,
+            rank => rank as usize,
+        };
+        for (x, y, size) in pip_layout(pips, w, h) {
+            let path = suit_shape(self.suit(), x, y, size)?;
+            thumbnail.fill_path(&path, colour);
+        }
+        Ok(thumbnail)
+    }
+}
+
+/// This function returns the centre and size of every pip on a French card.
+fn pip_layout(pips: usize, w: f32, h: f32) -> Vec<(f32, f32, f32)> {
+    if pips == 1 {
+        return vec![(w / 2.0, h / 2.0, w.min(h) * 0.2)];
+    }
+    let columns = if pips > 3 { 2 } else { 1 };
+    let rows = pips.div_ceil(columns);
+    let size = (h * 0.6 / rows as f32).min(w * 0.25) * 0.4;
+    (0..pips)
+        .map(|i| {
+            let (column, row) = (i % columns, i / columns);
+            let x = w * (column as f32 + 1.0) / (columns as f32 + 1.0);
+            let y = h * 0.2 + h * 0.6 * (row as f32 + 0.5) / rows as f32;
+            (x, y, size)
+        })
+        .collect()
+}
+
+/// This function returns a colour for the symbol, spreading hues by the golden angle so that neighbours look different.
+fn symbol_colour(symbol: SpotItSymbol) -> Color {
+    let hue = (symbol as usize as f32 * 137.508) % 360.0;
+    let value = if (symbol as usize).is_multiple_of(2) {
+        0.85
+    } else {
+        0.6
+    };
+    let (r, g, b) = hsv_to_rgb(hue, 0.75, value);
+    Color::from_rgba(r, g, b, 1.0).unwrap_or(Color::BLACK)
+}
+
+fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> (f32, f32, f32) {
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let m = value - chroma;
+    let (r, g, b) = match (hue / 60.0) as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    (r + m, g + m, b + m)
+}
+
+/// This function returns the shape of the symbol, which cycles through a circle, a square, a triangle and a diamond.
+fn symbol_shape(symbol: SpotItSymbol, x: f32, y: f32, size: f32) -> Result<Path, String> {
+    match symbol as usize % 4 {
+        0 => circle(x, y, size),
+        1 => rect(x - size * 0.85, y - size * 0.85, size * 1.7, size * 1.7),
+        2 => polygon(&[
+            (x, y - size),
+            (x + size * 0.95, y + size * 0.75),
+            (x - size * 0.95, y + size * 0.75),
+        ]),
+        _ => polygon(&[(x, y - size), (x + size, y), (x, y + size), (x - size, y)]),
+    }
+}
+
+/// This function returns the shape of a suit pip centred on (x, y).
+fn suit_shape(suit: FrenchSuit, x: f32, y: f32, size: f32) -> Result<Path, String> {
+    let mut pb = PathBuilder::new();
+    match suit {
+        FrenchSuit::Diamond => {
+            return polygon(&[
+                (x, y - size),
+                (x + size * 0.7, y),
+                (x, y + size),
+                (x - size * 0.7, y),
+            ])
+        }
+        FrenchSuit::Heart => {
+            pb.push_circle(x - size * 0.45, y - size * 0.3, size * 0.5);
+            pb.push_circle(x + size * 0.45, y - size * 0.3, size * 0.5);
+            push_polygon(
+                &mut pb,
+                &[
+                    (x - size * 0.93, y - size * 0.1),
+                    (x + size * 0.93, y - size * 0.1),
+                    (x, y + size),
+                ],
+            );
+        }
+        FrenchSuit::Spade => {
+            pb.push_circle(x - size * 0.45, y + size * 0.2, size * 0.5);
+            pb.push_circle(x + size * 0.45, y + size * 0.2, size * 0.5);
+            push_polygon(
+                &mut pb,
+                &[(x - size * 0.93, y), (x + size * 0.93, y), (x, y - size)],
+            );
+            push_polygon(
+                &mut pb,
+                &[
+                    (x, y + size * 0.2),
+                    (x + size * 0.3, y + size),
+                    (x - size * 0.3, y + size),
+                ],
+            );
+        }
+        FrenchSuit::Club => {
+            pb.push_circle(x, y - size * 0.5, size * 0.42);
+            pb.push_circle(x - size * 0.5, y + size * 0.1, size * 0.42);
+            pb.push_circle(x + size * 0.5, y + size * 0.1, size * 0.42);
+            push_polygon(
+                &mut pb,
+                &[
+                    (x, y),
+                    (x + size * 0.3, y + size),
+                    (x - size * 0.3, y + size),
+                ],
+            );
+        }
+    }
+    pb.finish()
+        .ok_or_else(|| "Suit is too small to draw.".to_string())
+}
+
+fn circle(x: f32, y: f32, radius: f32) -> Result<Path, String> {
+    PathBuilder::from_circle(x, y, radius).ok_or_else(|| "Circle is too small to draw.".to_string())
+}
+
+fn rect(x: f32, y: f32, width: f32, height: f32) -> Result<Path, String> {
+    Rect::from_xywh(x, y, width, height)
+        .map(PathBuilder::from_rect)
+        .ok_or_else(|| "Rectangle is too small to draw.".to_string())
+}
+
+fn polygon(points: &[(f32, f32)]) -> Result<Path, String> {
+    let mut pb = PathBuilder::new();
+    push_polygon(&mut pb, points);
+    pb.finish()
+        .ok_or_else(|| "Polygon is too small to draw.".to_string())
+}
+
+fn push_polygon(pb: &mut PathBuilder, points: &[(f32, f32)]) {
+    for (i, (x, y)) in points.iter().enumerate() {
+        match i {
+            0 => pb.move_to(*x, *y),
+            _ => pb.line_to(*x, *y),
+        }
+    }
+    pb.close();
+}
+
+impl SpotItDeck {
+    /// This function rasterizes every card of the deck as a PNG thumbnail of `width` x `height` pixels.
+    pub fn thumbnails(&self, width: u32, height: u32) -> Result<Vec<Vec<u8>>, String> {
+        self.cards
+            .iter()
+            .map(|card| card.to_png(width, height))
+            .collect()
+    }
+}
+
+impl FrenchDeck {
+    /// This function rasterizes every card of the deck as a PNG thumbnail of `width` x `height` pixels.
+    pub fn thumbnails(&self, width: u32, height: u32) -> Result<Vec<Vec<u8>>, String> {
+        self.cards
+            .iter()
+            .map(|card| card.to_png(width, height))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Deck;
+    use std::collections::HashSet;
+
+    const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+    #[test]
+    fn can_rasterize_spotitcard_as_png() {
+        let card = SpotItCard(HashSet::from([SpotItSymbol::Apple, SpotItSymbol::Banana]));
+        let png = card.to_png(128, 128).unwrap();
+        assert_eq!(png[..8], PNG_SIGNATURE);
+
+        let thumbnail = card.rasterize(128, 128).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (128, 128));
+        // corners are outside the round card
+        assert_eq!(thumbnail.pixel(0, 0), Some([0, 0, 0, 0]));
+        // Apple is the first symbol, which is drawn in the centre with its own colour
+        let apple = symbol_colour(SpotItSymbol::Apple).to_color_u8();
+        assert_eq!(
+            thumbnail.pixel(64, 64),
+            Some([apple.red(), apple.green(), apple.blue(), 0xff])
+        );
+        assert_eq!(thumbnail.pixel(128, 0), None);
+    }
+    #[test]
+    fn can_rasterize_deterministically() {
+        let deck = SpotItDeck::default();
+        let first = deck.thumbnails(64, 64).unwrap();
+        let second = deck.thumbnails(64, 64).unwrap();
+        assert_eq!(first.len(), 57);
+        assert_eq!(first, second);
+        assert_ne!(first[0], first[1]);
+    }
+    #[test]
+    fn can_rasterize_frenchcard_as_png() {
+        let deck = FrenchDeck::default();
+        let thumbnails = deck.thumbnails(63, 88).unwrap();
+        assert_eq!(thumbnails.len(), 52);
+        assert!(thumbnails.iter().all(|png| png[..8] == PNG_SIGNATURE));
+
+        let ace = FrenchCard(FrenchRank::Ace, FrenchSuit::Heart)
+            .rasterize(100, 140)
+            .unwrap();
+        assert_eq!(ace.pixel(50, 70), Some([0xc0, 0x39, 0x2b, 0xff]));
+        let ace = FrenchCard(FrenchRank::Ace, FrenchSuit::Spade)
+            .rasterize(100, 140)
+            .unwrap();
+        assert_eq!(ace.pixel(50, 70), Some([0x11, 0x11, 0x11, 0xff]));
+    }
+    #[test]
+    fn can_lay_out_pips_by_rank() {
+        assert_eq!(pip_layout(1, 100.0, 140.0).len(), 1);
+        assert_eq!(pip_layout(3, 100.0, 140.0).len(), 3);
+        let pips = pip_layout(10, 100.0, 140.0);
+        assert_eq!(pips.len(), 10);
+        assert!(pips
+            .iter()
+            .all(|(x, y, _)| *x > 0.0 && *x < 100.0 && *y > 0.0 && *y < 140.0));
+    }
+    #[test]
+    fn cannot_rasterize_empty_thumbnail() {
+        let card = FrenchCard(FrenchRank::Two, FrenchSuit::Club);
+        assert!(card.to_png(0, 10).is_err());
+    }
+}
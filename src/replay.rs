@@ -0,0 +1,526 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Event log and replays of SpotIt! games in `cardgame` crate.
+//!
+//! A `Recorder` plays a game of any mode and writes down everything the engine does as a stream of typed `Event`s: deals, cards revealed, claims, verdicts, penalties and score changes.
+//! Together with the mode, the players and the seed the deck was shuffled with, the events make a `Replay`, which is saved as a JSON file with a version number.
+//!
+//! A replay can rebuild the game as it was after any number of events, and `Replay::verify` plays the whole game again to check it ends the same way, e.g. to settle a dispute or reproduce a bug.
+//! Only deals, claims and penalties are played again: the other events follow from them, and are compared with what the engine does now.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+
+use crate::game::arbiter::Penalty;
+use crate::game::registry::{ModeOptions, Registry};
+use crate::game::{GameMode, Phase, PlayerId, Table, Verdict};
+use crate::save::read_versioned;
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use crate::SpotItDeck;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+/// This is the version of the replay format written by this crate. Replays of later versions cannot be read.
+pub const REPLAY_VERSION: u32 = 1;
+
+/// This enum defines what the engine did.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum Event {
+    /// This is a deal, of the game or of a round.
+    Deal,
+    /// This is a card turned face up, i.e. a card players can now look for a match on.
+    Reveal { card: SpotItCard },
+    /// This is a player claiming a symbol on one of their chances. See `GameMode::chances`.
+    Claim {
+        player: PlayerId,
+        chance: usize,
+        symbol: SpotItSymbol,
+    },
+    /// This is the verdict on the claim before.
+    Verdict(Verdict),
+    /// This is a penalty given to a player. See `arbiter::Penalty`.
+    Penalty { player: PlayerId, penalty: Penalty },
+    /// This is a change of a player's score, and the new score.
+    Score {
+        player: PlayerId,
+        change: i32,
+        score: i32,
+    },
+}
+
+/// This struct defines how a game stood when it was saved.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct Outcome {
+    /// This is the score of every player, in seating order.
+    pub scores: Vec<i32>,
+    /// This is the winners, or none if the game had not finished.
+    pub winners: Vec<PlayerId>,
+}
+
+impl Outcome {
+    fn of(game: &dyn GameMode) -> Self {
+        Outcome {
+            scores: game.table().players.iter().map(|p| p.score).collect(),
+            winners: game.winners(),
+        }
+    }
+}
+
+/// This struct defines a replay, i.e. how to set a game up and what happened in it.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    /// This is the version of the format. See `REPLAY_VERSION`.
+    pub version: u32,
+    /// This is the name of the game mode. See `Registry`.
+    pub mode: String,
+    /// This is the order of the projective plane the deck is generated from.
+    pub order: u8,
+    /// This is the seed the deck is shuffled with.
+    pub seed: u64,
+    /// This is the number of rounds, for modes played in rounds.
+    pub rounds: usize,
+    /// This is the names of the players, in seating order.
+    pub players: Vec<String>,
+    /// This is what happened, in order.
+    pub events: Vec<Event>,
+    /// This is how the game stood at the end of the events.
+    pub outcome: Outcome,
+}
+
+impl Replay {
+    /// This function writes the replay as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default() + "\n"
+    }
+
+    /// This function reads a replay written by `to_json`.
+    /// It returns an error if the text is not a replay, or was written by a later version of the format.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let value = read_versioned(text, "a replay", REPLAY_VERSION as u64)?;
+        serde_json::from_value(value).map_err(|e| format!("Not a valid replay: {}", e))
+    }
+
+    /// This function sets the game up again, before anything happened, with the mode found in the registry.
+    pub fn start(&self, registry: &Registry) -> Result<Recorder, String> {
+        let names: Vec<&str> = self.players.iter().map(|n| n.as_str()).collect();
+        Recorder::new(
+            registry,
+            &self.mode,
+            self.order,
+            self.seed,
+            &names,
+            self.rounds,
+        )
+    }
+
+    /// This function rebuilds the game as it was after the first `events` events, by dealing and claiming again.
+    pub fn state_at(&self, registry: &Registry, events: usize) -> Result<Recorder, String> {
+        let mut recorder = self.start(registry)?;
+        for (i, event) in self.events.iter().take(events).enumerate() {
+            let result = match event {
+                Event::Deal => recorder.deal(),
+                Event::Claim {
+                    player,
+                    chance,
+                    symbol,
+                } => recorder.claim(*player, *chance, *symbol).map(|_| ()),
+                Event::Penalty { player, penalty } => recorder.penalise(*player, *penalty),
+                _ => Ok(()),
+            };
+            result.map_err(|e| format!("Event {} cannot be played again: {}", i + 1, e))?;
+        }
+        Ok(recorder)
+    }
+
+    /// This function plays the whole game again, and checks that the engine does the same and ends the game the same.
+    /// It returns an error naming the first event which differs.
+    pub fn verify(&self, registry: &Registry) -> Result<(), String> {
+        let recorder = self.state_at(registry, self.events.len())?;
+        let replayed = recorder.replay();
+        for (i, (recorded, again)) in self.events.iter().zip(replayed.events.iter()).enumerate() {
+            if recorded != again {
+                return Err(format!(
+                    "Event {} differs: {:?} was recorded, but {:?} happens.",
+                    i + 1,
+                    recorded,
+                    again
+                ));
+            }
+        }
+        if self.events.len() != replayed.events.len() {
+            return Err(format!(
+                "{} events were recorded, but {} happen.",
+                self.events.len(),
+                replayed.events.len()
+            ));
+        }
+        if self.outcome != replayed.outcome {
+            return Err(format!(
+                "The game ended {:?}, but ends {:?} when played again.",
+                self.outcome, replayed.outcome
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// This struct defines a game of any mode which records what happens in it.
+pub struct Recorder {
+    game: Box<dyn GameMode>,
+    replay: Replay,
+}
+
+impl Recorder {
+    /// This function seats the players at a game of the mode found in the registry, with a deck generated from a projective plane of the order and shuffled with the seed.
+    pub fn new(
+        registry: &Registry,
+        mode: &str,
+        order: u8,
+        seed: u64,
+        names: &[&str],
+        rounds: usize,
+    ) -> Result<Self, String> {
+        let mode = registry.find(mode)?;
+        let mut deck = SpotItDeck::generate_by_prime(order)?;
+        deck.cards.shuffle(&mut StdRng::seed_from_u64(seed));
+        Ok(Recorder {
+            game: mode.start(deck, names, &ModeOptions { rounds })?,
+            replay: Replay {
+                version: REPLAY_VERSION,
+                mode: mode.name().to_string(),
+                order,
+                seed,
+                rounds,
+                players: names.iter().map(|n| n.to_string()).collect(),
+                events: Vec::new(),
+                outcome: Outcome::default(),
+            },
+        })
+    }
+
+    /// This function returns the game being recorded.
+    pub fn game(&self) -> &dyn GameMode {
+        self.game.as_ref()
+    }
+
+    /// This function returns the events so far.
+    pub fn events(&self) -> &[Event] {
+        &self.replay.events
+    }
+
+    /// This function returns the replay of the game so far.
+    pub fn replay(&self) -> Replay {
+        Replay {
+            outcome: Outcome::of(self.game()),
+            ..self.replay.clone()
+        }
+    }
+
+    /// This function returns the cards players can look for a match on, without repeats.
+    fn face_up_cards(&self) -> Vec<SpotItCard> {
+        let mut cards: Vec<SpotItCard> = Vec::new();
+        for player in 0..self.game.table().players.len() {
+            for card in self.game.chances(player).into_iter().flatten() {
+                if !cards.contains(card) {
+                    cards.push(card.clone());
+                }
+            }
+        }
+        cards
+    }
+
+    /// This function records the cards turned face up and the scores changed since the state given.
+    fn record_changes(&mut self, face_up: &[SpotItCard], scores: &[i32]) {
+        for card in self.face_up_cards() {
+            if !face_up.contains(&card) {
+                self.replay.events.push(Event::Reveal { card });
+            }
+        }
+        for (player, p) in self.game.table().players.iter().enumerate() {
+            if p.score != scores[player] {
+                self.replay.events.push(Event::Score {
+                    player,
+                    change: p.score - scores[player],
+                    score: p.score,
+                });
+            }
+        }
+    }
+
+    fn scores(&self) -> Vec<i32> {
+        self.game.table().players.iter().map(|p| p.score).collect()
+    }
+
+    /// This function deals the game, or the next round, and records it.
+    pub fn deal(&mut self) -> Result<(), String> {
+        let (face_up, scores) = (self.face_up_cards(), self.scores());
+        self.game.deal()?;
+        self.replay.events.push(Event::Deal);
+        self.record_changes(&face_up, &scores);
+        Ok(())
+    }
+
+    /// This function gives the player a penalty, and records it.
+    pub fn penalise(&mut self, player: PlayerId, penalty: Penalty) -> Result<(), String> {
+        let (face_up, scores) = (self.face_up_cards(), self.scores());
+        self.game.penalise(player, penalty)?;
+        self.replay.events.push(Event::Penalty { player, penalty });
+        self.record_changes(&face_up, &scores);
+        Ok(())
+    }
+
+    /// This function judges a claim of the symbol on the chance, and records it. A claim which is not accepted at all is not recorded.
+    pub fn claim(
+        &mut self,
+        player: PlayerId,
+        chance: usize,
+        symbol: SpotItSymbol,
+    ) -> Result<Verdict, String> {
+        let (face_up, scores) = (self.face_up_cards(), self.scores());
+        let verdict = self.game.claim_chance(player, chance, symbol)?;
+        self.replay.events.push(Event::Claim {
+            player,
+            chance,
+            symbol,
+        });
+        self.replay.events.push(Event::Verdict(verdict));
+        self.record_changes(&face_up, &scores);
+        Ok(verdict)
+    }
+}
+
+/// Deals, claims and penalties are recorded. The other hooks are those of the game, and what they do is not recorded.
+impl GameMode for Recorder {
+    fn table(&self) -> &Table {
+        self.game.table()
+    }
+
+    /// Changes made through the table itself are not recorded. See `penalise`.
+    fn table_mut(&mut self) -> &mut Table {
+        self.game.table_mut()
+    }
+
+    fn phase(&self) -> Phase {
+        self.game.phase()
+    }
+
+    fn set_phase(&mut self, phase: Phase) {
+        self.game.set_phase(phase)
+    }
+
+    fn deal(&mut self) -> Result<(), String> {
+        self.deal()
+    }
+
+    fn chances(&self, player: PlayerId) -> Vec<Vec<&SpotItCard>> {
+        self.game.chances(player)
+    }
+
+    fn resolve(&mut self, player: PlayerId, chance: usize) -> Result<(), String> {
+        self.game.resolve(player, chance)
+    }
+
+    fn score(&mut self, player: PlayerId, chance: usize) -> Result<(), String> {
+        self.game.score(player, chance)
+    }
+
+    fn miss(&mut self, player: PlayerId) -> Result<(), String> {
+        self.game.miss(player)
+    }
+
+    fn is_over(&self) -> bool {
+        self.game.is_over()
+    }
+
+    fn winners(&self) -> Vec<PlayerId> {
+        self.game.winners()
+    }
+
+    fn cards(&self) -> Vec<usize> {
+        self.game.cards()
+    }
+
+    fn claim_chance(
+        &mut self,
+        player: PlayerId,
+        chance: usize,
+        symbol: SpotItSymbol,
+    ) -> Result<Verdict, String> {
+        self.claim(player, chance, symbol)
+    }
+
+    fn penalise(&mut self, player: PlayerId, penalty: Penalty) -> Result<(), String> {
+        self.penalise(player, penalty)
+    }
+
+    fn lose_card(&mut self, player: PlayerId) -> Result<(), String> {
+        self.game.lose_card(player)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::bot::{Bot, Skill};
+    use crate::game::{play_turn, Spotter};
+
+    /// This function records a game between bots.
+    fn recorded(mode: &str, seed: u64) -> Replay {
+        let names = ["Ann", "Bob", "Cat"];
+        let mut recorder = Recorder::new(&Registry::builtin(), mode, 3, seed, &names, 2).unwrap();
+        let mut bots: Vec<Box<dyn Spotter>> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                Box::new(Bot::new(name, Skill::Beginner, seed + i as u64)) as Box<dyn Spotter>
+            })
+            .collect();
+        while recorder.phase() == Phase::Setup {
+            recorder.deal().unwrap();
+            while play_turn(&mut recorder, &mut bots).unwrap().is_some() {}
+        }
+        recorder.replay()
+    }
+
+    #[test]
+    fn events_follow_the_game() {
+        let replay = recorded("classic", 1);
+        let events = &replay.events;
+        // The deal turns two cards over
+        assert_eq!(events[0], Event::Deal);
+        assert!(matches!(events[1], Event::Reveal { .. }));
+        assert!(matches!(events[2], Event::Reveal { .. }));
+        assert!(matches!(events[3], Event::Claim { .. }));
+        let claims = events
+            .iter()
+            .filter(|e| matches!(e, Event::Claim { .. }))
+            .count();
+        let verdicts = events
+            .iter()
+            .filter(|e| matches!(e, Event::Verdict(_)))
+            .count();
+        let points: i32 = events
+            .iter()
+            .map(|e| match e {
+                Event::Score { change, .. } => *change,
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(claims, verdicts);
+        assert_eq!(points, 12);
+        assert_eq!(replay.outcome.scores.iter().sum::<i32>(), 12);
+        assert!(!replay.outcome.winners.is_empty());
+    }
+    #[test]
+    fn replays_are_saved_and_verified() {
+        let replay = recorded("classic", 2);
+        let json = replay.to_json();
+        assert!(json.contains("\"version\": 1"));
+        assert!(json.contains("\"mode\": \"classic\""));
+        let read = Replay::from_json(&json).unwrap();
+        assert_eq!(read, replay);
+        assert_eq!(read.verify(&Registry::builtin()), Ok(()));
+
+        // Any change to what happened is caught
+        let mut forged = replay.clone();
+        forged.outcome.scores[0] += 1;
+        assert!(forged
+            .verify(&Registry::builtin())
+            .unwrap_err()
+            .starts_with("The game ended"));
+        let mut forged = replay.clone();
+        let verdict = forged
+            .events
+            .iter()
+            .position(|e| matches!(e, Event::Verdict(Verdict::Correct(_))))
+            .unwrap();
+        forged.events.remove(verdict);
+        assert!(forged
+            .verify(&Registry::builtin())
+            .unwrap_err()
+            .starts_with("Event"));
+        let mut forged = replay.clone();
+        forged.seed += 1;
+        assert!(forged.verify(&Registry::builtin()).is_err());
+    }
+    #[test]
+    fn intermediate_states_are_rebuilt() {
+        let replay = recorded("tower", 3);
+        let start = replay.state_at(&Registry::builtin(), 0).unwrap();
+        assert_eq!(start.phase(), Phase::Setup);
+        let first_claim = replay
+            .events
+            .iter()
+            .position(|e| matches!(e, Event::Claim { .. }))
+            .unwrap();
+        let dealt = replay.state_at(&Registry::builtin(), first_claim).unwrap();
+        assert_eq!(dealt.phase(), Phase::Playing);
+        assert_eq!(dealt.events(), &replay.events[..first_claim]);
+        assert!(dealt.table().players.iter().all(|p| p.score == 0));
+        let end = replay
+            .state_at(&Registry::builtin(), replay.events.len())
+            .unwrap();
+        assert_eq!(end.replay(), replay);
+    }
+    #[test]
+    fn every_mode_can_be_replayed() {
+        for mode in ["well", "hot-potato", "poisoned-gift", "triplet"] {
+            let replay = recorded(mode, 4);
+            assert_eq!(
+                replay.outcome,
+                Outcome::of(
+                    replay
+                        .state_at(&Registry::builtin(), usize::MAX)
+                        .unwrap()
+                        .game()
+                )
+            );
+            assert_eq!(replay.verify(&Registry::builtin()), Ok(()), "{}", mode);
+        }
+    }
+    #[test]
+    fn penalties_are_recorded_and_replayed() {
+        let mut recorder =
+            Recorder::new(&Registry::builtin(), "tower", 3, 6, &["Ann", "Bob"], 1).unwrap();
+        recorder.deal().unwrap();
+        recorder.penalise(1, Penalty::Deduct(2)).unwrap();
+        recorder.penalise(0, Penalty::LoseCard).unwrap();
+        assert_eq!(
+            recorder.events()[recorder.events().len() - 3..],
+            [
+                Event::Penalty {
+                    player: 1,
+                    penalty: Penalty::Deduct(2)
+                },
+                Event::Score {
+                    player: 1,
+                    change: -2,
+                    score: -2
+                },
+                Event::Penalty {
+                    player: 0,
+                    penalty: Penalty::LoseCard
+                },
+            ][..]
+        );
+        let replay = recorder.replay();
+        assert_eq!(replay.verify(&Registry::builtin()), Ok(()));
+        assert!(replay
+            .state_at(&Registry::builtin(), usize::MAX)
+            .unwrap()
+            .table()
+            .players[0]
+            .pile
+            .is_empty());
+    }
+    #[test]
+    fn unknown_versions_are_refused() {
+        let json = recorded("classic", 5).to_json();
+        let later = json.replacen("\"version\": 1", "\"version\": 2", 1);
+        assert!(Replay::from_json(&later).unwrap_err().contains("version 2"));
+        assert!(Replay::from_json("{}").is_err());
+        assert!(Replay::from_json("not json").is_err());
+    }
+}
@@ -0,0 +1,291 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Saved games of SpotIt! in `cardgame` crate.
+//!
+//! A `SavedGame` holds the whole state of a game in progress, i.e. the piles in order, the players' cards and scores, the mode and its rules, and the computer opponents with the state of their random number generators.
+//! It is written as JSON, so that a game interrupted on a kiosk can be resumed later and go on exactly as it would have.
+//!
+//! Every file has a version. Files of older versions are brought up to date by the `MIGRATIONS`, one version at a time, before they are read.
+//! To change the format, bump `SAVE_VERSION` and add the step from the previous version at the end of `MIGRATIONS`.
+//!
+//! The other files of this crate with a version, e.g. profiles, replays and tournaments, are read with `read_versioned` and written with `write_atomically` too.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+
+use crate::game::bot::Bot;
+use crate::game::hot_potato::HotPotato;
+use crate::game::poisoned_gift::PoisonedGift;
+use crate::game::solo::SoloState;
+use crate::game::tower::Tower;
+use crate::game::triplet::Triplet;
+use crate::game::well::Well;
+use crate::game::{Game, GameMode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+/// This is the version of the save format written by this crate.
+pub const SAVE_VERSION: u64 = 1;
+
+/// This is a step of migration, which changes a save of one version into the next version.
+type Migration = fn(&mut Value) -> Result<(), String>;
+
+/// This is the steps of migration: the first one changes version 1 into version 2, and so on. There are none yet.
+const MIGRATIONS: [Migration; SAVE_VERSION as usize - 1] = [];
+
+/// This enum defines the game saved, in any mode.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SavedMode {
+    Classic(Game),
+    Solo(SoloState),
+    Tower(Tower),
+    Well(Well),
+    HotPotato(HotPotato),
+    PoisonedGift(PoisonedGift),
+    Triplet(Triplet),
+}
+
+impl SavedMode {
+    /// This function returns the game to go on playing, or None for a solo game, which needs a clock. See `Solo::resume`.
+    pub fn into_game(self) -> Option<Box<dyn GameMode>> {
+        Some(match self {
+            SavedMode::Classic(game) => Box::new(game),
+            SavedMode::Solo(_) => return None,
+            SavedMode::Tower(game) => Box::new(game),
+            SavedMode::Well(game) => Box::new(game),
+            SavedMode::HotPotato(game) => Box::new(game),
+            SavedMode::PoisonedGift(game) => Box::new(game),
+            SavedMode::Triplet(game) => Box::new(game),
+        })
+    }
+}
+
+/// This struct defines a saved game.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    /// This is the version of the format. See `SAVE_VERSION`.
+    pub version: u64,
+    /// This is the game.
+    pub game: SavedMode,
+    /// This is the computer opponents, in the order they sit at the table.
+    pub bots: Vec<Bot>,
+}
+
+impl SavedGame {
+    /// This function saves a game with the bots playing it.
+    pub fn new(game: SavedMode, bots: Vec<Bot>) -> Self {
+        SavedGame {
+            version: SAVE_VERSION,
+            game,
+            bots,
+        }
+    }
+
+    /// This function writes the saved game as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default() + "\n"
+    }
+
+    /// This function reads a saved game written by `to_json`, in this version of the format or an older one.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let value = read_versioned(text, "a saved game", SAVE_VERSION)?;
+        serde_json::from_value(migrate(value, &MIGRATIONS)?)
+            .map_err(|e| format!("Not a valid saved game: {}", e))
+    }
+
+    /// This function writes the saved game to a file, so that an old save is kept if writing is interrupted. See `write_atomically`.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        write_atomically(path, &self.to_json())
+    }
+
+    /// This function reads a saved game from a file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::from_json(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+/// This function writes the text to a file. The file is replaced in one go, so the old file is kept if writing is interrupted.
+pub fn write_atomically(path: &Path, text: &str) -> Result<(), String> {
+    let partial = path.with_extension("partial");
+    std::fs::write(&partial, text)
+        .and_then(|_| std::fs::rename(&partial, path))
+        .map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+}
+
+/// This function reads JSON with a version, e.g. `{"version": 1, ...}`, for a format whose latest version is `latest`. `kind` names what the JSON should be, e.g. "a replay", for errors.
+/// It returns an error if the text is not JSON, has no version, or has a later version than `latest`.
+pub fn read_versioned(text: &str, kind: &str, latest: u64) -> Result<Value, String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| format!("Not valid JSON: {}", e))?;
+    check_version(&value, kind, latest)?;
+    Ok(value)
+}
+
+/// This function returns the version of the JSON, or an error if it has none, or a later one than `latest`. See `read_versioned`.
+fn check_version(value: &Value, kind: &str, latest: u64) -> Result<u64, String> {
+    let version = value
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .filter(|v| *v >= 1)
+        .ok_or_else(|| format!("This is not {}: there is no version.", kind))?;
+    if version > latest {
+        return Err(format!(
+            "This is {} of version {}, but only version {} and below can be read.",
+            kind, version, latest
+        ));
+    }
+    Ok(version)
+}
+
+/// This function brings a save up to the latest version, i.e. the one after the last step of migration.
+/// It returns an error if the save has no version, or a later version than the steps know about.
+fn migrate(mut value: Value, steps: &[Migration]) -> Result<Value, String> {
+    let version = check_version(&value, "a saved game", steps.len() as u64 + 1)?;
+    for (step, to) in steps[version as usize - 1..].iter().zip(version + 1..) {
+        step(&mut value)?;
+        value["version"] = Value::from(to);
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::bot::Skill;
+    use crate::game::clock::ManualClock;
+    use crate::game::solo::Solo;
+    use crate::game::{common_symbol, play_turn, Phase, Spotter, Turn};
+    use crate::{Deck, SpotItDeck};
+    use std::time::Duration;
+
+    fn spotters(bots: &[Bot]) -> Vec<Box<dyn Spotter>> {
+        bots.iter()
+            .map(|b| Box::new(b.clone()) as Box<dyn Spotter>)
+            .collect()
+    }
+
+    fn play_out(game: &mut dyn GameMode, spotters: &mut [Box<dyn Spotter>]) -> Vec<Turn> {
+        let mut turns = Vec::new();
+        while let Some(turn) = play_turn(game, spotters).unwrap() {
+            turns.push(turn);
+        }
+        turns
+    }
+
+    #[test]
+    fn resumed_game_is_the_same() {
+        let mut deck = SpotItDeck::generate_by_prime(3).unwrap();
+        deck.shuffle();
+        let mut game = Tower::new(deck, &["Ann", "Bob"]).unwrap();
+        game.deal().unwrap();
+        let mut bots = spotters(&[Bot::new("Ann", Skill::Expert, 1)]);
+        for _ in 0..4 {
+            play_turn(&mut game, &mut bots).unwrap();
+        }
+        let saved = SavedGame::new(SavedMode::Tower(game.clone()), Vec::new());
+        let json = saved.to_json();
+        assert!(json.contains("\"version\": 1"));
+        assert!(json.contains("\"tower\""));
+        let read = SavedGame::from_json(&json).unwrap();
+        assert_eq!(read, saved);
+        assert_eq!(read.game, SavedMode::Tower(game));
+    }
+    #[test]
+    fn bots_keep_their_random_state() {
+        let mut game = Game::new(SpotItDeck::generate_by_prime(3).unwrap(), &["A", "B"]).unwrap();
+        game.deal().unwrap();
+        let mut bots = vec![
+            Bot::new("A", Skill::Beginner, 1),
+            Bot::new("B", Skill::Beginner, 2),
+        ];
+        // Move the generators on from their seeds
+        for bot in bots.iter_mut() {
+            bot.spot(&game.chances(0));
+        }
+        let saved = SavedGame::new(SavedMode::Classic(game.clone()), bots.clone());
+        let read = SavedGame::from_json(&saved.to_json()).unwrap();
+        assert_eq!(read.bots, bots);
+
+        let mut original: Box<dyn GameMode> = Box::new(game);
+        let turns = play_out(original.as_mut(), &mut spotters(&bots));
+        let mut resumed = read.game.into_game().unwrap();
+        assert_eq!(play_out(resumed.as_mut(), &mut spotters(&read.bots)), turns);
+        assert_eq!(resumed.phase(), Phase::Finished);
+        assert_eq!(resumed.table(), original.table());
+    }
+    #[test]
+    fn solo_game_resumes_with_the_time_spent() {
+        let clock = ManualClock::new();
+        let mut solo = Solo::new(SpotItDeck::default(), "Ann", &clock).unwrap();
+        solo.deal().unwrap();
+        clock.advance(Duration::from_secs(3));
+        let saved = SavedGame::new(SavedMode::Solo(solo.state()), Vec::new());
+        assert!(saved.clone().game.into_game().is_none());
+
+        let SavedMode::Solo(state) = SavedGame::from_json(&saved.to_json()).unwrap().game else {
+            panic!("The mode was not kept");
+        };
+        let later = ManualClock::new();
+        later.set(Duration::from_secs(100));
+        let mut resumed = Solo::resume(state, &later);
+        later.advance(Duration::from_secs(2));
+        assert_eq!(resumed.elapsed(), Duration::from_secs(5));
+        let (centre, draw) = resumed.face_up_cards().unwrap();
+        let symbol = common_symbol(centre, draw).unwrap();
+        resumed.claim(symbol).unwrap();
+        assert_eq!(resumed.reaction_times(), [Duration::from_secs(5)]);
+        assert_eq!(resumed.elapsed(), Duration::ZERO);
+    }
+    #[test]
+    fn saves_are_written_to_files() {
+        let dir = std::env::temp_dir().join(format!("cardgame-save-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("game.json");
+        let mut game = Triplet::new(SpotItDeck::default(), &["Ann"]).unwrap();
+        game.deal().unwrap();
+        let saved = SavedGame::new(SavedMode::Triplet(game), Vec::new());
+        saved.save(&path).unwrap();
+        assert_eq!(SavedGame::load(&path), Ok(saved));
+        assert!(!path.with_extension("partial").exists());
+        assert!(SavedGame::load(&dir.join("missing.json")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    #[test]
+    fn old_versions_are_migrated_and_new_ones_refused() {
+        fn rename_players(value: &mut Value) -> Result<(), String> {
+            let players = value
+                .as_object_mut()
+                .and_then(|o| o.remove("people"))
+                .ok_or_else(|| "There are no people.".to_string())?;
+            value["players"] = players;
+            Ok(())
+        }
+        let steps: [Migration; 2] = [rename_players, |_| Ok(())];
+        let old = serde_json::json!({"version": 1, "people": ["Ann"]});
+        assert_eq!(
+            migrate(old, &steps),
+            Ok(serde_json::json!({"version": 3, "players": ["Ann"]}))
+        );
+        let middle = serde_json::json!({"version": 2, "people": ["Ann"]});
+        assert_eq!(migrate(middle.clone(), &steps).unwrap()["version"], 3);
+        assert!(migrate(serde_json::json!({"version": 1}), &steps).is_err());
+        assert!(migrate(serde_json::json!({"version": 4}), &steps)
+            .unwrap_err()
+            .contains("version 4"));
+        assert!(SavedGame::from_json("{\"version\": 2}").is_err());
+        assert!(SavedGame::from_json("{\"game\": {}}")
+            .unwrap_err()
+            .contains("no version"));
+        assert_eq!(
+            read_versioned("{\"version\": 2}", "a replay", 1),
+            Err(
+                "This is a replay of version 2, but only version 1 and below can be read."
+                    .to_string()
+            )
+        );
+        assert!(read_versioned("[", "a replay", 1).is_err());
+    }
+}
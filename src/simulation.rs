@@ -0,0 +1,328 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Headless simulation of SpotIt! games between bots in `cardgame` crate.
+//!
+//! A `Simulation` plays many games of one mode between computer opponents, with no one watching, and reports how they went: win rates by seat, game length and cards per player.
+//! Designers use it to compare house rules and decks made by `SpotItDeck::generate_by_prime` before trying them at the table.
+//!
+//! Everything random, i.e. the shuffles and the bots, is drawn from the seed of the simulation, so the same simulation always gives the same report.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+
+use crate::game::bot::{Bot, Skill};
+use crate::game::hot_potato::OFFICIAL_ROUNDS;
+use crate::game::registry::{ModeEntry, ModeOptions, Registry};
+use crate::game::{play_game, GameMode, PlayerId, Spotter, Verdict, MAX_TURNS};
+use crate::SpotItDeck;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+use std::time::Duration;
+
+/// This struct defines a simulation, i.e. many games of a mode between bots.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Simulation {
+    /// This is the name of the game mode. See `Registry`.
+    pub mode: String,
+    /// This is the skill of the bot in every seat, in seating order.
+    pub bots: Vec<Skill>,
+    /// This is the order of the projective plane the deck is generated from. See `SpotItDeck::generate_by_prime`.
+    pub order: u8,
+    /// This is the number of games to play.
+    pub games: usize,
+    /// This is the number of rounds of "The Hot Potato". Other modes do not use it.
+    pub rounds: usize,
+    /// This is the most claims in a game. A game still going after that many is stopped and counted as unfinished.
+    pub max_turns: usize,
+    /// This is the seed everything random is drawn from.
+    pub seed: u64,
+}
+
+impl Simulation {
+    /// This function sets up a thousand games of the mode between the bots, with the default deck and seed 0.
+    pub fn new(mode: &str, bots: &[Skill]) -> Self {
+        Simulation {
+            mode: mode.to_string(),
+            bots: bots.to_vec(),
+            order: 7,
+            games: 1000,
+            rounds: OFFICIAL_ROUNDS,
+            max_turns: MAX_TURNS,
+            seed: 0,
+        }
+    }
+
+    /// This function plays every game of a mode of this crate and returns the report. See `run_in`.
+    pub fn run(&self) -> Result<SimulationReport, String> {
+        self.run_in(&Registry::builtin())
+    }
+
+    /// This function plays every game of the mode found in the registry and returns the report.
+    /// It returns an error if there is no such mode, the deck cannot be generated or the mode cannot be played with these bots, e.g. too many of them.
+    pub fn run_in(&self, registry: &Registry) -> Result<SimulationReport, String> {
+        let mode = registry.find(&self.mode)?;
+        let cards = SpotItDeck::generate_by_prime(self.order)?.cards;
+        let names: Vec<String> = (1..=self.bots.len())
+            .map(|i| format!("Bot {}", i))
+            .collect();
+        let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut games = Vec::with_capacity(self.games);
+        for _ in 0..self.games {
+            let mut deck = SpotItDeck {
+                cards: cards.clone(),
+            };
+            deck.cards.shuffle(&mut rng);
+            let mut spotters: Vec<Box<dyn Spotter>> = self
+                .bots
+                .iter()
+                .zip(names.iter())
+                .map(|(skill, name)| {
+                    Box::new(Bot::new(name, *skill, rng.gen())) as Box<dyn Spotter>
+                })
+                .collect();
+            games.push(self.play(mode, deck, &names, &mut spotters)?);
+        }
+        Ok(SimulationReport {
+            mode: mode.name().to_string(),
+            seats: self.bots.len(),
+            games,
+        })
+    }
+
+    /// This function plays one game to the end, or until it runs out of turns.
+    fn play(
+        &self,
+        mode: &ModeEntry,
+        deck: SpotItDeck,
+        names: &[&str],
+        spotters: &mut [Box<dyn Spotter>],
+    ) -> Result<GameRecord, String> {
+        let mut record = GameRecord::default();
+        let options = ModeOptions {
+            rounds: self.rounds,
+        };
+        let mut game = mode.start(deck, names, &options)?;
+        for turn in play_game(game.as_mut(), spotters, self.max_turns)? {
+            record.turns += 1;
+            record.time += turn.sighting.after;
+            if let Verdict::Wrong(_) = turn.verdict {
+                record.wrong += 1;
+            }
+        }
+        record.finish(game.as_ref());
+        Ok(record)
+    }
+}
+
+/// This struct defines what happened in one simulated game.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct GameRecord {
+    /// This is the winners, or none if the game did not finish.
+    pub winners: Vec<PlayerId>,
+    /// This is the number of claims, right or wrong.
+    pub turns: usize,
+    /// This is the number of wrong claims.
+    pub wrong: usize,
+    /// This is the time the game took, i.e. the reaction times of all the claims added up.
+    pub time: Duration,
+    /// This is the cards every player ended with, in seating order, as counted by `GameMode::cards`, e.g. set aside over all rounds in "The Hot Potato".
+    pub cards: Vec<usize>,
+}
+
+impl GameRecord {
+    fn finish<M: GameMode + ?Sized>(&mut self, game: &M) {
+        self.winners = game.winners();
+        self.cards = game.cards();
+    }
+
+    /// This function returns true if the game was played to the end.
+    pub fn finished(&self) -> bool {
+        !self.winners.is_empty()
+    }
+}
+
+/// This struct defines the report of a simulation.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SimulationReport {
+    /// This is the name of the game mode.
+    pub mode: String,
+    /// This is the number of players.
+    pub seats: usize,
+    /// This is what happened in every game, in the order played.
+    pub games: Vec<GameRecord>,
+}
+
+impl SimulationReport {
+    /// This function returns the games which were played to the end.
+    fn finished(&self) -> impl Iterator<Item = &GameRecord> {
+        self.games.iter().filter(|g| g.finished())
+    }
+
+    /// This function returns the number of games stopped before the end.
+    pub fn unfinished(&self) -> usize {
+        self.games.len() - self.finished().count()
+    }
+
+    /// This function returns the share of finished games won from every seat, in seating order. A tie is a win for every player in it.
+    pub fn win_rates(&self) -> Vec<f64> {
+        let mut wins = vec![0; self.seats];
+        for game in self.finished() {
+            for winner in game.winners.iter() {
+                wins[*winner] += 1;
+            }
+        }
+        let finished = self.finished().count().max(1) as f64;
+        wins.into_iter().map(|w| w as f64 / finished).collect()
+    }
+
+    /// This function returns the mean number of claims in a finished game.
+    pub fn mean_turns(&self) -> f64 {
+        self.mean(|g| g.turns as f64)
+    }
+
+    /// This function returns the mean number of wrong claims in a finished game.
+    pub fn mean_wrong(&self) -> f64 {
+        self.mean(|g| g.wrong as f64)
+    }
+
+    /// This function returns the mean time a finished game took.
+    pub fn mean_time(&self) -> Duration {
+        Duration::from_secs_f64(self.mean(|g| g.time.as_secs_f64()))
+    }
+
+    /// This function returns the mean number of cards every seat ended a finished game with, in seating order.
+    pub fn mean_cards(&self) -> Vec<f64> {
+        (0..self.seats)
+            .map(|seat| self.mean(|g| g.cards.get(seat).copied().unwrap_or(0) as f64))
+            .collect()
+    }
+
+    fn mean<F: Fn(&GameRecord) -> f64>(&self, value: F) -> f64 {
+        let (count, total) = self
+            .finished()
+            .fold((0, 0.0), |(count, total), g| (count + 1, total + value(g)));
+        match count {
+            0 => 0.0,
+            _ => total / count as f64,
+        }
+    }
+
+    /// This function returns the report as JSON, for other tools to read.
+    pub fn to_json(&self) -> String {
+        let json = serde_json::json!({
+            "mode": self.mode,
+            "games": self.games.len(),
+            "unfinished": self.unfinished(),
+            "win_rates": self.win_rates(),
+            "mean_turns": self.mean_turns(),
+            "mean_wrong": self.mean_wrong(),
+            "mean_seconds": self.mean_time().as_secs_f64(),
+            "mean_cards": self.mean_cards(),
+        });
+        serde_json::to_string_pretty(&json).unwrap_or_default() + "\n"
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Mode: {}", self.mode)?;
+        writeln!(
+            f,
+            "Games: {} ({} unfinished)",
+            self.games.len(),
+            self.unfinished()
+        )?;
+        writeln!(f, "Claims per game: {:.1}", self.mean_turns())?;
+        writeln!(f, "Wrong claims per game: {:.1}", self.mean_wrong())?;
+        writeln!(f, "Game length: {:.1}s", self.mean_time().as_secs_f64())?;
+        write!(f, "Seat  Wins    Cards")?;
+        for (seat, (rate, cards)) in self
+            .win_rates()
+            .into_iter()
+            .zip(self.mean_cards())
+            .enumerate()
+        {
+            write!(
+                f,
+                "\n{:<4}  {:>5.1}%  {:>5.1}",
+                seat + 1,
+                rate * 100.0,
+                cards
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulation_is_reproducible() {
+        let mut simulation = Simulation::new("classic", &[Skill::Beginner, Skill::Expert]);
+        simulation.order = 3;
+        simulation.games = 20;
+        let report = simulation.run().unwrap();
+        assert_eq!(simulation.run().unwrap(), report);
+        assert_eq!(report.games.len(), 20);
+        assert_eq!(report.unfinished(), 0);
+        // 13 cards make 12 rounds
+        assert!(report.games.iter().all(|g| g.turns == 12 + g.wrong));
+        let rates = report.win_rates();
+        assert!(rates[1] > rates[0]);
+        assert_eq!(report.mean_cards().iter().sum::<f64>(), 12.0);
+
+        simulation.seed = 1;
+        assert_ne!(simulation.run().unwrap(), report);
+    }
+    #[test]
+    fn every_mode_can_be_simulated() {
+        for mode in Registry::builtin().names() {
+            let seats = match mode {
+                "solo" => 1,
+                _ => 3,
+            };
+            let mut simulation = Simulation::new(mode, &vec![Skill::Intermediate; seats]);
+            simulation.order = 3;
+            simulation.games = 5;
+            simulation.rounds = 2;
+            let report = simulation.run().unwrap();
+            assert_eq!(report.unfinished(), 0, "{}", mode);
+            assert!(report.win_rates().iter().sum::<f64>() >= 1.0);
+            assert!(report.to_string().starts_with(&format!("Mode: {}", mode)));
+            assert!(report.to_json().contains("\"win_rates\""));
+        }
+    }
+    #[test]
+    fn cards_are_counted_by_the_mode() {
+        let mut simulation = Simulation::new("hot-potato", &[Skill::Intermediate; 3]);
+        simulation.order = 3;
+        simulation.games = 5;
+        simulation.rounds = 2;
+        for game in simulation.run().unwrap().games {
+            // The winners set aside the fewest cards, though every hand is empty at the end
+            let fewest = *game.cards.iter().min().unwrap();
+            assert!(game.cards.iter().sum::<usize>() > 0);
+            assert!(game.winners.iter().all(|w| game.cards[*w] == fewest));
+        }
+    }
+    #[test]
+    fn games_are_stopped_after_too_many_turns() {
+        let mut simulation = Simulation::new("tower", &[Skill::Beginner]);
+        simulation.games = 2;
+        simulation.max_turns = 10;
+        let report = simulation.run().unwrap();
+        assert_eq!(report.unfinished(), 2);
+        assert_eq!(report.win_rates(), vec![0.0]);
+        assert_eq!(report.mean_turns(), 0.0);
+        assert!(Simulation::new("well", &[Skill::Expert; 60]).run().is_err());
+        assert_eq!(
+            Simulation::new("chess", &[Skill::Expert]).run(),
+            Err("Unknown game mode: chess.".to_string())
+        );
+    }
+}
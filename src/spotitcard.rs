@@ -10,10 +10,16 @@
 //! [`French Card Game`]: https://en.wikipedia.org/wiki/French_playing_cards
 //! [`SpotIt! Rules`]: https://www.ultraboardgames.com/spot-it/game-rules.php
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-#[derive(EnumIter, Debug, PartialEq, Clone, Copy, Eq, Hash)]
+#[derive(
+    EnumIter, Debug, PartialEq, Clone, Copy, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
 /// Thie enum defines the suits (or pattern) of the SpotIt! game. One card can has one or more suits.
 pub enum SpotItSymbol {
     Apple,
@@ -111,9 +117,98 @@ pub enum SpotItSymbol {
     Yuzu,
 }
 
+impl fmt::Display for SpotItSymbol {
+    /// This function shows the name of the symbol in words, e.g. "Chico Fruit".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = format!("{:?}", self);
+        let mut words = String::new();
+        for (i, c) in name.chars().enumerate() {
+            if i > 0 && c.is_uppercase() {
+                words.push(' ');
+            }
+            words.push(c);
+        }
+        f.pad(&words)
+    }
+}
+
+impl FromStr for SpotItSymbol {
+    type Err = String;
+    /// This function reads a symbol from its name, in words or not, in any case, e.g. "Chico Fruit" or "chicofruit".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s.split_whitespace().collect();
+        SpotItSymbol::iter()
+            .find(|symbol| format!("{:?}", symbol).eq_ignore_ascii_case(&name))
+            .ok_or_else(|| format!("Unknown symbol: {}.", s.trim()))
+    }
+}
+
+/// This enum defines the kinds of fruit the symbols show, e.g. to give a hint without naming the symbol.
+#[derive(EnumIter, Debug, PartialEq, Clone, Copy, Eq, Hash, PartialOrd, Ord)]
+pub enum SymbolCategory {
+    Berry,
+    Citrus,
+    Melon,
+    StoneFruit,
+    OrchardFruit,
+    TropicalFruit,
+    ExoticFruit,
+}
+
+impl fmt::Display for SymbolCategory {
+    /// This function shows the category in words, e.g. "stone fruit".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SymbolCategory::Berry => "berry",
+            SymbolCategory::Citrus => "citrus fruit",
+            SymbolCategory::Melon => "melon",
+            SymbolCategory::StoneFruit => "stone fruit",
+            SymbolCategory::OrchardFruit => "orchard fruit",
+            SymbolCategory::TropicalFruit => "tropical fruit",
+            SymbolCategory::ExoticFruit => "exotic fruit",
+        };
+        f.pad(name)
+    }
+}
+
+impl SpotItSymbol {
+    /// This function returns the kind of fruit the symbol shows.
+    pub fn category(&self) -> SymbolCategory {
+        use SpotItSymbol::*;
+        match self {
+            Bilberry | Blackberry | Blackcurrant | Blueberry | Boysenberry | Currant
+            | Cloudberry | Cranberry | Elderberry | GojiBerry | Gooseberry | Grape | Raisin
+            | Honeyberry | Huckleberry | JuniperBerry | Marionberry | Mulberry | Raspberry
+            | Salmonberry | Redcurrant | SalalBerry | Strawberry => SymbolCategory::Berry,
+            Grapefruit | Kumquat | Lemon | Lime | Orange | BloodOrange | Clementine | Mandarine
+            | Tangerine | Pomelo | Satsuma | UgliFruit | Yuzu => SymbolCategory::Citrus,
+            Cucumber | Kiwano | Melon | Cantaloupe | Honeydew | Watermelon => SymbolCategory::Melon,
+            Apricot | Cherry | Damson | Date | Jujube | Nance | Nectarine | Olive | Peach
+            | Plum | Prune | Plumcot => SymbolCategory::StoneFruit,
+            Apple | Loquat | Pear | Quince => SymbolCategory::OrchardFruit,
+            Avocado | Banana | Cherimoya | ChicoFruit | Coconut | CustardApple | Dragonfruit
+            | Durian | Feijoa | Guava | Jabuticaba | Jackfruit | Jambul | Kiwifruit | Longan
+            | Lychee | Mango | Mangosteen | MiracleFruit | Papaya | Passionfruit | Plantain
+            | Pineapple | PurpleMangosteen | Rambutan | Salak | Soursop | StarFruit | Tamarind => {
+                SymbolCategory::TropicalFruit
+            }
+            Fig | Persimmon | Physalis | Pomegranate | SolanumQuitoense | Tamarillo => {
+                SymbolCategory::ExoticFruit
+            }
+        }
+    }
+
+    /// This function returns the first letter of the name of the symbol, in upper case.
+    pub fn initial(&self) -> char {
+        format!("{:?}", self).chars().next().unwrap_or(' ')
+    }
+}
+
 /// This tuple struct defines a SpotIt Card.
 /// Please notice that a SpotIt Card can have 0, 1, or more than one suits. For example, a card can have both Apple and Banana suits.
-#[derive(Debug, PartialEq, Clone, Eq)]
+/// It is serialized as the list of its symbols in a stable order.
+#[derive(Debug, PartialEq, Clone, Eq, Serialize, Deserialize)]
+#[serde(into = "Vec<SpotItSymbol>", from = "Vec<SpotItSymbol>")]
 pub struct SpotItCard(pub HashSet<SpotItSymbol>);
 impl SpotItCard {
     /// This function returns true if the two cards have exactly one suit in common.
@@ -125,6 +220,36 @@ impl SpotItCard {
     pub fn symbols(&self) -> Vec<SpotItSymbol> {
         self.0.iter().cloned().collect()
     }
+    /// This function returns the symbols of the card in a stable order, which is handy for rendering.
+    pub fn sorted_symbols(&self) -> Vec<SpotItSymbol> {
+        let mut symbols = self.symbols();
+        symbols.sort();
+        symbols
+    }
+}
+
+impl From<SpotItCard> for Vec<SpotItSymbol> {
+    fn from(card: SpotItCard) -> Self {
+        card.sorted_symbols()
+    }
+}
+
+impl From<Vec<SpotItSymbol>> for SpotItCard {
+    fn from(symbols: Vec<SpotItSymbol>) -> Self {
+        SpotItCard(symbols.into_iter().collect())
+    }
+}
+
+impl fmt::Display for SpotItCard {
+    /// This function shows the symbols of the card in a stable order, e.g. "[Apple, Banana]".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<String> = self
+            .sorted_symbols()
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        write!(f, "[{}]", names.join(", "))
+    }
 }
 
 #[cfg(test)]
@@ -178,4 +303,50 @@ mod tests {
         assert_eq!(card1.symbols(), vec![SpotItSymbol::Yuzu]);
         //TODO how to test the order of the symbols?
     }
+    #[test]
+    fn can_show_sorted_symbols_on_spotitcard() {
+        let card1 = SpotItCard(HashSet::from([
+            SpotItSymbol::Yuzu,
+            SpotItSymbol::Apple,
+            SpotItSymbol::Banana,
+        ]));
+        assert_eq!(
+            card1.sorted_symbols(),
+            vec![
+                SpotItSymbol::Apple,
+                SpotItSymbol::Banana,
+                SpotItSymbol::Yuzu
+            ]
+        );
+    }
+    #[test]
+    fn can_parse_symbol_from_name() {
+        assert_eq!("Chico Fruit".parse(), Ok(SpotItSymbol::ChicoFruit));
+        assert_eq!(" chicofruit ".parse(), Ok(SpotItSymbol::ChicoFruit));
+        for symbol in SpotItSymbol::iter() {
+            assert_eq!(symbol.to_string().parse(), Ok(symbol));
+        }
+        assert!("Chico Fruits".parse::<SpotItSymbol>().is_err());
+    }
+    #[test]
+    fn can_display_spotitcard() {
+        assert_eq!(SpotItSymbol::ChicoFruit.to_string(), "Chico Fruit");
+        assert_eq!(format!("{:>6}", SpotItSymbol::Fig), "   Fig");
+        let card = SpotItCard(HashSet::from([
+            SpotItSymbol::ChicoFruit,
+            SpotItSymbol::Apple,
+        ]));
+        assert_eq!(card.to_string(), "[Apple, Chico Fruit]");
+        assert_eq!(SpotItCard(HashSet::new()).to_string(), "[]");
+    }
+    #[test]
+    fn symbols_have_a_category_and_an_initial() {
+        assert_eq!(SpotItSymbol::Lime.category(), SymbolCategory::Citrus);
+        assert_eq!(SpotItSymbol::Raisin.category(), SymbolCategory::Berry);
+        assert_eq!(SymbolCategory::StoneFruit.to_string(), "stone fruit");
+        for category in SymbolCategory::iter() {
+            assert!(SpotItSymbol::iter().any(|s| s.category() == category));
+        }
+        assert_eq!(SpotItSymbol::ChicoFruit.initial(), 'C');
+    }
 }
@@ -0,0 +1,523 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Support library to render cards of `cardgame` crate as SVG.
+//!
+//! Provide per-card SVG rendering for SpotIt! and French cards, and a print-and-play sheet generator which lays a whole deck out on A4 or Letter pages, ready to be printed and cut locally.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt!`]: https://www.amazon.com/Asmodee-SP411-Spot-It/dp/B0039S7NO6
+//! [`French Card Game`]: https://en.wikipedia.org/wiki/French_playing_cards
+//! [`SVG`]: https://www.w3.org/TR/SVG11/
+
+use crate::difficulty::{Layout, Placement};
+use crate::frenchcard::FrenchCard;
+use crate::spotitcard::SpotItCard;
+use crate::theme::Theme;
+use crate::{FrenchDeck, SpotItDeck};
+
+/// This trait defines a card which can draw its face as SVG.
+pub trait SvgCard {
+    /// This function returns the SVG elements of the card face, drawn in a box of `width` x `height` with the origin at the top left corner.
+    /// The background must be extended by `bleed` on every side so that the card can be cut without white edges.
+    fn svg_face(&self, width: f64, height: f64, bleed: f64) -> String;
+
+    /// This function returns a standalone SVG document of the card face, with the size in millimetres.
+    fn to_svg(&self, width: f64, height: f64) -> String {
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}mm\" height=\"{h}mm\" viewBox=\"0 0 {w} {h}\">\n{}</svg>\n",
+            self.svg_face(width, height, 0.0),
+            w = fmt(width),
+            h = fmt(height),
+        )
+    }
+}
+
+impl SvgCard for SpotItCard {
+    /// This function draws a round SpotIt! card, with one symbol in the centre and the others on a ring around it.
+    fn svg_face(&self, width: f64, height: f64, bleed: f64) -> String {
+        // The fruits theme has a label for every symbol
+        let labels = Theme::fruits().card_labels(self).unwrap_or_default();
+        let placements = Layout::default().placements(self);
+        spotit_face(&labels, &placements, width, height, bleed)
+    }
+}
+
+impl SvgCard for FrenchCard {
+    /// This function draws a French card with its rank and suit on the corners and a large suit in the middle.
+    fn svg_face(&self, width: f64, height: f64, bleed: f64) -> String {
+        let colour = if self.suit().is_red() {
+            "#c0392b"
+        } else {
+            "#111111"
+        };
+        let rank = self.rank().short_name();
+        let glyph = self.suit().glyph();
+        let corner = height * 0.09;
+        let mut svg = background(width, height, bleed, "#ffffff");
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" fill=\"none\" stroke=\"#999999\" stroke-width=\"0.3\"/>\n",
+            fmt(width * 0.03),
+            fmt(width * 0.03),
+            fmt(width * 0.94),
+            fmt(height - width * 0.06),
+            fmt(width * 0.05),
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" fill=\"{c}\">{}{}</text>\n",
+            fmt(width * 0.08),
+            fmt(corner + height * 0.03),
+            fmt(corner),
+            rank,
+            glyph,
+            c = colour,
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" fill=\"{c}\" text-anchor=\"end\" transform=\"rotate(180 {} {})\">{}{}</text>\n",
+            fmt(width * 0.08),
+            fmt(corner + height * 0.03),
+            fmt(corner),
+            fmt(width / 2.0),
+            fmt(height / 2.0),
+            rank,
+            glyph,
+            c = colour,
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" fill=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+            fmt(width / 2.0),
+            fmt(height / 2.0),
+            fmt(width * 0.5),
+            colour,
+            glyph,
+        ));
+        svg
+    }
+}
+
+/// This function draws a round SpotIt! card face from the labels of its symbols, sized and turned as placed.
+pub(crate) fn spotit_face(
+    labels: &[String],
+    placements: &[Placement],
+    width: f64,
+    height: f64,
+    bleed: f64,
+) -> String {
+    let (cx, cy) = (width / 2.0, height / 2.0);
+    let radius = width.min(height) / 2.0 * 0.94;
+    let mut svg = background(width, height, bleed, "#ffffff");
+    svg.push_str(&format!(
+        "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"#fffdf5\" stroke=\"#333333\" stroke-width=\"0.5\"/>\n",
+        fmt(cx),
+        fmt(cy),
+        fmt(radius),
+    ));
+    let centres = ring_layout(labels.len(), cx, cy, radius);
+    for ((label, (x, y)), placement) in labels.iter().zip(centres).zip(placements) {
+        let font_size = (radius * 0.16).min(radius * 1.8 / label.chars().count().max(1) as f64);
+        let turn = match placement.angle {
+            0.0 => String::new(),
+            angle => format!(
+                " transform=\"rotate({} {} {})\"",
+                fmt(angle),
+                fmt(x),
+                fmt(y)
+            ),
+        };
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\"{}>{}</text>\n",
+            fmt(x),
+            fmt(y),
+            fmt(font_size * placement.scale),
+            turn,
+            escape_xml(label),
+        ));
+    }
+    svg
+}
+
+/// This function returns the centres of the symbols on a round card of the given radius.
+/// The first symbol sits in the centre, the rest are spread evenly on a ring.
+pub(crate) fn ring_layout(count: usize, cx: f64, cy: f64, radius: f64) -> Vec<(f64, f64)> {
+    (0..count)
+        .map(|i| match i {
+            0 => (cx, cy),
+            _ => {
+                let angle = 2.0 * std::f64::consts::PI * (i - 1) as f64 / (count - 1) as f64
+                    - std::f64::consts::FRAC_PI_2;
+                (
+                    cx + radius * 0.62 * angle.cos(),
+                    cy + radius * 0.62 * angle.sin(),
+                )
+            }
+        })
+        .collect()
+}
+
+/// This function draws the background of a card, extended by the bleed on every side.
+fn background(width: f64, height: f64, bleed: f64, colour: &str) -> String {
+    format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+        fmt(-bleed),
+        fmt(-bleed),
+        fmt(width + 2.0 * bleed),
+        fmt(height + 2.0 * bleed),
+        colour,
+    )
+}
+
+/// This function escapes the characters which are not allowed in SVG text.
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// This function formats a length in millimetres with at most two decimal places.
+fn fmt(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_string(),
+        _ => text.to_string(),
+    }
+}
+
+/// This enum defines the paper sizes supported by the print sheet generator.
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub enum PaperSize {
+    /// ISO A4, 210 x 297 mm.
+    A4,
+    /// US Letter, 8.5 x 11 inches.
+    Letter,
+}
+
+impl PaperSize {
+    /// This function returns the width and height of the paper in millimetres, in portrait orientation.
+    pub fn dimensions(&self) -> (f64, f64) {
+        match self {
+            PaperSize::A4 => (210.0, 297.0),
+            PaperSize::Letter => (215.9, 279.4),
+        }
+    }
+}
+
+/// This struct defines how a deck is laid out on print-and-play sheets.
+/// All lengths are in millimetres.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrintSheet {
+    /// This is the paper size of every page.
+    pub paper: PaperSize,
+    /// This is the number of cards across a page.
+    pub columns: usize,
+    /// This is the number of cards down a page.
+    pub rows: usize,
+    /// This is the width of a card after it is cut.
+    pub card_width: f64,
+    /// This is the height of a card after it is cut.
+    pub card_height: f64,
+    /// This is the extra artwork printed around every card, so that a slightly off cut leaves no white edge.
+    pub bleed: f64,
+    /// This is true if cut marks are printed in the margin, in line with the card edges.
+    pub cut_marks: bool,
+    /// This is the optional SVG fragment drawn on the back of every card, in a box of `card_width` x `card_height`.
+    /// When it is set, every page of fronts is followed by a page of backs, mirrored for double-sided printing.
+    pub back: Option<String>,
+}
+
+impl Default for PrintSheet {
+    /// This function creates an A4 sheet of 3 x 3 poker sized cards with cut marks and a 2 mm bleed.
+    fn default() -> Self {
+        PrintSheet {
+            paper: PaperSize::A4,
+            columns: 3,
+            rows: 3,
+            card_width: 63.0,
+            card_height: 88.0,
+            bleed: 2.0,
+            cut_marks: true,
+            back: None,
+        }
+    }
+}
+
+/// This is the length of a cut mark, and the gap between a cut mark and the card grid.
+const CUT_MARK_LENGTH: f64 = 3.0;
+const CUT_MARK_GAP: f64 = 1.0;
+
+impl PrintSheet {
+    /// This function returns the number of cards on each page.
+    pub fn cards_per_page(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    /// This function returns the top left corner of the card grid, which is centred on the page.
+    fn origin(&self) -> Result<(f64, f64), String> {
+        let (paper_width, paper_height) = self.paper.dimensions();
+        let grid_width = self.columns as f64 * (self.card_width + 2.0 * self.bleed);
+        let grid_height = self.rows as f64 * (self.card_height + 2.0 * self.bleed);
+        if self.columns == 0 || self.rows == 0 {
+            return Err("A page must have at least one column and one row of cards.".to_string());
+        }
+        if self.card_width <= 0.0 || self.card_height <= 0.0 || self.bleed < 0.0 {
+            return Err("Card size must be positive and bleed must not be negative.".to_string());
+        }
+        if grid_width > paper_width || grid_height > paper_height {
+            return Err(format!(
+                "{} x {} cards of {} x {} mm do not fit on {:?} paper.",
+                self.columns, self.rows, self.card_width, self.card_height, self.paper
+            ));
+        }
+        let margin_x = (paper_width - grid_width) / 2.0;
+        let margin_y = (paper_height - grid_height) / 2.0;
+        if self.cut_marks && margin_x.min(margin_y) < CUT_MARK_LENGTH + CUT_MARK_GAP {
+            return Err("There is not enough margin on the page for cut marks.".to_string());
+        }
+        Ok((margin_x, margin_y))
+    }
+
+    /// This function returns the top left corner of the trimmed card in the given cell.
+    fn card_position(&self, origin: (f64, f64), column: usize, row: usize) -> (f64, f64) {
+        (
+            origin.0 + column as f64 * (self.card_width + 2.0 * self.bleed) + self.bleed,
+            origin.1 + row as f64 * (self.card_height + 2.0 * self.bleed) + self.bleed,
+        )
+    }
+
+    /// This function renders the cards onto pages, and returns every page as a standalone SVG document.
+    pub fn render<C: SvgCard>(&self, cards: &[C]) -> Result<Vec<String>, String> {
+        let origin = self.origin()?;
+        let mut pages = Vec::new();
+        for chunk in cards.chunks(self.cards_per_page()) {
+            let mut body = String::new();
+            for (i, card) in chunk.iter().enumerate() {
+                let (x, y) = self.card_position(origin, i % self.columns, i / self.columns);
+                body.push_str(&self.place(
+                    x,
+                    y,
+                    &card.svg_face(self.card_width, self.card_height, self.bleed),
+                ));
+            }
+            pages.push(self.page(origin, &body));
+
+            if let Some(back) = &self.back {
+                // Mirror the columns so that every back lines up with its front when printed double-sided
+                let mut body = String::new();
+                for i in 0..chunk.len() {
+                    let column = self.columns - 1 - i % self.columns;
+                    let (x, y) = self.card_position(origin, column, i / self.columns);
+                    let face = format!(
+                        "{}{}",
+                        background(self.card_width, self.card_height, self.bleed, "#ffffff"),
+                        back
+                    );
+                    body.push_str(&self.place(x, y, &face));
+                }
+                pages.push(self.page(origin, &body));
+            }
+        }
+        Ok(pages)
+    }
+
+    /// This function moves the elements of a card face to its place on the page.
+    fn place(&self, x: f64, y: f64, face: &str) -> String {
+        format!(
+            "<g transform=\"translate({} {})\">\n{}</g>\n",
+            fmt(x),
+            fmt(y),
+            face
+        )
+    }
+
+    /// This function wraps the cards of a page into a SVG document, adding cut marks when needed.
+    fn page(&self, origin: (f64, f64), body: &str) -> String {
+        let (paper_width, paper_height) = self.paper.dimensions();
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}mm\" height=\"{h}mm\" viewBox=\"0 0 {w} {h}\">\n",
+            w = fmt(paper_width),
+            h = fmt(paper_height),
+        );
+        svg.push_str(body);
+        if self.cut_marks {
+            svg.push_str(&self.cut_marks_svg(origin));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// This function draws the cut marks in the margin around the card grid, in line with every trim edge.
+    fn cut_marks_svg(&self, origin: (f64, f64)) -> String {
+        let (left, top) = origin;
+        let right = left + self.columns as f64 * (self.card_width + 2.0 * self.bleed);
+        let bottom = top + self.rows as f64 * (self.card_height + 2.0 * self.bleed);
+        let mut lines = Vec::new();
+        for column in 0..self.columns {
+            let (x, _) = self.card_position(origin, column, 0);
+            for x in [x, x + self.card_width] {
+                lines.push((
+                    x,
+                    top - CUT_MARK_GAP,
+                    x,
+                    top - CUT_MARK_GAP - CUT_MARK_LENGTH,
+                ));
+                lines.push((
+                    x,
+                    bottom + CUT_MARK_GAP,
+                    x,
+                    bottom + CUT_MARK_GAP + CUT_MARK_LENGTH,
+                ));
+            }
+        }
+        for row in 0..self.rows {
+            let (_, y) = self.card_position(origin, 0, row);
+            for y in [y, y + self.card_height] {
+                lines.push((
+                    left - CUT_MARK_GAP,
+                    y,
+                    left - CUT_MARK_GAP - CUT_MARK_LENGTH,
+                    y,
+                ));
+                lines.push((
+                    right + CUT_MARK_GAP,
+                    y,
+                    right + CUT_MARK_GAP + CUT_MARK_LENGTH,
+                    y,
+                ));
+            }
+        }
+        let mut svg =
+            String::from("<g class=\"cut-marks\" stroke=\"#000000\" stroke-width=\"0.2\">\n");
+        for (x1, y1, x2, y2) in lines {
+            svg.push_str(&format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n",
+                fmt(x1),
+                fmt(y1),
+                fmt(x2),
+                fmt(y2)
+            ));
+        }
+        svg.push_str("</g>\n");
+        svg
+    }
+}
+
+impl SpotItDeck {
+    /// This function renders the whole deck onto print-and-play sheets, one SVG document per page.
+    pub fn print_sheets(&self, sheet: &PrintSheet) -> Result<Vec<String>, String> {
+        sheet.render(&self.cards)
+    }
+}
+
+impl FrenchDeck {
+    /// This function renders the whole deck onto print-and-play sheets, one SVG document per page.
+    pub fn print_sheets(&self, sheet: &PrintSheet) -> Result<Vec<String>, String> {
+        sheet.render(&self.cards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frenchcard::{FrenchRank, FrenchSuit};
+    use crate::spotitcard::SpotItSymbol;
+    use crate::Deck;
+    use std::collections::HashSet;
+
+    #[test]
+    fn can_render_spotitcard_as_svg() {
+        let card = SpotItCard(HashSet::from([SpotItSymbol::Banana, SpotItSymbol::Apple]));
+        let svg = card.to_svg(70.0, 70.0);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("width=\"70mm\""));
+        assert!(svg.contains(">Apple</text>"));
+        assert!(svg.contains(">Banana</text>"));
+        // rendering is stable even though symbols are stored in a set
+        assert_eq!(svg, card.clone().to_svg(70.0, 70.0));
+    }
+    #[test]
+    fn can_render_frenchcard_as_svg() {
+        let svg = FrenchCard(FrenchRank::Ace, FrenchSuit::Heart).to_svg(63.0, 88.0);
+        assert!(svg.contains("A♥"));
+        assert!(svg.contains("#c0392b"));
+        let svg = FrenchCard(FrenchRank::Ten, FrenchSuit::Spade).to_svg(63.0, 88.0);
+        assert!(svg.contains("10♠"));
+        assert!(!svg.contains("#c0392b"));
+    }
+    #[test]
+    fn can_print_default_decks_on_sheets() {
+        let sheet = PrintSheet::default();
+        let pages = FrenchDeck::default().print_sheets(&sheet).unwrap();
+        assert_eq!(pages.len(), 6); // 52 cards, 9 per page
+        assert!(pages.iter().all(|page| page.contains("width=\"210mm\"")));
+        assert!(pages[0].contains("class=\"cut-marks\""));
+
+        let sheet = PrintSheet {
+            paper: PaperSize::Letter,
+            columns: 2,
+            rows: 3,
+            card_width: 80.0,
+            card_height: 80.0,
+            ..PrintSheet::default()
+        };
+        let pages = SpotItDeck::default().print_sheets(&sheet).unwrap();
+        assert_eq!(pages.len(), 10); // 57 cards, 6 per page
+        assert_eq!(pages[9].matches("<g transform").count(), 3);
+    }
+    #[test]
+    fn can_print_backs_after_every_page() {
+        let sheet = PrintSheet {
+            back: Some("<text>SpotIt!</text>".to_string()),
+            cut_marks: false,
+            ..PrintSheet::default()
+        };
+        let pages = SpotItDeck::generate_by_prime(2)
+            .unwrap()
+            .print_sheets(&sheet)
+            .unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[1].matches("SpotIt!").count(), 7);
+        assert!(!pages[0].contains("cut-marks"));
+    }
+    #[test]
+    fn can_mirror_backs_for_double_sided_printing() {
+        let sheet = PrintSheet::default();
+        let origin = sheet.origin().unwrap();
+        let (front_x, _) = sheet.card_position(origin, 0, 0);
+        let (back_x, _) = sheet.card_position(origin, sheet.columns - 1, 0);
+        let (paper_width, _) = sheet.paper.dimensions();
+        assert!((front_x - (paper_width - back_x - sheet.card_width)).abs() < 1e-9);
+    }
+    #[test]
+    fn cannot_print_sheet_which_does_not_fit() {
+        let sheet = PrintSheet {
+            columns: 4,
+            ..PrintSheet::default()
+        };
+        assert!(FrenchDeck::default().print_sheets(&sheet).is_err());
+        let sheet = PrintSheet {
+            rows: 0,
+            ..PrintSheet::default()
+        };
+        assert!(FrenchDeck::default().print_sheets(&sheet).is_err());
+        let mut deck = FrenchDeck::new();
+        deck.push_card(FrenchCard(FrenchRank::Two, FrenchSuit::Club));
+        let sheet = PrintSheet {
+            card_width: 65.0,
+            ..PrintSheet::default()
+        };
+        assert!(deck.print_sheets(&sheet).is_err()); // no margin left for cut marks
+    }
+    #[test]
+    fn can_escape_xml() {
+        assert_eq!(escape_xml("Salt & <Pepper>"), "Salt &amp; &lt;Pepper&gt;");
+    }
+}
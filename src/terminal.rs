@@ -0,0 +1,307 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Support library to render cards of `cardgame` crate in a terminal.
+//!
+//! A SpotIt! card is drawn as a compact framed box of emoji, or of coloured three letter abbreviations for symbols without an emoji.
+//! A French card is drawn as "A♠" or as its Unicode playing card glyph, in red or black.
+//! Everything falls back to plain ASCII when the terminal does not support Unicode or colours.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt!`]: https://www.amazon.com/Asmodee-SP411-Spot-It/dp/B0039S7NO6
+//! [`French Card Game`]: https://en.wikipedia.org/wiki/French_playing_cards
+//! [`ANSI escape code`]: https://en.wikipedia.org/wiki/ANSI_escape_code
+
+use crate::frenchcard::FrenchCard;
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use std::collections::HashSet;
+use std::io::IsTerminal;
+use strum::IntoEnumIterator;
+
+const RESET: &str = "\x1b[0m";
+const RED: &str = "\x1b[31m";
+const BOLD: &str = "\x1b[1m";
+
+/// This is the number of symbols on one row of a SpotIt! card box.
+const SYMBOLS_PER_ROW: usize = 4;
+
+/// This struct defines what the terminal supports.
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub struct TermStyle {
+    /// This is true if the terminal can show Unicode box drawing characters and emoji.
+    pub unicode: bool,
+    /// This is true if the terminal understands ANSI colours.
+    pub colour: bool,
+    /// This is true if French cards are shown as Unicode playing card glyphs, e.g. 🂡 rather than A♠.
+    pub card_glyphs: bool,
+}
+
+impl TermStyle {
+    /// This function creates a style for terminals without Unicode or colours.
+    pub fn plain() -> Self {
+        TermStyle {
+            unicode: false,
+            colour: false,
+            card_glyphs: false,
+        }
+    }
+
+    /// This function creates a style for modern terminals with Unicode and colours.
+    pub fn fancy() -> Self {
+        TermStyle {
+            unicode: true,
+            colour: true,
+            card_glyphs: false,
+        }
+    }
+
+    /// This function detects what the standard output supports from the environment.
+    pub fn detect() -> Self {
+        Self::from_env(
+            |name| std::env::var(name).ok(),
+            std::io::stdout().is_terminal(),
+        )
+    }
+
+    /// This function works out the style from environment variables.
+    /// Colours follow `NO_COLOR` and need a terminal, Unicode needs a UTF-8 locale, and neither works on a `dumb` terminal.
+    pub fn from_env<F: Fn(&str) -> Option<String>>(var: F, is_terminal: bool) -> Self {
+        let dumb = var("TERM").is_some_and(|term| term == "dumb");
+        let utf8 = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|name| var(name))
+            .find(|value| !value.is_empty())
+            .is_some_and(|locale| {
+                let locale = locale.to_lowercase();
+                locale.contains("utf-8") || locale.contains("utf8")
+            });
+        TermStyle {
+            unicode: utf8 && !dumb,
+            colour: is_terminal && !dumb && var("NO_COLOR").is_none_or(|v| v.is_empty()),
+            card_glyphs: false,
+        }
+    }
+
+    fn paint(&self, text: &str, code: &str) -> String {
+        match self.colour {
+            true => format!("{}{}{}", code, text, RESET),
+            false => text.to_string(),
+        }
+    }
+}
+
+/// This trait defines a card which can be drawn in a terminal.
+pub trait TermCard {
+    /// This function returns the lines of text which draw the card.
+    fn render(&self, style: &TermStyle) -> String;
+}
+
+impl TermCard for SpotItCard {
+    /// This function draws the card as a framed box, with four symbols on a row.
+    fn render(&self, style: &TermStyle) -> String {
+        let cells: Vec<String> = self
+            .sorted_symbols()
+            .iter()
+            .map(|symbol| symbol_cell(*symbol, style))
+            .collect();
+        let (top, bottom, side) = match style.unicode {
+            true => (("╭", "─", "╮"), ("╰", "─", "╯"), "│"),
+            false => (("+", "-", "+"), ("+", "-", "+"), "|"),
+        };
+        let width = SYMBOLS_PER_ROW * 4 + 1;
+        let mut lines = vec![format!("{}{}{}", top.0, top.1.repeat(width), top.2)];
+        for row in cells.chunks(SYMBOLS_PER_ROW) {
+            let mut line = format!("{} ", side);
+            for cell in row {
+                line.push_str(cell);
+            }
+            line.push_str(&"    ".repeat(SYMBOLS_PER_ROW - row.len()));
+            line.push_str(side);
+            lines.push(line);
+        }
+        lines.push(format!(
+            "{}{}{}",
+            bottom.0,
+            bottom.1.repeat(width),
+            bottom.2
+        ));
+        lines.join("\n")
+    }
+}
+
+impl TermCard for FrenchCard {
+    /// This function draws the card as its rank and suit, in red for diamonds and hearts.
+    fn render(&self, style: &TermStyle) -> String {
+        let text = match (style.unicode, style.card_glyphs) {
+            (true, true) => self.glyph().to_string(),
+            (true, false) => self.to_string(),
+            (false, _) => format!("{}{}", self.rank(), ascii_suit(self)),
+        };
+        match self.suit().is_red() {
+            true => style.paint(&text, RED),
+            false => style.paint(&text, BOLD),
+        }
+    }
+}
+
+/// This function returns the ASCII letter of the suit, e.g. "S" for a spade.
+fn ascii_suit(card: &FrenchCard) -> char {
+    format!("{:?}", card.suit()).chars().next().unwrap_or('?')
+}
+
+/// This function returns a cell 4 columns wide which shows the symbol.
+fn symbol_cell(symbol: SpotItSymbol, style: &TermStyle) -> String {
+    match (style.unicode, emoji(symbol)) {
+        // Emoji are 2 columns wide
+        (true, Some(emoji)) => format!("{}  ", emoji),
+        _ => {
+            let code = format!("\x1b[38;5;{}m", symbol_colour(symbol));
+            format!("{} ", style.paint(&abbreviation(symbol), &code))
+        }
+    }
+}
+
+/// This function returns the emoji of the symbol, if Unicode has one.
+pub fn emoji(symbol: SpotItSymbol) -> Option<&'static str> {
+    match symbol {
+        SpotItSymbol::Apple => Some("🍎"),
+        SpotItSymbol::Avocado => Some("🥑"),
+        SpotItSymbol::Banana => Some("🍌"),
+        SpotItSymbol::Blueberry => Some("🫐"),
+        SpotItSymbol::Cherry => Some("🍒"),
+        SpotItSymbol::Coconut => Some("🥥"),
+        SpotItSymbol::Cucumber => Some("🥒"),
+        SpotItSymbol::Grape => Some("🍇"),
+        SpotItSymbol::Kiwifruit => Some("🥝"),
+        SpotItSymbol::Lemon => Some("🍋"),
+        SpotItSymbol::Mango => Some("🥭"),
+        SpotItSymbol::Melon => Some("🍈"),
+        SpotItSymbol::Olive => Some("🫒"),
+        SpotItSymbol::Orange => Some("🍊"),
+        SpotItSymbol::Peach => Some("🍑"),
+        SpotItSymbol::Pear => Some("🍐"),
+        SpotItSymbol::Pineapple => Some("🍍"),
+        SpotItSymbol::Strawberry => Some("🍓"),
+        SpotItSymbol::Watermelon => Some("🍉"),
+        _ => None,
+    }
+}
+
+/// This function returns a three letter abbreviation of the symbol, which is unique among all symbols.
+/// It is the first three letters of the name, or the first letter and two later letters when those are taken.
+pub fn abbreviation(symbol: SpotItSymbol) -> String {
+    let mut taken = HashSet::new();
+    for s in SpotItSymbol::iter() {
+        let name: Vec<char> = format!("{:?}", s).chars().collect();
+        let candidate = (1..name.len())
+            .flat_map(|i| (i + 1..name.len()).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                format!(
+                    "{}{}{}",
+                    name[0].to_ascii_uppercase(),
+                    name[i].to_ascii_lowercase(),
+                    name[j].to_ascii_lowercase()
+                )
+            })
+            .find(|candidate| !taken.contains(candidate))
+            .unwrap_or_else(|| format!("{:?}", s));
+        if s == symbol {
+            return candidate;
+        }
+        taken.insert(candidate);
+    }
+    unreachable!()
+}
+
+/// This function returns an ANSI 256 colour for the symbol, skipping dark colours which are hard to read.
+pub fn symbol_colour(symbol: SpotItSymbol) -> usize {
+    // Colours 16 to 231 are a 6 x 6 x 6 cube, keep the brighter two thirds of it
+    let bright: Vec<usize> = (16..232)
+        .filter(|c| {
+            let (r, g, b) = ((c - 16) / 36, (c - 16) / 6 % 6, (c - 16) % 6);
+            r + g + b >= 6
+        })
+        .collect();
+    bright[(symbol as usize * 37) % bright.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frenchcard::{FrenchRank, FrenchSuit};
+    use crate::{Deck, SpotItDeck};
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| v.to_string())
+        }
+    }
+
+    #[test]
+    fn can_render_spotitcard_in_plain_ascii() {
+        let card = SpotItCard(HashSet::from([
+            SpotItSymbol::Apple,
+            SpotItSymbol::Banana,
+            SpotItSymbol::Yuzu,
+        ]));
+        let text = card.render(&TermStyle::plain());
+        assert_eq!(
+            text,
+            "+-----------------+\n| App Ban Yuz     |\n+-----------------+"
+        );
+        assert!(text.is_ascii());
+    }
+    #[test]
+    fn can_render_spotitcard_with_emoji_and_colours() {
+        let card = SpotItCard(HashSet::from([SpotItSymbol::Apple, SpotItSymbol::Yuzu]));
+        let text = card.render(&TermStyle::fancy());
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with('╭'));
+        assert!(lines[1].starts_with("│ 🍎  \x1b[38;5;"));
+        assert!(lines[1].contains("Yuz\x1b[0m"));
+    }
+    #[test]
+    fn can_render_default_deck_in_boxes_of_same_width() {
+        let deck = SpotItDeck::default();
+        for card in deck.cards.iter() {
+            let text = card.render(&TermStyle::plain());
+            assert_eq!(text.lines().count(), 4); // 8 symbols on 2 rows
+            assert!(text.lines().all(|line| line.len() == 19));
+        }
+    }
+    #[test]
+    fn abbreviations_are_unique() {
+        let abbreviations: HashSet<String> = SpotItSymbol::iter().map(abbreviation).collect();
+        assert_eq!(abbreviations.len(), SpotItSymbol::iter().count());
+        assert_eq!(abbreviation(SpotItSymbol::Blackberry), "Bla");
+        assert_eq!(abbreviation(SpotItSymbol::Blackcurrant), "Blc");
+    }
+    #[test]
+    fn can_render_frenchcard() {
+        let ace = FrenchCard(FrenchRank::Ace, FrenchSuit::Heart);
+        assert_eq!(ace.render(&TermStyle::plain()), "AH");
+        assert_eq!(ace.render(&TermStyle::fancy()), "\x1b[31mA♥\x1b[0m");
+        let style = TermStyle {
+            card_glyphs: true,
+            ..TermStyle::fancy()
+        };
+        assert_eq!(
+            FrenchCard(FrenchRank::Ace, FrenchSuit::Spade).render(&style),
+            "\x1b[1m🂡\x1b[0m"
+        );
+    }
+    #[test]
+    fn can_detect_terminal_support() {
+        let style = TermStyle::from_env(env(&[("LANG", "en_GB.UTF-8")]), true);
+        assert_eq!(style, TermStyle::fancy());
+        let style = TermStyle::from_env(env(&[("LANG", "en_GB.UTF-8"), ("NO_COLOR", "1")]), true);
+        assert!(style.unicode && !style.colour);
+        let style = TermStyle::from_env(env(&[("LANG", "C"), ("LC_ALL", "")]), false);
+        assert_eq!(style, TermStyle::plain());
+        let style = TermStyle::from_env(env(&[("LANG", "en_US.utf8"), ("TERM", "dumb")]), true);
+        assert_eq!(style, TermStyle::plain());
+    }
+}
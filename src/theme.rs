@@ -0,0 +1,456 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Support library to re-skin SpotIt! decks in `cardgame` crate.
+//!
+//! A theme gives every `SpotItSymbol` a label by its position in the enum, so the same deck can be shown as fruits, animals, emoji, letters or colours without regenerating its structure.
+//! Themes can also be loaded from a text file with one label per line.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`SpotIt!`]: https://www.amazon.com/Asmodee-SP411-Spot-It/dp/B0039S7NO6
+//! [`SpotIt! Rules`]: https://www.ultraboardgames.com/spot-it/game-rules.php
+
+use crate::difficulty::Layout;
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use crate::svg::{spotit_face, SvgCard};
+use crate::SpotItDeck;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use strum::IntoEnumIterator;
+
+const ANIMALS: &[&str] = &[
+    "Ant",
+    "Bat",
+    "Bear",
+    "Bee",
+    "Beetle",
+    "Bison",
+    "Butterfly",
+    "Camel",
+    "Cat",
+    "Chicken",
+    "Cow",
+    "Crab",
+    "Crocodile",
+    "Deer",
+    "Dog",
+    "Dolphin",
+    "Donkey",
+    "Duck",
+    "Eagle",
+    "Elephant",
+    "Flamingo",
+    "Fox",
+    "Frog",
+    "Giraffe",
+    "Goat",
+    "Gorilla",
+    "Hedgehog",
+    "Hippo",
+    "Horse",
+    "Kangaroo",
+    "Koala",
+    "Ladybird",
+    "Leopard",
+    "Lion",
+    "Lizard",
+    "Llama",
+    "Lobster",
+    "Monkey",
+    "Mouse",
+    "Octopus",
+    "Otter",
+    "Owl",
+    "Panda",
+    "Parrot",
+    "Peacock",
+    "Penguin",
+    "Pig",
+    "Rabbit",
+    "Raccoon",
+    "Rhino",
+    "Seal",
+    "Shark",
+    "Sheep",
+    "Snail",
+    "Snake",
+    "Spider",
+    "Squirrel",
+    "Swan",
+    "Tiger",
+    "Turtle",
+    "Whale",
+    "Wolf",
+    "Zebra",
+];
+
+const EMOJI: &[&str] = &[
+    "🍎", "🍌", "🍇", "🍉", "🍋", "🍒", "🍓", "🥝", "🍍", "🥥", "🥕", "🌽", "🍄", "🌵", "🌻", "🌹",
+    "🍀", "🌈", "☀️", "🌙", "⭐", "⚡", "❄️", "🔥", "💧", "🎈", "🎁", "🎵", "🔔", "🔑", "🔒", "💡",
+    "✏️", "✂️", "📌", "📎", "⏰", "⚓", "🚲", "🚗", "🚀", "⛵", "🏠", "⛄", "⚽", "🏀", "🎲", "🧩",
+    "🐶", "🐱", "🐭", "🐰", "🦊", "🐻", "🐼", "🐸", "🐙", "🐢", "🦋", "🐝", "🐞", "🐬", "👻", "💀",
+    "👀", "❤️", "💎", "🎩", "👓", "🧦",
+];
+
+const LETTERS_AND_DIGITS: &[&str] = &[
+    "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S",
+    "T", "U", "V", "W", "X", "Y", "Z", "a", "b", "d", "e", "f", "g", "h", "i", "j", "k", "m", "n",
+    "q", "r", "t", "y", "0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "&", "@", "#", "?", "!",
+    "%", "+", "=",
+];
+
+const COLOURS: &[&str] = &[
+    "Red",
+    "Orange",
+    "Yellow",
+    "Green",
+    "Blue",
+    "Purple",
+    "Pink",
+    "Brown",
+    "Black",
+    "White",
+    "Grey",
+    "Gold",
+    "Silver",
+    "Navy",
+    "Teal",
+    "Olive",
+    "Maroon",
+    "Lime",
+    "Aqua",
+    "Fuchsia",
+    "Coral",
+    "Salmon",
+    "Crimson",
+    "Indigo",
+    "Violet",
+    "Lavender",
+    "Beige",
+    "Ivory",
+    "Khaki",
+    "Tan",
+    "Chocolate",
+    "Turquoise",
+    "Cyan",
+    "Magenta",
+    "Plum",
+    "Orchid",
+    "Peach",
+    "Mint",
+    "Cream",
+    "Amber",
+    "Ruby",
+    "Emerald",
+    "Sapphire",
+    "Jade",
+    "Scarlet",
+    "Cherry",
+    "Lemon",
+    "Mustard",
+    "Ochre",
+    "Rust",
+    "Sand",
+    "Slate",
+    "Charcoal",
+    "Cobalt",
+    "Azure",
+    "Cerulean",
+    "Mauve",
+    "Lilac",
+    "Burgundy",
+    "Apricot",
+];
+
+/// This struct defines a theme, which is the labels shown for the symbols of a SpotIt! deck.
+/// The n-th label is shown for the n-th `SpotItSymbol`.
+#[derive(Debug, PartialEq, Clone, Eq)]
+pub struct Theme {
+    /// This is the name of the theme, used to choose it from a registry.
+    pub name: String,
+    labels: Vec<String>,
+}
+
+impl Theme {
+    /// This function creates a theme from its labels.
+    /// It returns an error if there is no label, a label is blank, or two symbols would share a label.
+    pub fn new(name: &str, labels: Vec<String>) -> Result<Self, String> {
+        if name.trim().is_empty() {
+            return Err("Theme must have a name.".to_string());
+        }
+        if labels.is_empty() {
+            return Err(format!("Theme {} has no label.", name));
+        }
+        let mut uniq = HashSet::new();
+        for label in labels.iter() {
+            if label.trim().is_empty() {
+                return Err(format!("Theme {} has a blank label.", name));
+            }
+            if !uniq.insert(label) {
+                return Err(format!(
+                    "Theme {} has label {} more than once.",
+                    name, label
+                ));
+            }
+        }
+        Ok(Theme {
+            name: name.to_string(),
+            labels,
+        })
+    }
+
+    /// This function parses a theme from text with one label per line.
+    /// Blank lines and lines starting with `#` are ignored.
+    pub fn parse(name: &str, text: &str) -> Result<Self, String> {
+        let labels = text
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.to_string())
+            .collect();
+        Self::new(name, labels)
+    }
+
+    /// This function loads a theme from a text file with one label per line, named after the file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| format!("{} is not a valid theme file name.", path.display()))?;
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::parse(name, &text)
+    }
+
+    /// This function creates the default theme, which is the names of the fruits in `SpotItSymbol`.
+    pub fn fruits() -> Self {
+        let labels = SpotItSymbol::iter()
+            .map(|symbol| symbol.to_string())
+            .collect();
+        Self::new("fruits", labels).unwrap()
+    }
+    /// This function creates a theme of animal names.
+    pub fn animals() -> Self {
+        Self::builtin("animals", ANIMALS)
+    }
+    /// This function creates a theme of emoji.
+    pub fn emoji() -> Self {
+        Self::builtin("emoji", EMOJI)
+    }
+    /// This function creates a theme of letters, digits and a few signs.
+    pub fn letters() -> Self {
+        Self::builtin("letters", LETTERS_AND_DIGITS)
+    }
+    /// This function creates a theme of colour names.
+    pub fn colours() -> Self {
+        Self::builtin("colours", COLOURS)
+    }
+    fn builtin(name: &str, labels: &[&str]) -> Self {
+        Self::new(name, labels.iter().map(|label| label.to_string()).collect()).unwrap()
+    }
+
+    /// This function returns the number of symbols the theme has a label for.
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+    /// This function returns false if the theme has any label.
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+    /// This function returns the label of the symbol, or None if the theme is too small.
+    pub fn label(&self, symbol: SpotItSymbol) -> Option<&str> {
+        self.labels.get(symbol as usize).map(|label| label.as_str())
+    }
+    /// This function returns the symbol shown with the label, if any.
+    pub fn symbol(&self, label: &str) -> Option<SpotItSymbol> {
+        let index = self.labels.iter().position(|l| l == label)?;
+        SpotItSymbol::iter().nth(index)
+    }
+    /// This function returns the labels of the card, in the same order as `SpotItCard::sorted_symbols`.
+    pub fn card_labels(&self, card: &SpotItCard) -> Result<Vec<String>, String> {
+        card.sorted_symbols()
+            .iter()
+            .map(|symbol| {
+                self.label(*symbol)
+                    .map(|label| label.to_string())
+                    .ok_or_else(|| format!("Theme {} has no label for {:?}.", self.name, symbol))
+            })
+            .collect()
+    }
+}
+
+/// This struct defines a SpotIt! card shown with the labels of a theme.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ThemedCard<'a> {
+    /// This is the card, whose symbols decide which cards match.
+    pub card: &'a SpotItCard,
+    /// This is the labels of the symbols on the card.
+    pub labels: Vec<String>,
+}
+
+impl SvgCard for ThemedCard<'_> {
+    fn svg_face(&self, width: f64, height: f64, bleed: f64) -> String {
+        let placements = Layout::default().placements(self.card);
+        spotit_face(&self.labels, &placements, width, height, bleed)
+    }
+}
+
+impl SpotItDeck {
+    /// This function re-skins the deck with a theme, keeping every card and symbol as it is.
+    /// It returns an error if the theme has no label for a symbol used by the deck.
+    pub fn reskin<'a>(&'a self, theme: &Theme) -> Result<Vec<ThemedCard<'a>>, String> {
+        self.cards
+            .iter()
+            .map(|card| {
+                Ok(ThemedCard {
+                    card,
+                    labels: theme.card_labels(card)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// This struct defines a registry of themes, to list and choose themes by name.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ThemeRegistry {
+    themes: Vec<Theme>,
+}
+
+impl Default for ThemeRegistry {
+    /// This function creates a registry with the built-in themes.
+    fn default() -> Self {
+        ThemeRegistry {
+            themes: vec![
+                Theme::fruits(),
+                Theme::animals(),
+                Theme::emoji(),
+                Theme::letters(),
+                Theme::colours(),
+            ],
+        }
+    }
+}
+
+impl ThemeRegistry {
+    /// This function creates an empty registry.
+    pub fn new() -> Self {
+        ThemeRegistry { themes: Vec::new() }
+    }
+    /// This function adds a theme to the registry, replacing any theme of the same name.
+    pub fn register(&mut self, theme: Theme) {
+        match self.themes.iter_mut().find(|t| t.name == theme.name) {
+            Some(existing) => *existing = theme,
+            None => self.themes.push(theme),
+        }
+    }
+    /// This function loads a theme from a file and adds it to the registry, returning its name.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<String, String> {
+        let theme = Theme::from_file(path)?;
+        let name = theme.name.clone();
+        self.register(theme);
+        Ok(name)
+    }
+    /// This function returns the theme of the name.
+    pub fn get(&self, name: &str) -> Option<&Theme> {
+        self.themes.iter().find(|theme| theme.name == name)
+    }
+    /// This function returns the names of the themes, in the order they were registered.
+    pub fn names(&self) -> Vec<&str> {
+        self.themes
+            .iter()
+            .map(|theme| theme.name.as_str())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svg::PrintSheet;
+    use crate::Deck;
+
+    #[test]
+    fn builtin_themes_can_skin_default_deck() {
+        let deck = SpotItDeck::default();
+        let registry = ThemeRegistry::default();
+        assert_eq!(
+            registry.names(),
+            vec!["fruits", "animals", "emoji", "letters", "colours"]
+        );
+        for name in registry.names() {
+            let theme = registry.get(name).unwrap();
+            let cards = deck.reskin(theme).unwrap();
+            assert_eq!(cards.len(), 57);
+            assert!(cards.iter().all(|c| c.labels.len() == 8));
+        }
+    }
+    #[test]
+    fn can_label_symbols_by_position() {
+        let fruits = Theme::fruits();
+        assert_eq!(fruits.len(), SpotItSymbol::iter().count());
+        assert_eq!(fruits.label(SpotItSymbol::Apple), Some("Apple"));
+        assert_eq!(fruits.label(SpotItSymbol::ChicoFruit), Some("Chico Fruit"));
+        assert_eq!(fruits.symbol("Chico Fruit"), Some(SpotItSymbol::ChicoFruit));
+
+        let animals = Theme::animals();
+        assert_eq!(animals.label(SpotItSymbol::Apple), Some("Ant"));
+        assert_eq!(animals.label(SpotItSymbol::Yuzu), None);
+        assert_eq!(animals.symbol("Bat"), Some(SpotItSymbol::Apricot));
+    }
+    #[test]
+    fn reskin_keeps_structure_of_deck() {
+        let deck = SpotItDeck::generate_by_prime(3).unwrap();
+        let cards = deck.reskin(&Theme::letters()).unwrap();
+        assert_eq!(cards[0].card, &deck.cards[0]);
+        // first card is [Apple, Apricot, Avocado, Currant]
+        assert_eq!(cards[0].labels, vec!["A", "B", "C", "J"]);
+    }
+    #[test]
+    fn cannot_reskin_deck_with_small_theme() {
+        let theme = Theme::parse("tiny", "one\ntwo\nthree").unwrap();
+        let deck = SpotItDeck::generate_by_prime(1).unwrap();
+        assert!(deck.reskin(&theme).is_ok());
+        let deck = SpotItDeck::generate_by_prime(2).unwrap();
+        assert!(deck.reskin(&theme).is_err());
+    }
+    #[test]
+    fn can_parse_user_theme() {
+        let theme = Theme::parse("shapes", "# my shapes\nCircle\n\n  Square \nStar\n").unwrap();
+        assert_eq!(theme.len(), 3);
+        assert_eq!(theme.label(SpotItSymbol::Apricot), Some("Square"));
+        assert!(Theme::parse("empty", "# nothing\n").is_err());
+        assert!(Theme::parse("twice", "Circle\nCircle\n").is_err());
+        assert!(Theme::parse(" ", "Circle\n").is_err());
+    }
+    #[test]
+    fn can_load_user_theme_from_file() {
+        let path = std::env::temp_dir().join(format!("cardgame-theme-{}.txt", std::process::id()));
+        fs::write(&path, "Sun\nMoon\nStar\n").unwrap();
+        let mut registry = ThemeRegistry::new();
+        let name = registry.load_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(registry.names(), vec![name.as_str()]);
+        assert_eq!(
+            registry.get(&name).unwrap().label(SpotItSymbol::Avocado),
+            Some("Star")
+        );
+        assert!(registry.load_file(&path).is_err());
+    }
+    #[test]
+    fn can_replace_theme_by_name() {
+        let mut registry = ThemeRegistry::default();
+        registry.register(Theme::parse("animals", "Cat\nDog\n").unwrap());
+        assert_eq!(registry.names().len(), 5);
+        assert_eq!(registry.get("animals").unwrap().len(), 2);
+    }
+    #[test]
+    fn can_print_reskinned_deck() {
+        let deck = SpotItDeck::generate_by_prime(2).unwrap();
+        let cards = deck.reskin(&Theme::emoji()).unwrap();
+        let pages = PrintSheet::default().render(&cards).unwrap();
+        assert_eq!(pages.len(), 1);
+        assert!(pages[0].contains(">🍎</text>"));
+    }
+}
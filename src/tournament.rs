@@ -0,0 +1,920 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Tournaments and leagues of SpotIt! in `cardgame` crate.
+//!
+//! A `Tournament` pairs players off in matches of several games of one mode, and ranks them in standings.
+//! It is run as a round robin, where everyone meets everyone once, or as a single-elimination bracket, where the winner of every match goes through to the next round until one player is left.
+//!
+//! Players are seeded in the order they are entered, the best first. Seeds place the players in the bracket, so that the best two can only meet in the final, and settle ties no other tie-break can.
+//! A match is won by the player who wins more of its games, then by the higher total score over its games, then, in a bracket, by the better seed. In a round robin, a match still level is drawn.
+//!
+//! Results are either recorded game by game, e.g. from a game at the office, or played out by bots with `Tournament::play_game`.
+//! The tournament is kept in one local JSON file with a version, like a `save::SavedGame`.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+
+use crate::game::bot::{Bot, Skill};
+use crate::game::registry::{ModeOptions, Registry};
+use crate::game::{play_game, Spotter, MAX_TURNS};
+use crate::save::{read_versioned, write_atomically};
+use crate::{Deck, SpotItDeck};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// This is the version of the tournament format written by this crate. Tournaments of later versions cannot be read.
+pub const TOURNAMENT_VERSION: u64 = 1;
+
+/// This is the points a match win is worth in a round robin. A draw is worth one point and a loss none.
+pub const WIN_POINTS: u32 = 3;
+
+/// This enum defines how players are paired off.
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Serialize, Deserialize)]
+pub enum Format {
+    /// This is a league where everyone meets everyone once.
+    RoundRobin,
+    /// This is a bracket where the loser of every match is out.
+    SingleElimination,
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Format::RoundRobin => "round-robin",
+            Format::SingleElimination => "single-elimination",
+        };
+        f.pad(name)
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace(['_', ' '], "-").as_str() {
+            "round-robin" | "league" => Ok(Format::RoundRobin),
+            "single-elimination" | "knockout" | "bracket" => Ok(Format::SingleElimination),
+            _ => Err(format!("Unknown tournament format: {}.", s)),
+        }
+    }
+}
+
+/// This struct defines the result of one game of a match.
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Serialize, Deserialize)]
+pub struct GameResult {
+    /// This is the score of the two players of the match, in the order they are listed in the match.
+    pub scores: [i32; 2],
+    /// This is the winner, 0 or 1, or None for a drawn game.
+    pub winner: Option<usize>,
+}
+
+impl GameResult {
+    /// This function creates the result of a game won by the higher score, or drawn on equal scores.
+    pub fn from_scores(scores: [i32; 2]) -> Self {
+        let winner = match scores[0].cmp(&scores[1]) {
+            Ordering::Greater => Some(0),
+            Ordering::Less => Some(1),
+            Ordering::Equal => None,
+        };
+        GameResult { scores, winner }
+    }
+}
+
+/// This struct defines a match between two players, or a bye when there is no opponent.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Match {
+    /// This is the round of the match, from 1.
+    pub round: usize,
+    /// This is the players, the better seed first, or a single player with a bye.
+    pub players: Vec<String>,
+    /// This is the games played so far.
+    pub games: Vec<GameResult>,
+}
+
+impl Match {
+    /// This function returns true if the match is a bye, which its only player wins without playing.
+    pub fn is_bye(&self) -> bool {
+        self.players.len() < 2
+    }
+
+    /// This function returns the number of games won by each player.
+    pub fn game_wins(&self) -> [usize; 2] {
+        let mut wins = [0; 2];
+        for winner in self.games.iter().filter_map(|g| g.winner) {
+            wins[winner] += 1;
+        }
+        wins
+    }
+
+    /// This function returns the total score of each player over the games played.
+    pub fn totals(&self) -> [i32; 2] {
+        self.games.iter().fold([0; 2], |[a, b], game| {
+            [a + game.scores[0], b + game.scores[1]]
+        })
+    }
+}
+
+/// This enum defines the outcome of a match.
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub enum Outcome {
+    /// This is a match won by the player, 0 or 1.
+    Won(usize),
+    /// This is a drawn match, only possible in a round robin.
+    Drawn,
+}
+
+/// This struct defines where a player stands in a tournament.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Standing {
+    /// This is the name of the player.
+    pub name: String,
+    /// This is the seed of the player, from 1 for the best.
+    pub seed: usize,
+    /// This is the number of matches finished, and byes of a bracket, which take the player through.
+    pub played: usize,
+    /// This is the number of matches won, and byes of a bracket.
+    pub won: usize,
+    /// This is the number of matches drawn.
+    pub drawn: usize,
+    /// This is the number of matches lost.
+    pub lost: usize,
+    /// This is the number of games won.
+    pub games_won: usize,
+    /// This is the number of games lost.
+    pub games_lost: usize,
+    /// This is the total score of the player over every game.
+    pub score_for: i32,
+    /// This is the total score of the opponents of the player over every game.
+    pub score_against: i32,
+    /// This is the last round the player reached, which ranks players of a bracket.
+    pub round: usize,
+    /// This is true if the player is still in the bracket. Players of a round robin are never out.
+    pub alive: bool,
+}
+
+impl Standing {
+    /// This function returns the league points of the player: `WIN_POINTS` a match won and one a match drawn.
+    pub fn points(&self) -> u32 {
+        self.won as u32 * WIN_POINTS + self.drawn as u32
+    }
+
+    /// This function returns the games won less the games lost.
+    pub fn game_difference(&self) -> i64 {
+        self.games_won as i64 - self.games_lost as i64
+    }
+
+    /// This function returns the score of the player less the score of their opponents.
+    pub fn score_difference(&self) -> i32 {
+        self.score_for - self.score_against
+    }
+}
+
+/// This struct defines the standings of a tournament, the first place first.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Standings {
+    /// This is how the tournament is run, which sets the columns shown.
+    pub format: Format,
+    /// This is where every player stands, the first place first.
+    pub rows: Vec<Standing>,
+}
+
+impl fmt::Display for Standings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self
+            .rows
+            .iter()
+            .map(|r| r.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(6);
+        let last = match self.format {
+            Format::RoundRobin => "Pts",
+            Format::SingleElimination => "Reached",
+        };
+        write!(
+            f,
+            "{:>3}  {:<width$}  {:>2}  {:>2}  {:>2}  {:>2}  {:>5}  {:>9}  {}",
+            "#",
+            "Player",
+            "P",
+            "W",
+            "D",
+            "L",
+            "Games",
+            "Score",
+            last,
+            width = width
+        )?;
+        for (i, row) in self.rows.iter().enumerate() {
+            let last = match self.format {
+                Format::RoundRobin => row.points().to_string(),
+                Format::SingleElimination if row.alive => format!("round {}, in", row.round),
+                Format::SingleElimination => format!("round {}", row.round),
+            };
+            write!(
+                f,
+                "\n{:>3}  {:<width$}  {:>2}  {:>2}  {:>2}  {:>2}  {:>5}  {:>9}  {}",
+                i + 1,
+                row.name,
+                row.played,
+                row.won,
+                row.drawn,
+                row.lost,
+                format!("{}-{}", row.games_won, row.games_lost),
+                format!("{}-{}", row.score_for, row.score_against),
+                last,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// This struct defines a tournament: its players, how they are paired off, and the matches played.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Tournament {
+    /// This is the version of the format. See `TOURNAMENT_VERSION`.
+    pub version: u64,
+    /// This is the name of the tournament, e.g. "Office league".
+    pub name: String,
+    /// This is how players are paired off.
+    pub format: Format,
+    /// This is the name of the game mode. See `Registry`.
+    pub mode: String,
+    /// This is the number of games of every match.
+    pub games_per_match: usize,
+    /// This is the players, in seed order, the best first.
+    pub players: Vec<String>,
+    /// This is the matches, round by round.
+    pub matches: Vec<Match>,
+}
+
+impl Tournament {
+    /// This function enters the players, the best seed first, and pairs them off for the first round, or every round of a round robin.
+    /// It returns an error if there are fewer than two players, a player with no name, two players of the same name, or no game a match.
+    pub fn new(
+        name: &str,
+        format: Format,
+        mode: &str,
+        players: &[&str],
+        games_per_match: usize,
+    ) -> Result<Self, String> {
+        if players.len() < 2 {
+            return Err("A tournament needs at least two players.".to_string());
+        }
+        if players.iter().any(|p| p.trim().is_empty()) {
+            return Err("Every player needs a name.".to_string());
+        }
+        if let Some(twice) = players
+            .iter()
+            .enumerate()
+            .find(|(i, p)| players[..*i].contains(p))
+        {
+            return Err(format!("{} is entered twice.", twice.1));
+        }
+        if games_per_match == 0 {
+            return Err("A match needs at least one game.".to_string());
+        }
+        let mut tournament = Tournament {
+            version: TOURNAMENT_VERSION,
+            name: name.to_string(),
+            format,
+            mode: mode.to_string(),
+            games_per_match,
+            players: players.iter().map(|p| p.to_string()).collect(),
+            matches: Vec::new(),
+        };
+        tournament.matches = match format {
+            Format::RoundRobin => tournament.round_robin(),
+            Format::SingleElimination => tournament.bracket(),
+        };
+        Ok(tournament)
+    }
+
+    /// This function pairs everyone with everyone by the circle method, so that every player plays once a round.
+    /// With an odd number of players, one of them has a bye every round.
+    fn round_robin(&self) -> Vec<Match> {
+        let mut seats: Vec<Option<&String>> = self.players.iter().map(Some).collect();
+        if seats.len() % 2 == 1 {
+            seats.push(None);
+        }
+        let n = seats.len();
+        let mut matches = Vec::new();
+        for round in 1..n {
+            for i in 0..n / 2 {
+                let pair = [seats[i], seats[n - 1 - i]];
+                let mut players: Vec<String> =
+                    pair.iter().flatten().map(|p| p.to_string()).collect();
+                players.sort_by_key(|p| self.seed(p));
+                matches.push(Match {
+                    round,
+                    players,
+                    games: Vec::new(),
+                });
+            }
+            // The first seat stays, and the others turn round it
+            seats[1..].rotate_right(1);
+        }
+        matches
+    }
+
+    /// This function pairs the players of the first round of a bracket, the best seed against the worst, with byes for the best seeds when the players do not fill the bracket.
+    fn bracket(&self) -> Vec<Match> {
+        let size = self.players.len().next_power_of_two();
+        // Seeds by position, e.g. 1, 8, 4, 5, 2, 7, 3, 6, so that the best seeds meet last
+        let mut order = vec![1];
+        while order.len() < size {
+            let count = order.len() * 2;
+            order = order.iter().flat_map(|s| [*s, count + 1 - s]).collect();
+        }
+        order
+            .chunks(2)
+            .map(|pair| Match {
+                round: 1,
+                players: pair
+                    .iter()
+                    .filter_map(|seed| self.players.get(seed - 1).cloned())
+                    .collect(),
+                games: Vec::new(),
+            })
+            .collect()
+    }
+
+    /// This function returns the seed of the player, from 1 for the best, or None if they are not entered.
+    pub fn seed(&self, player: &str) -> Option<usize> {
+        self.players.iter().position(|p| p == player).map(|i| i + 1)
+    }
+
+    /// This function returns the number of the last round paired so far.
+    pub fn round(&self) -> usize {
+        self.matches.iter().map(|m| m.round).max().unwrap_or(0)
+    }
+
+    /// This function returns the outcome of the match, or None if it has games left to play.
+    pub fn outcome(&self, id: usize) -> Option<Outcome> {
+        let game = self.matches.get(id)?;
+        if game.is_bye() {
+            return Some(Outcome::Won(0));
+        }
+        if game.games.len() < self.games_per_match {
+            return None;
+        }
+        let ([wins, other_wins], [total, other_total]) = (game.game_wins(), game.totals());
+        let outcome = match (wins.cmp(&other_wins), total.cmp(&other_total)) {
+            (Ordering::Greater, _) | (Ordering::Equal, Ordering::Greater) => Outcome::Won(0),
+            (Ordering::Less, _) | (Ordering::Equal, Ordering::Less) => Outcome::Won(1),
+            // Players are listed the better seed first
+            _ if self.format == Format::SingleElimination => Outcome::Won(0),
+            _ => Outcome::Drawn,
+        };
+        Some(outcome)
+    }
+
+    /// This function returns the winner of the match, or None if it is drawn or not finished.
+    pub fn winner(&self, id: usize) -> Option<&str> {
+        match self.outcome(id)? {
+            Outcome::Won(player) => Some(self.matches[id].players[player].as_str()),
+            Outcome::Drawn => None,
+        }
+    }
+
+    /// This function returns the matches with games left to play, by number.
+    pub fn pending(&self) -> Vec<usize> {
+        (0..self.matches.len())
+            .filter(|id| self.outcome(*id).is_none())
+            .collect()
+    }
+
+    /// This function returns true once every match has been played and, in a bracket, one player is left.
+    pub fn is_over(&self) -> bool {
+        match self.format {
+            Format::RoundRobin => self.pending().is_empty(),
+            Format::SingleElimination => self.champion().is_some(),
+        }
+    }
+
+    /// This function returns the winner of a bracket once the final is played, or the first place of a round robin once every match is played.
+    pub fn champion(&self) -> Option<&str> {
+        match self.format {
+            Format::RoundRobin if self.pending().is_empty() => {
+                let first = self.standings().rows.into_iter().next()?;
+                self.players
+                    .iter()
+                    .find(|p| **p == first.name)
+                    .map(|p| p.as_str())
+            }
+            Format::RoundRobin => None,
+            Format::SingleElimination => {
+                let round = self.round();
+                let last: Vec<usize> = (0..self.matches.len())
+                    .filter(|id| self.matches[*id].round == round)
+                    .collect();
+                match last.as_slice() {
+                    [id] => self.winner(*id),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// This function records a game of the match. Once the last match of a bracket round is over, the next round is paired.
+    /// It returns an error if there is no such match, it is a bye or all its games are played.
+    pub fn record_game(&mut self, id: usize, result: GameResult) -> Result<(), String> {
+        self.playable(id)?;
+        if result.winner.is_some_and(|w| w > 1) {
+            return Err("A game is won by one of the two players.".to_string());
+        }
+        self.matches[id].games.push(result);
+        self.next_round();
+        Ok(())
+    }
+
+    /// This function returns the match, or an error if there is no such match, it is a bye or all its games are played.
+    fn playable(&self, id: usize) -> Result<&Match, String> {
+        let game = self
+            .matches
+            .get(id)
+            .ok_or_else(|| format!("There is no match {}.", id.saturating_add(1)))?;
+        if game.is_bye() {
+            return Err(format!("Match {} is a bye.", id + 1));
+        }
+        if game.games.len() >= self.games_per_match {
+            return Err(format!("Match {} is over.", id + 1));
+        }
+        Ok(game)
+    }
+
+    /// This function pairs the winners of the last round of a bracket, in bracket order, once every match of it is over.
+    fn next_round(&mut self) {
+        let round = self.round();
+        if self.format != Format::SingleElimination || !self.pending().is_empty() {
+            return;
+        }
+        let winners: Vec<String> = (0..self.matches.len())
+            .filter(|id| self.matches[*id].round == round)
+            .filter_map(|id| self.winner(id).map(|w| w.to_string()))
+            .collect();
+        if winners.len() < 2 {
+            return;
+        }
+        for pair in winners.chunks(2) {
+            let mut players = pair.to_vec();
+            players.sort_by_key(|p| self.seed(p));
+            self.matches.push(Match {
+                round: round + 1,
+                players,
+                games: Vec::new(),
+            });
+        }
+        // A round made only of byes is over at once
+        self.next_round();
+    }
+
+    /// This function checks that the mode of the tournament is in the registry and can seat the two players of a match, e.g. before the tournament is written.
+    pub fn check_mode(&self, registry: &Registry) -> Result<(), String> {
+        let names: Vec<&str> = self.players.iter().take(2).map(|p| p.as_str()).collect();
+        registry
+            .start(
+                &self.mode,
+                SpotItDeck::default(),
+                &names,
+                &ModeOptions::default(),
+            )
+            .map(|_| ())
+    }
+
+    /// This function plays the next game of the match between two bots of the skill, with a deck shuffled by the seed, and records it.
+    /// The score of each player is the score the game gives them, and the winner is the one the game names, if only one.
+    /// It returns an error before any game is played if there is no such match, it is a bye or all its games are played.
+    pub fn play_game(
+        &mut self,
+        registry: &Registry,
+        id: usize,
+        skill: Skill,
+        seed: u64,
+    ) -> Result<GameResult, String> {
+        // The match is checked before the game is played, not only when it is recorded
+        let game = self.playable(id)?;
+        let names: Vec<&str> = game.players.iter().map(|p| p.as_str()).collect();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut deck = SpotItDeck::default();
+        deck.cards.shuffle(&mut rng);
+        let mut mode = registry.start(&self.mode, deck, &names, &ModeOptions::default())?;
+        let mut spotters: Vec<Box<dyn Spotter>> = names
+            .iter()
+            .map(|name| Box::new(Bot::new(name, skill, rng.gen())) as Box<dyn Spotter>)
+            .collect();
+        // A game still going after the most turns is stopped where it is
+        play_game(mode.as_mut(), &mut spotters, MAX_TURNS)?;
+        let players = &mode.table().players;
+        let scores = [players[0].score, players.get(1).map_or(0, |p| p.score)];
+        let result = match mode.winners().as_slice() {
+            [winner] => GameResult {
+                scores,
+                winner: Some(*winner),
+            },
+            _ => GameResult::from_scores(scores),
+        };
+        self.record_game(id, result)?;
+        Ok(result)
+    }
+
+    /// This function returns the standings. A round robin is ranked by league points, then games won less games lost, then score less the score of opponents, then score, then seed.
+    /// A bracket is ranked by the players still in first, then by the round reached, then as a round robin.
+    pub fn standings(&self) -> Standings {
+        let mut rows: Vec<Standing> = self
+            .players
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Standing {
+                name: name.clone(),
+                seed: i + 1,
+                alive: true,
+                ..Standing::default()
+            })
+            .collect();
+        for (id, game) in self.matches.iter().enumerate() {
+            let seats: Vec<usize> = game
+                .players
+                .iter()
+                .filter_map(|p| self.seed(p).map(|s| s - 1))
+                .collect();
+            for seat in seats.iter() {
+                rows[*seat].round = rows[*seat].round.max(game.round);
+            }
+            let wins = game.game_wins();
+            let totals = game.totals();
+            for (i, seat) in seats.iter().enumerate() {
+                let row = &mut rows[*seat];
+                row.games_won += wins[i];
+                row.games_lost += wins[1 - i];
+                row.score_for += totals[i];
+                row.score_against += totals[1 - i];
+            }
+            // Byes of a round robin are rounds off, and do not count
+            let Some(outcome) = self.outcome(id) else {
+                continue;
+            };
+            if game.is_bye() && self.format == Format::RoundRobin {
+                continue;
+            }
+            for (i, seat) in seats.iter().enumerate() {
+                let row = &mut rows[*seat];
+                row.played += 1;
+                match outcome {
+                    Outcome::Won(winner) if winner == i => row.won += 1,
+                    Outcome::Won(_) => {
+                        row.lost += 1;
+                        row.alive = self.format == Format::RoundRobin;
+                    }
+                    Outcome::Drawn => row.drawn += 1,
+                }
+            }
+        }
+        rows.sort_by(|a, b| {
+            let bracket = match self.format {
+                Format::RoundRobin => Ordering::Equal,
+                Format::SingleElimination => b.alive.cmp(&a.alive).then(b.round.cmp(&a.round)),
+            };
+            bracket
+                .then(b.points().cmp(&a.points()))
+                .then(b.game_difference().cmp(&a.game_difference()))
+                .then(b.score_difference().cmp(&a.score_difference()))
+                .then(b.score_for.cmp(&a.score_for))
+                .then(a.seed.cmp(&b.seed))
+        });
+        Standings {
+            format: self.format,
+            rows,
+        }
+    }
+
+    /// This function writes the tournament as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default() + "\n"
+    }
+
+    /// This function reads a tournament written by `to_json`, and maybe edited by hand since.
+    /// It returns an error if the text is not a tournament, was written by a later version of the format, or its matches do not fit its players and games. See `check`.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let value = read_versioned(text, "a tournament", TOURNAMENT_VERSION)?;
+        let tournament: Tournament =
+            serde_json::from_value(value).map_err(|e| format!("Not a valid tournament: {}", e))?;
+        tournament.check()?;
+        Ok(tournament)
+    }
+
+    /// This function checks that every player has a name, and that every match is between one or two players of the tournament, with no more games than a match has, each won by one of its players or drawn.
+    fn check(&self) -> Result<(), String> {
+        if self.players.iter().any(|p| p.trim().is_empty()) {
+            return Err("Every player needs a name.".to_string());
+        }
+        for (id, game) in self.matches.iter().enumerate() {
+            if game.players.is_empty() || game.players.len() > 2 {
+                return Err(format!(
+                    "Match {} is not between one or two players.",
+                    id + 1
+                ));
+            }
+            if let Some(stranger) = game.players.iter().find(|p| !self.players.contains(p)) {
+                return Err(format!("{} of match {} is not a player.", stranger, id + 1));
+            }
+            if game.games.len() > self.games_per_match {
+                return Err(format!(
+                    "Match {} has {} games, but a match has {}.",
+                    id + 1,
+                    game.games.len(),
+                    self.games_per_match
+                ));
+            }
+            if game.games.iter().any(|g| g.winner.is_some_and(|w| w > 1)) {
+                return Err(format!(
+                    "A game of match {} is won by neither of its players.",
+                    id + 1
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// This function reads a tournament from a file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::from_json(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// This function writes the tournament to a file, so that the old results are kept if writing is interrupted. See `save::write_atomically`.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        write_atomically(path, &self.to_json())
+    }
+}
+
+impl fmt::Display for Tournament {
+    /// This function lists the matches round by round, with their games and winners, then the standings.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {} of {}, {} game{} a match",
+            self.name,
+            self.format,
+            self.mode,
+            self.games_per_match,
+            if self.games_per_match == 1 { "" } else { "s" }
+        )?;
+        for (id, game) in self.matches.iter().enumerate() {
+            if id == 0 || self.matches[id - 1].round != game.round {
+                writeln!(f, "Round {}", game.round)?;
+            }
+            if game.is_bye() {
+                writeln!(f, "  {:>3}. {} has a bye", id + 1, game.players[0])?;
+                continue;
+            }
+            let games: Vec<String> = game
+                .games
+                .iter()
+                .map(|g| format!("{}-{}", g.scores[0], g.scores[1]))
+                .collect();
+            let result = match self.outcome(id) {
+                Some(Outcome::Won(winner)) => format!(", won by {}", game.players[winner]),
+                Some(Outcome::Drawn) => ", drawn".to_string(),
+                None => String::new(),
+            };
+            writeln!(
+                f,
+                "  {:>3}. {} v {}: {}{}",
+                id + 1,
+                game.players[0],
+                game.players[1],
+                match games.is_empty() {
+                    true => "to play".to_string(),
+                    false => games.join(", "),
+                },
+                result
+            )?;
+        }
+        if let Some(champion) = self.champion() {
+            writeln!(f, "Champion: {}", champion)?;
+        }
+        write!(f, "{}", self.standings())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn win(scores: [i32; 2]) -> GameResult {
+        GameResult::from_scores(scores)
+    }
+
+    #[test]
+    fn round_robins_pair_everyone_once() {
+        let players = ["Ann", "Bob", "Cat", "Dan", "Eve"];
+        let tournament =
+            Tournament::new("League", Format::RoundRobin, "classic", &players, 1).unwrap();
+        // 5 players play 5 rounds of 2 matches and a bye
+        assert_eq!(tournament.round(), 5);
+        let byes: Vec<&Match> = tournament.matches.iter().filter(|m| m.is_bye()).collect();
+        assert_eq!(byes.len(), 5);
+        for (i, a) in players.iter().enumerate() {
+            for b in players[i + 1..].iter() {
+                let meetings = tournament
+                    .matches
+                    .iter()
+                    .filter(|m| {
+                        m.players.contains(&a.to_string()) && m.players.contains(&b.to_string())
+                    })
+                    .count();
+                assert_eq!(meetings, 1, "{} v {}", a, b);
+            }
+        }
+        for round in 1..=5 {
+            let seated: usize = tournament
+                .matches
+                .iter()
+                .filter(|m| m.round == round)
+                .map(|m| m.players.len())
+                .sum();
+            assert_eq!(seated, 5);
+        }
+        assert!(Tournament::new("", Format::RoundRobin, "classic", &["Ann"], 1).is_err());
+        assert!(Tournament::new("", Format::RoundRobin, "classic", &["Ann", "Ann"], 1).is_err());
+        assert!(Tournament::new("", Format::RoundRobin, "classic", &["Ann", " "], 1).is_err());
+        assert!(Tournament::new("", Format::RoundRobin, "classic", &["Ann", "Bob"], 0).is_err());
+    }
+    #[test]
+    fn matches_aggregate_games_and_break_ties() {
+        let mut tournament =
+            Tournament::new("League", Format::RoundRobin, "classic", &["Ann", "Bob"], 3).unwrap();
+        tournament.record_game(0, win([10, 5])).unwrap();
+        tournament.record_game(0, win([4, 9])).unwrap();
+        assert_eq!(tournament.outcome(0), None);
+        tournament.record_game(0, win([7, 7])).unwrap();
+        // One game each and a draw, so the total score decides: 21 to 21 is a draw
+        assert_eq!(tournament.outcome(0), Some(Outcome::Drawn));
+        assert!(tournament.record_game(0, win([1, 0])).is_err());
+        assert!(tournament.record_game(5, win([1, 0])).is_err());
+
+        let mut tournament = Tournament::new(
+            "Cup",
+            Format::SingleElimination,
+            "classic",
+            &["Ann", "Bob"],
+            2,
+        )
+        .unwrap();
+        tournament.record_game(0, win([3, 5])).unwrap();
+        tournament.record_game(0, win([8, 6])).unwrap();
+        // One game each, 11 to 11: the better seed goes through
+        assert_eq!(tournament.winner(0), Some("Ann"));
+        assert_eq!(tournament.champion(), Some("Ann"));
+        assert!(tournament.is_over());
+    }
+    #[test]
+    fn brackets_seed_players_and_advance_winners() {
+        let players = ["S1", "S2", "S3", "S4", "S5", "S6"];
+        let mut tournament =
+            Tournament::new("Cup", Format::SingleElimination, "classic", &players, 1).unwrap();
+        let first: Vec<Vec<String>> = tournament
+            .matches
+            .iter()
+            .map(|m| m.players.clone())
+            .collect();
+        // 6 players fill a bracket of 8, and the top two seeds have byes
+        assert_eq!(
+            first,
+            [vec!["S1"], vec!["S4", "S5"], vec!["S2"], vec!["S3", "S6"]]
+        );
+        assert_eq!(tournament.pending(), [1, 3]);
+        tournament.record_game(1, win([2, 5])).unwrap();
+        assert_eq!(tournament.round(), 1);
+        tournament.record_game(3, win([5, 2])).unwrap();
+        assert_eq!(tournament.round(), 2);
+        assert_eq!(tournament.matches[4].players, ["S1", "S5"]);
+        assert_eq!(tournament.matches[5].players, ["S2", "S3"]);
+        tournament.record_game(4, win([5, 1])).unwrap();
+        tournament.record_game(5, win([0, 5])).unwrap();
+        assert_eq!(tournament.matches[6].players, ["S1", "S3"]);
+        assert_eq!(tournament.champion(), None);
+        tournament.record_game(6, win([3, 5])).unwrap();
+        assert_eq!(tournament.champion(), Some("S3"));
+
+        let standings = tournament.standings();
+        let names: Vec<&str> = standings.rows.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["S3", "S1", "S5", "S2", "S4", "S6"]);
+        assert!(standings.rows[0].alive);
+        assert!(!standings.rows[1].alive);
+        assert_eq!(standings.rows[0].won, 3);
+        assert_eq!(standings.rows[0].round, 3);
+    }
+    #[test]
+    fn standings_rank_by_points_then_tie_breaks() {
+        let mut tournament = Tournament::new(
+            "League",
+            Format::RoundRobin,
+            "classic",
+            &["Ann", "Bob", "Cat"],
+            1,
+        )
+        .unwrap();
+        for id in tournament.pending() {
+            let game = &tournament.matches[id];
+            let scores = match (game.players[0].as_str(), game.players[1].as_str()) {
+                ("Ann", "Bob") => [5, 3],
+                ("Bob", "Cat") => [9, 1],
+                (_, _) => [2, 6],
+            };
+            tournament.record_game(id, win(scores)).unwrap();
+        }
+        assert!(tournament.is_over());
+        let standings = tournament.standings();
+        let rows: Vec<(&str, u32, i32)> = standings
+            .rows
+            .iter()
+            .map(|r| (r.name.as_str(), r.points(), r.score_difference()))
+            .collect();
+        // Everyone won once, so the score difference decides
+        assert_eq!(rows, [("Bob", 3, 6), ("Ann", 3, -2), ("Cat", 3, -4)]);
+        assert_eq!(tournament.champion(), Some("Bob"));
+        let table = standings.to_string();
+        assert!(table.lines().next().unwrap().contains("Player"));
+        assert_eq!(table.lines().count(), 4);
+    }
+    #[test]
+    fn bots_play_matches_with_the_engine() {
+        let mut tournament = Tournament::new(
+            "Bots",
+            Format::SingleElimination,
+            "tower",
+            &["Ann", "Bob", "Cat"],
+            2,
+        )
+        .unwrap();
+        let registry = Registry::builtin();
+        let mut seed = 0;
+        while let Some(id) = tournament.pending().first().copied() {
+            seed += 1;
+            tournament
+                .play_game(&registry, id, Skill::Expert, seed)
+                .unwrap();
+        }
+        assert!(tournament.champion().is_some());
+        assert!(tournament
+            .matches
+            .iter()
+            .all(|m| m.is_bye() || m.games.len() == 2));
+        assert!(tournament
+            .play_game(&registry, 0, Skill::Expert, 0)
+            .is_err());
+        assert_eq!(tournament.check_mode(&registry), Ok(()));
+
+        let solo = Tournament::new("Solo", Format::RoundRobin, "solo", &["Ann", "Bob"], 1).unwrap();
+        assert!(solo.check_mode(&registry).is_err());
+    }
+    #[test]
+    fn tournaments_are_kept_in_a_file() {
+        let dir = std::env::temp_dir().join(format!("cardgame-tournament-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("league.json");
+        let mut tournament =
+            Tournament::new("League", Format::RoundRobin, "classic", &["Ann", "Bob"], 1).unwrap();
+        tournament.record_game(0, win([3, 1])).unwrap();
+        assert!(tournament.record_game(usize::MAX, win([3, 1])).is_err());
+        tournament.save(&path).unwrap();
+        assert_eq!(Tournament::load(&path), Ok(tournament));
+        assert!(Tournament::load(&dir.join("none.json")).is_err());
+
+        // Hand-edited files are checked
+        let json = std::fs::read_to_string(&path).unwrap();
+        for (from, to) in [
+            ("\"winner\": 0", "\"winner\": 5"),
+            ("\"games_per_match\": 1", "\"games_per_match\": 0"),
+            (
+                "\"players\": [\n        \"Ann\",\n        \"Bob\"\n      ]",
+                "\"players\": []",
+            ),
+            ("\"Bob\"\n      ]", "\"Dan\"\n      ]"),
+            ("\"Ann\"", "\" \""),
+        ] {
+            assert!(json.contains(from), "{}", from);
+            assert!(
+                Tournament::from_json(&json.replace(from, to)).is_err(),
+                "{}",
+                to
+            );
+        }
+
+        std::fs::write(&path, "{\"version\": 2}").unwrap();
+        assert!(Tournament::load(&path).unwrap_err().contains("version 2"));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!("Knockout".parse(), Ok(Format::SingleElimination));
+        assert_eq!("round_robin".parse(), Ok(Format::RoundRobin));
+        assert!("swiss".parse::<Format>().is_err());
+    }
+}
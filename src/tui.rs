@@ -0,0 +1,382 @@
+#![allow(missing_docs)]
+#![warn(rustdoc::private_doc_tests)]
+
+//! Full-screen terminal UI to play SpotIt! in `cardgame` crate.
+//!
+//! The UI plays "The Tower": the player's card and the centre card, i.e. the top of the draw pile, are shown side by side with coloured symbol labels.
+//! The player picks a symbol of their card with the arrow keys and claims it with Enter, or claims it at once with its number.
+//! A live timer, the scoreboard and a log of what happened are shown below the cards.
+//!
+//! A game left before the end can be saved, and resumed later from where it was.
+//!
+//! The UI only keeps state and draws frames, and is driven by key codes, so it can be tested by drawing to ratatui's `TestBackend`.
+//! The binary runs the event loop.
+//!
+//! [`Repository`]: https://github.com/anguschiu1/cardgame
+//! [`Ratatui`]: https://ratatui.rs/
+
+use crate::game::clock::Clock;
+use crate::game::tower::Tower;
+use crate::game::{Phase, Verdict};
+use crate::save::{SavedGame, SavedMode};
+use crate::spotitcard::{SpotItCard, SpotItSymbol};
+use crate::terminal::symbol_colour;
+use crate::SpotItDeck;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Frame;
+use std::time::Duration;
+
+/// This is the height of a card on screen: the 8 symbols of the default deck and the border.
+const CARD_HEIGHT: u16 = 10;
+/// This is the fewest lines of the event log shown.
+const LOG_LINES: u16 = 6;
+
+/// This struct defines the state of the UI around a game.
+#[derive(Debug)]
+pub struct App<C: Clock> {
+    game: Tower,
+    clock: C,
+    selected: usize,
+    shown_at: Duration,
+    log: Vec<String>,
+    quit: bool,
+}
+
+impl<C: Clock> App<C> {
+    /// This function deals a game of "The Tower" for the player, timed by the clock.
+    pub fn new(deck: SpotItDeck, name: &str, clock: C) -> Result<Self, String> {
+        let mut game = Tower::new(deck, &[name])?;
+        game.deal()?;
+        let shown_at = clock.now();
+        Ok(App {
+            game,
+            clock,
+            selected: 0,
+            shown_at,
+            log: vec!["Pick the symbol your card shares with the centre card.".to_string()],
+            quit: false,
+        })
+    }
+
+    /// This function goes on with a saved game of "The Tower", timed by the clock from now on.
+    pub fn resume(saved: SavedGame, clock: C) -> Result<Self, String> {
+        let SavedMode::Tower(game) = saved.game else {
+            return Err("Only games of The Tower can be played here.".to_string());
+        };
+        let shown_at = clock.now();
+        Ok(App {
+            game,
+            clock,
+            selected: 0,
+            shown_at,
+            log: vec!["Game resumed.".to_string()],
+            quit: false,
+        })
+    }
+
+    /// This function returns the game to save.
+    pub fn saved(&self) -> SavedGame {
+        SavedGame::new(SavedMode::Tower(self.game.clone()), Vec::new())
+    }
+
+    /// This function returns the game being played.
+    pub fn game(&self) -> &Tower {
+        &self.game
+    }
+
+    /// This function returns the event log, oldest first.
+    pub fn log(&self) -> &[String] {
+        &self.log
+    }
+
+    /// This function returns true once the player has asked to quit.
+    pub fn is_done(&self) -> bool {
+        self.quit
+    }
+
+    /// This function returns the symbols of the player's card, in the order they are listed.
+    fn own_symbols(&self) -> Vec<SpotItSymbol> {
+        self.game.table().players[0]
+            .top_card()
+            .map(|c| c.sorted_symbols())
+            .unwrap_or_default()
+    }
+
+    /// This function returns the symbol currently picked.
+    pub fn selected_symbol(&self) -> Option<SpotItSymbol> {
+        self.own_symbols().get(self.selected).copied()
+    }
+
+    /// This function returns the time the centre card has been shown for, which stops when the game ends.
+    pub fn elapsed(&self) -> Duration {
+        match self.game.phase() {
+            Phase::Playing => self.clock.now().saturating_sub(self.shown_at),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// This function reacts to a key: arrows or Tab move the selection, Enter or a number claims, and q or Esc quits.
+    pub fn handle_key(&mut self, key: KeyCode) {
+        let count = self.own_symbols().len().max(1);
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Left | KeyCode::Up | KeyCode::BackTab => {
+                self.selected = (self.selected + count - 1) % count
+            }
+            KeyCode::Right | KeyCode::Down | KeyCode::Tab => {
+                self.selected = (self.selected + 1) % count
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => self.claim(),
+            KeyCode::Char(c) => {
+                if let Some(number) = c.to_digit(10).filter(|n| (1..=count as u32).contains(n)) {
+                    self.selected = number as usize - 1;
+                    self.claim();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// This function claims the symbol picked, and logs the verdict.
+    fn claim(&mut self) {
+        let Some(symbol) = self.selected_symbol() else {
+            return;
+        };
+        let time = self.elapsed();
+        match self.game.claim(0, symbol) {
+            Ok(Verdict::Correct(_)) => {
+                self.log.push(format!(
+                    "Correct: {} in {:.1}s.",
+                    symbol,
+                    time.as_secs_f64()
+                ));
+                self.selected = 0;
+                self.shown_at = self.clock.now();
+                if self.game.phase() == Phase::Finished {
+                    self.log.push("Game over. Press q to quit.".to_string());
+                }
+            }
+            Ok(_) => self
+                .log
+                .push(format!("Wrong: {} is not on the centre card.", symbol)),
+            Err(message) => self.log.push(message),
+        }
+    }
+
+    /// This function draws the whole screen.
+    pub fn draw(&self, frame: &mut Frame) {
+        let [title, cards, bottom, help] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(CARD_HEIGHT),
+            Constraint::Min(LOG_LINES + 2),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        frame.render_widget(
+            Paragraph::new("SpotIt! The Tower").style(Style::new().add_modifier(Modifier::BOLD)),
+            title,
+        );
+
+        let [own, centre] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(cards);
+        let table = self.game.table();
+        self.draw_card(frame, own, "Your card", table.players[0].top_card(), true);
+        self.draw_card(frame, centre, "Centre", self.game.face_up_card(), false);
+
+        let [side, log] =
+            Layout::horizontal([Constraint::Length(30), Constraint::Min(20)]).areas(bottom);
+        let [timer, scores] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(3)]).areas(side);
+        frame.render_widget(
+            Paragraph::new(format!("{:.1}s", self.elapsed().as_secs_f64()))
+                .block(Block::new().borders(Borders::ALL).title("Timer")),
+            timer,
+        );
+        let mut lines = vec![Line::from(format!("Cards left: {}", table.draw_pile.len()))];
+        lines.extend(table.players.iter().map(|p| {
+            Line::from(format!(
+                "{}: {} points, {} cards",
+                p.name,
+                p.score,
+                p.pile.len()
+            ))
+        }));
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::new().borders(Borders::ALL).title("Scoreboard")),
+            scores,
+        );
+        let skip = self
+            .log
+            .len()
+            .saturating_sub(log.height.saturating_sub(2) as usize);
+        let lines: Vec<Line> = self.log[skip..]
+            .iter()
+            .map(|l| Line::from(l.as_str()))
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::new().borders(Borders::ALL).title("Log")),
+            log,
+        );
+        frame.render_widget(
+            Paragraph::new("←/→ pick  Enter claim  1-9 claim by number  q quit")
+                .style(Style::new().fg(Color::DarkGray)),
+            help,
+        );
+    }
+
+    /// This function draws a card as a list of coloured symbol labels, marking the pick on the player's card.
+    fn draw_card(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        title: &str,
+        card: Option<&SpotItCard>,
+        own: bool,
+    ) {
+        let lines: Vec<Line> = match card {
+            Some(card) => card
+                .sorted_symbols()
+                .iter()
+                .enumerate()
+                .map(|(i, symbol)| {
+                    let colour = Style::new().fg(Color::Indexed(symbol_colour(*symbol) as u8));
+                    let label = match own {
+                        true => format!("{} {}", i + 1, symbol),
+                        false => symbol.to_string(),
+                    };
+                    match own && i == self.selected {
+                        true => Line::from(vec![
+                            Span::raw("> "),
+                            Span::styled(label, colour.add_modifier(Modifier::REVERSED)),
+                        ]),
+                        false => Line::from(vec![Span::raw("  "), Span::styled(label, colour)]),
+                    }
+                })
+                .collect(),
+            None => vec![Line::from("  No card")],
+        };
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::new().borders(Borders::ALL).title(title)),
+            area,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::ManualClock;
+    use crate::game::common_symbol;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn screen<C: Clock>(app: &App<C>) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let mut text = String::new();
+        for y in 0..buffer.area.height {
+            for x in 0..buffer.area.width {
+                text.push_str(buffer[(x, y)].symbol());
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    fn answer_position<C: Clock>(app: &App<C>) -> usize {
+        let own = app.game().table().players[0].top_card().unwrap();
+        let symbol = common_symbol(own, app.game().face_up_card().unwrap()).unwrap();
+        own.sorted_symbols()
+            .iter()
+            .position(|s| *s == symbol)
+            .unwrap()
+    }
+
+    #[test]
+    fn draws_cards_timer_scoreboard_and_log() {
+        let clock = ManualClock::new();
+        let app = App::new(SpotItDeck::generate_by_prime(3).unwrap(), "Ann", &clock).unwrap();
+        let own = app.game().table().players[0].top_card().unwrap().clone();
+        let centre = app.game().face_up_card().unwrap().clone();
+        clock.advance(Duration::from_millis(2500));
+        let text = screen(&app);
+        assert!(text.contains("Your card"));
+        assert!(text.contains("Centre"));
+        for symbol in own
+            .sorted_symbols()
+            .iter()
+            .chain(centre.sorted_symbols().iter())
+        {
+            assert!(text.contains(&symbol.to_string()));
+        }
+        assert!(text.contains(&format!("> 1 {}", own.sorted_symbols()[0])));
+        assert!(text.contains("2.5s"));
+        assert!(text.contains("Ann: 0 points, 1 cards"));
+        assert!(text.contains("Cards left: 12"));
+        assert!(text.contains("Pick the symbol"));
+    }
+    #[test]
+    fn symbols_are_picked_and_claimed_with_keys() {
+        let clock = ManualClock::new();
+        let mut app = App::new(SpotItDeck::generate_by_prime(2).unwrap(), "Ann", &clock).unwrap();
+        let first = app.selected_symbol();
+        app.handle_key(KeyCode::Left);
+        app.handle_key(KeyCode::Right);
+        assert_eq!(app.selected_symbol(), first);
+
+        // A wrong pick is logged and changes nothing
+        let answer = answer_position(&app);
+        let wrong = (answer + 1) % 3;
+        for _ in 0..wrong {
+            app.handle_key(KeyCode::Right);
+        }
+        app.handle_key(KeyCode::Enter);
+        assert!(app.log().last().unwrap().starts_with("Wrong"));
+        assert_eq!(app.game().table().players[0].score, 0);
+
+        // Number keys claim at once
+        clock.advance(Duration::from_secs(1));
+        app.handle_key(KeyCode::Char(
+            char::from_digit(answer as u32 + 1, 10).unwrap(),
+        ));
+        assert!(app.log().last().unwrap().ends_with("in 1.0s."));
+        assert_eq!(app.game().table().players[0].score, 1);
+        assert_eq!(app.elapsed(), Duration::ZERO);
+
+        while app.game().phase() == Phase::Playing {
+            let answer = answer_position(&app);
+            app.handle_key(KeyCode::Char(
+                char::from_digit(answer as u32 + 1, 10).unwrap(),
+            ));
+        }
+        assert!(screen(&app).contains("Game over. Press q to quit."));
+        assert!(!app.is_done());
+        app.handle_key(KeyCode::Char('q'));
+        assert!(app.is_done());
+    }
+    #[test]
+    fn saved_game_is_resumed() {
+        let clock = ManualClock::new();
+        let mut app = App::new(SpotItDeck::generate_by_prime(2).unwrap(), "Ann", &clock).unwrap();
+        app.handle_key(KeyCode::Char(
+            char::from_digit(answer_position(&app) as u32 + 1, 10).unwrap(),
+        ));
+        let saved = SavedGame::from_json(&app.saved().to_json()).unwrap();
+        let resumed = App::resume(saved, &clock).unwrap();
+        assert_eq!(resumed.game(), app.game());
+        assert_eq!(resumed.log(), ["Game resumed."]);
+        assert!(screen(&resumed).contains("Ann: 1 points, 2 cards"));
+
+        let solo =
+            crate::game::solo::Solo::new(SpotItDeck::generate_by_prime(2).unwrap(), "Ann", &clock)
+                .unwrap();
+        let other = SavedGame::new(SavedMode::Solo(solo.state()), Vec::new());
+        assert!(App::resume(other, &clock).is_err());
+    }
+}
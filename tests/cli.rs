@@ -0,0 +1,114 @@
+use cardgame::game::{common_symbol, Game};
+use cardgame::{Deck, SpotItDeck};
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], script: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_cardgame"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(script.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// This function plays the classic game on the ordered deck and returns the right answers in order.
+fn classic_answers() -> Vec<String> {
+    let mut game = Game::new(SpotItDeck::default(), &["Player"]).unwrap();
+    game.deal().unwrap();
+    let mut answers = Vec::new();
+    while let Some((centre, draw)) = game.face_up_cards() {
+        let symbol = common_symbol(centre, draw).unwrap();
+        answers.push(symbol.to_string().to_lowercase());
+        game.claim(0, symbol).unwrap();
+    }
+    answers
+}
+
+#[test]
+fn plays_classic_game_from_script() {
+    let mut script = vec!["scores".to_string(), "zzz".to_string()];
+    script.extend(classic_answers());
+    let output = run(
+        &["--script", "--mode", "classic", "--order", "ordered"],
+        &(script.join("\n") + "\n"),
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Playing classic (ordered deck)."));
+    assert!(stdout.contains("Player: 0 points, 0 cards"));
+    assert!(stdout.contains("Unknown symbol: zzz."));
+    assert!(stdout.contains("Round 56"));
+    assert!(stdout.ends_with("Game over.\nPlayer: 56 points, 56 cards\n"));
+    // Plain style is used for scripts
+    assert!(!stdout.contains('\x1b'));
+    assert!(stdout.contains("+-----------------+"));
+}
+
+#[test]
+fn plays_solo_game_from_menus() {
+    let mut script = vec!["solo".to_string(), "1".to_string()];
+    script.extend(classic_answers());
+    let output = run(&["--script", "--name", "Ann"], &(script.join("\n") + "\n"));
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Choose a game mode:\n  1) classic\n  2) solo"));
+    assert!(stdout.contains("Playing solo (ordered deck)."));
+    assert!(stdout.contains("Ann: 56 points, 56 cards"));
+    assert!(stdout.contains("Matches: 56\nWrong answers: 0\n"));
+    assert!(stdout.ends_with("Accuracy: 100%\n"));
+}
+
+#[test]
+fn stops_at_end_of_script() {
+    let output = run(
+        &[
+            "--script", "--mode", "tower", "--order", "shuffled", "--seed", "7",
+        ],
+        "",
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.ends_with("Game stopped.\nPlayer: 0 points, 1 cards\n"));
+}
+
+#[test]
+fn rejects_unknown_options() {
+    let output = run(&["--mode", "chess"], "");
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Unknown game mode: chess."));
+    let output = run(&["--help"], "");
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("Usage: cardgame"));
+}
+
+#[test]
+fn plays_at_a_difficulty() {
+    let output = run(
+        &[
+            "--script",
+            "--mode",
+            "classic",
+            "--order",
+            "ordered",
+            "--difficulty",
+            "toddler",
+        ],
+        "quit\n",
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Playing classic (ordered toddler deck)."));
+    assert!(stdout.contains("Round 1"));
+}
@@ -0,0 +1,397 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cardgame"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+/// This function returns a fresh directory for the files of a test.
+fn temp_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("cardgame-{}-{}", std::process::id(), test));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn generates_decks_by_order_or_symbols_per_card() {
+    let output = run(&["generate", "--order", "2"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output).lines().count(), 7);
+    assert!(stdout(&output).starts_with("[Apple, Apricot, Bilberry]\n"));
+
+    let by_symbols = run(&["generate", "--symbols-per-card", "3", "--format", "csv"]);
+    assert_eq!(stdout(&by_symbols).lines().count(), 7);
+    assert!(stdout(&by_symbols).starts_with("Apple,Apricot,Bilberry\n"));
+
+    let shuffled = run(&["generate", "--seed", "42", "--format", "json"]);
+    assert_eq!(
+        stdout(&shuffled),
+        stdout(&run(&["generate", "--seed", "42", "--format", "json"]))
+    );
+    assert_ne!(
+        stdout(&shuffled),
+        stdout(&run(&["generate", "--format", "json"]))
+    );
+
+    let output = run(&["generate", "--order", "4"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "4 is not prime\n"
+    );
+    // Orders too large for the symbols are invalid decks, not crashes
+    for args in [
+        ["generate", "--order", "16"],
+        ["generate", "--symbols-per-card", "17"],
+        ["generate", "--order", "255"],
+    ] {
+        assert_eq!(run(&args).status.code(), Some(1), "{:?}", args);
+    }
+    for args in [
+        ["generate", "--order", "0"],
+        ["generate", "--symbols-per-card", "1"],
+    ] {
+        assert_eq!(run(&args).status.code(), Some(2), "{:?}", args);
+    }
+    assert_eq!(
+        run(&["generate", "--order", "2", "--symbols-per-card", "3"])
+            .status
+            .code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn generates_decks_by_difficulty() {
+    let output = run(&["generate", "--difficulty", "toddler"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output).lines().count(), 7);
+    let expert = run(&["generate", "--difficulty", "expert"]);
+    assert_eq!(stdout(&expert).lines().count(), 57);
+    assert!(stdout(&expert).starts_with("[Blackberry, Grape, Lemon, Lime, "));
+
+    let svg = temp_dir("difficulty");
+    let output = run(&[
+        "generate",
+        "--difficulty",
+        "expert",
+        "--format",
+        "svg",
+        "-o",
+        svg.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    let card = std::fs::read_to_string(svg.join("card-01.svg")).unwrap();
+    assert!(card.contains("rotate("));
+    assert_eq!(
+        run(&["generate", "--difficulty", "expert", "--order", "3"])
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(
+        run(&["generate", "--difficulty", "impossible"])
+            .status
+            .code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn validates_deck_files() {
+    let dir = temp_dir("validate");
+    let good = dir.join("good.json");
+    assert!(run(&[
+        "generate",
+        "--order",
+        "3",
+        "--format",
+        "json",
+        "-o",
+        good.to_str().unwrap()
+    ])
+    .status
+    .success());
+    let output = run(&["validate", good.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).ends_with("good.json: 13 cards, valid.\n"));
+
+    let bad = dir.join("bad.csv");
+    std::fs::write(&bad, "Apple,Banana\nApple,Banana\nFig\n").unwrap();
+    let output = run(&["validate", bad.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).starts_with("Cards 1 and 2 are the same.\n"));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .ends_with("bad.csv: 3 problems, not valid.\n"));
+
+    let unreadable = dir.join("unknown.txt");
+    std::fs::write(&unreadable, "[Apple, Dragon]\n").unwrap();
+    assert_eq!(
+        run(&["validate", unreadable.to_str().unwrap()])
+            .status
+            .code(),
+        Some(1)
+    );
+    assert_eq!(
+        run(&["validate", dir.join("missing.txt").to_str().unwrap()])
+            .status
+            .code(),
+        Some(3)
+    );
+}
+
+#[test]
+fn prints_stats_and_renders_decks() {
+    let output = run(&["stats"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Cards: 57\nSymbols: 57\nSymbols per card: 8\n"));
+    assert!(stdout(&output).ends_with("Projective plane of order 7\n"));
+
+    let output = run(&["render", "--order", "1", "--plain"]);
+    assert_eq!(
+        stdout(&output),
+        "Card 1\n+-----------------+\n| App Apr         |\n+-----------------+\n\
+         Card 2\n+-----------------+\n| App Avo         |\n+-----------------+\n\
+         Card 3\n+-----------------+\n| Apr Avo         |\n+-----------------+\n"
+    );
+}
+
+#[test]
+fn exports_decks_between_formats() {
+    let dir = temp_dir("export");
+    let text = dir.join("deck.txt");
+    std::fs::write(&text, "# two cards\n[Apple, Fig]\n[Fig, Yuzu]\n").unwrap();
+    let output = run(&["export", text.to_str().unwrap(), "--format", "csv"]);
+    assert_eq!(stdout(&output), "Apple,Fig\nFig,Yuzu\n");
+
+    let svg = dir.join("svg");
+    let output = run(&[
+        "export",
+        text.to_str().unwrap(),
+        "--format",
+        "svg",
+        "-o",
+        svg.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    let card = std::fs::read_to_string(svg.join("card-1.svg")).unwrap();
+    assert!(card.starts_with("<svg"));
+    assert!(svg.join("card-2.svg").exists());
+    assert_eq!(run(&["export", "--format", "svg"]).status.code(), Some(2));
+    assert_eq!(
+        run(&["export", text.to_str().unwrap(), "--order", "3"])
+            .status
+            .code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn simulates_games_between_bots() {
+    let args = [
+        "simulate",
+        "--mode",
+        "tower",
+        "--bots",
+        "beginner,expert",
+        "--games",
+        "50",
+        "--order",
+        "3",
+        "--seed",
+        "9",
+    ];
+    let output = run(&args);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.starts_with("Mode: tower\nGames: 50 (0 unfinished)\n"));
+    // A header and a line per seat
+    assert_eq!(
+        text.lines().skip_while(|l| !l.starts_with("Seat")).count(),
+        3
+    );
+    assert_eq!(text, stdout(&run(&args)));
+
+    let json = run(&["simulate", "--games", "5", "--order", "2", "--json"]);
+    assert!(stdout(&json).contains("\"win_rates\""));
+
+    assert_eq!(run(&["simulate", "--order", "4"]).status.code(), Some(1));
+    assert_eq!(
+        run(&["simulate", "--order", "16", "--games", "1"])
+            .status
+            .code(),
+        Some(1)
+    );
+    assert_eq!(
+        run(&["simulate", "--bots", "godlike"]).status.code(),
+        Some(2)
+    );
+    assert_eq!(run(&["simulate", "--mode", "chess"]).status.code(), Some(2));
+}
+
+#[test]
+fn lists_game_modes() {
+    let output = run(&["modes"]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert_eq!(text.lines().count(), 7);
+    assert!(text.lines().any(|l| l.starts_with("hot-potato ")));
+}
+
+#[test]
+fn runs_tournaments_kept_in_a_file() {
+    let dir = temp_dir("tournament");
+    let file = dir.join("league.json");
+    let file = file.to_str().unwrap();
+    let output = run(&[
+        "tournament",
+        "new",
+        file,
+        "--players",
+        "Ann,Bob,Cat",
+        "--games",
+        "2",
+        "--name",
+        "Office league",
+    ]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Office league: round-robin of classic, 2 games a match\n"));
+    assert!(stdout(&output).contains("Ann v Bob: to play"));
+    assert_eq!(
+        run(&["tournament", "new", file, "--players", "Dan,Eve"])
+            .status
+            .code(),
+        Some(2)
+    );
+
+    let number = stdout(&output)
+        .lines()
+        .find(|l| l.contains("Ann v Bob"))
+        .and_then(|l| l.trim().split('.').next())
+        .unwrap()
+        .to_string();
+    let output = run(&[
+        "tournament",
+        "record",
+        file,
+        "--match",
+        &number,
+        "--scores",
+        "12,-1",
+    ]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("Ann v Bob: 12--1"));
+    assert_eq!(
+        run(&[
+            "tournament",
+            "record",
+            file,
+            "--match",
+            "99",
+            "--scores",
+            "1,2"
+        ])
+        .status
+        .code(),
+        Some(2)
+    );
+    assert_eq!(
+        run(&[
+            "tournament",
+            "record",
+            file,
+            "--match",
+            &number,
+            "--scores",
+            "1"
+        ])
+        .status
+        .code(),
+        Some(2)
+    );
+    // Matches are numbered from 1
+    assert_eq!(
+        run(&[
+            "tournament",
+            "record",
+            file,
+            "--match",
+            "0",
+            "--scores",
+            "1,2"
+        ])
+        .status
+        .code(),
+        Some(2)
+    );
+
+    let output = run(&[
+        "tournament",
+        "play",
+        file,
+        "--skill",
+        "expert",
+        "--seed",
+        "3",
+    ]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("Champion: "));
+    assert!(!stdout(&output).contains("to play"));
+    assert_eq!(stdout(&run(&["tournament", "show", file])), stdout(&output));
+    assert!(stdout(&output).contains("  #  Player"));
+    assert_eq!(
+        run(&[
+            "tournament",
+            "show",
+            dir.join("none.json").to_str().unwrap()
+        ])
+        .status
+        .code(),
+        Some(3)
+    );
+    assert_eq!(
+        run(&[
+            "tournament",
+            "new",
+            dir.join("cup.json").to_str().unwrap(),
+            "--players",
+            "Ann,Bob",
+            "--mode",
+            "chess"
+        ])
+        .status
+        .code(),
+        Some(2)
+    );
+    let solo = dir.join("solo.json");
+    let output = run(&[
+        "tournament",
+        "new",
+        solo.to_str().unwrap(),
+        "--players",
+        "Ann,Bob",
+        "--mode",
+        "solo",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!solo.exists());
+    let unnamed = dir.join("unnamed.json");
+    let output = run(&[
+        "tournament",
+        "new",
+        unnamed.to_str().unwrap(),
+        "--players",
+        "Ann,",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!unnamed.exists());
+}